@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("No credentials stored as {0}")]
+    NotFound(String),
+    #[error("The {0} credential store is not available on this platform")]
+    Unsupported(String),
+    #[error("Unable to access the credential store\n ∟{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Credential store failed\n ∟{0}")]
+    Backend(String),
+}
+
+/// Where the credentials of the wallet are kept, a blob of bytes per target name.
+///
+/// The blobs are already encrypted or not secret on their own, like the salt,
+/// a store adds protection where the platform offers it.
+pub trait CredentialStore: Send + Sync {
+    /// Fails with [Error::NotFound] if nothing is stored as `target_name`
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error>;
+
+    /// Replaces the blob stored as `target_name`
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error>;
+
+    /// Fails with [Error::NotFound] if nothing is stored as `target_name`
+    fn delete(&self, target_name: &str) -> Result<(), Error>;
+}
@@ -1,4 +1,6 @@
 pub mod app_path;
+pub mod credential_store;
 pub mod icons_repository;
 pub mod network_gateway;
+pub mod price_source;
 pub mod wallet_data_repository;
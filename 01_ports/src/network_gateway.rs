@@ -1 +1,107 @@
-// pub trait Network
+use std::future::Future;
+
+use deps::{
+    radix_gateway_sdk::generated::model::{
+        StateEntityDetailsResponse, StateEntityFungiblesPageResponse,
+        StateEntityNonFungibleIdsPageResponse, StateEntityNonFungiblesPageResponse,
+        StateNonFungibleDataResponse, StreamTransactionsResponse, TransactionConstructionResponse,
+        TransactionPreviewResponse, TransactionStatusResponse, TransactionSubmitResponse,
+    },
+    reqwest,
+    scrypto::crypto::Ed25519PublicKey,
+    serde_json,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Request failed\n ∟{0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Gateway responded with status {status}\n ∟{message}")]
+    Status { status: u16, message: String },
+    #[error("No response available for {0}")]
+    NotFound(String),
+    #[error("Unable to parse response\n ∟{0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    /// Requests are not sent at all if the configured proxy can not be used
+    #[error("Unable to use the proxy\n ∟{0}")]
+    Proxy(String),
+}
+
+/// The requests the wallet makes to a Radix network gateway.
+///
+/// Paged requests take the cursor of the previous page and the state version to read the page at,
+/// following pages should be read at the state version of the first page to be consistent.
+/// Implementations are cheap to clone so they can be moved into spawned tasks.
+pub trait NetworkGateway: Clone + Send + Sync + 'static {
+    /// Details and metadata of the entities
+    fn entity_details(
+        &self,
+        addresses: &[&str],
+    ) -> impl Future<Output = Result<StateEntityDetailsResponse, Error>> + Send;
+
+    fn fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> impl Future<Output = Result<StateEntityFungiblesPageResponse, Error>> + Send;
+
+    fn non_fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> impl Future<Output = Result<StateEntityNonFungiblesPageResponse, Error>> + Send;
+
+    fn non_fungible_ids_page(
+        &self,
+        account_address: &str,
+        resource_address: &str,
+        vault_address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> impl Future<Output = Result<StateEntityNonFungibleIdsPageResponse, Error>> + Send;
+
+    fn non_fungible_data(
+        &self,
+        resource_address: &str,
+        non_fungible_ids: &[String],
+    ) -> impl Future<Output = Result<StateNonFungibleDataResponse, Error>> + Send;
+
+    /// Committed user transactions that affected the entity in ascending order,
+    /// starting at `from_state_version` (inclusive)
+    fn transactions_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        from_state_version: Option<i64>,
+        at_state_version: Option<i64>,
+    ) -> impl Future<Output = Result<StreamTransactionsResponse, Error>> + Send;
+
+    fn transaction_construction(
+        &self,
+    ) -> impl Future<Output = Result<TransactionConstructionResponse, Error>> + Send;
+
+    /// The signer public keys are needed to include the cost of verifying their signatures in the fee
+    fn preview_transaction(
+        &self,
+        manifest: &str,
+        start_epoch_inclusive: u64,
+        end_epoch_exclusive: u64,
+        message: Option<serde_json::Value>,
+        signer_public_keys: &[Ed25519PublicKey],
+    ) -> impl Future<Output = Result<TransactionPreviewResponse, Error>> + Send;
+
+    /// Takes the hex encoded compiled notarized transaction
+    fn submit_transaction(
+        &self,
+        notarized_transaction_hex: &str,
+    ) -> impl Future<Output = Result<TransactionSubmitResponse, Error>> + Send;
+
+    /// Takes the bech32 encoded intent hash of a submitted transaction
+    fn transaction_status(
+        &self,
+        intent_hash: &str,
+    ) -> impl Future<Output = Result<TransactionStatusResponse, Error>> + Send;
+}
@@ -0,0 +1,36 @@
+use std::{collections::HashMap, future::Future};
+
+use deps::{reqwest, scrypto::math::Decimal, serde_json};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Request failed\n ∟{0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Price source responded with status {0}")]
+    Status(u16),
+    #[error("Unable to read prices\n ∟{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Unable to parse prices\n ∟{0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    #[error("Invalid price \"{price}\" for {resource_address}")]
+    InvalidPrice {
+        resource_address: String,
+        price: String,
+    },
+    #[error("Prices are in {found}, expected {expected}")]
+    WrongCurrency { expected: String, found: String },
+}
+
+/// Where the fiat prices of resources come from.
+///
+/// Resources are passed as addresses and the currency as an ISO 4217 code like `USD`.
+/// Implementations are cheap to clone so they can be moved into spawned tasks.
+pub trait PriceSource: Clone + Send + Sync + 'static {
+    /// The price of one unit of each resource, resources the source has no price for are left out
+    fn prices(
+        &self,
+        resource_addresses: &[&str],
+        currency: &str,
+    ) -> impl Future<Output = Result<HashMap<String, Decimal>, Error>> + Send;
+}
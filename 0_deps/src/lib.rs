@@ -1,11 +1,14 @@
 pub use {
-    async_sqlite, asynciter, bincode, bip39, bytes, const_format, debug_print, ed25519_dalek_fiat,
-    fast_image_resize, flate2, futures, iced, image, lazy_static, no_mangle_if_debug, once_cell,
-    openssl_sys, radix_gateway_sdk, rand, regex, reqwest, ring, scrypto, serde, serde_json,
-    simple_logger, slip10_ed25519, tokio, zeroize,
+    argon2, async_sqlite, asynciter, bincode, bip39, bytes, const_format, debug_print,
+    ed25519_dalek_fiat, fast_image_resize, flate2, futures, iced, image, lazy_static,
+    no_mangle_if_debug, once_cell, openssl_sys, radix_gateway_sdk, radix_transactions, rand, regex,
+    reqwest, ring, scrypto, serde, serde_json, simple_logger, slip10_ed25519, tokio, zeroize,
 };
 
 pub use {hot_ice, hot_lib_reloader};
 
 #[cfg(windows)]
 pub use {winapi, windows};
+
+#[cfg(target_os = "linux")]
+pub use secret_service;
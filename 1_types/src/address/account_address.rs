@@ -22,6 +22,8 @@ static STOKENET_REGEX: Lazy<Regex> =
 pub enum AccountAddress {
     Mainnet([u8; Self::MAINNET_LENGTH]),
     Stokenet([u8; Self::STOKENET_LENGTH]),
+    /// An address on a custom network, holding the network id and the address
+    Custom(u8, Box<[u8]>),
 }
 
 impl Address for AccountAddress {
@@ -43,6 +45,7 @@ impl Address for AccountAddress {
         match self {
             Self::Mainnet(bytes) => bytes,
             Self::Stokenet(bytes) => bytes,
+            Self::Custom(_, bytes) => bytes,
         }
     }
 
@@ -50,6 +53,7 @@ impl Address for AccountAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_LENGTH,
         }
     }
 
@@ -57,6 +61,7 @@ impl Address for AccountAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_DOUBLE_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_DOUBLE_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_DOUBLE_LENGTH,
         }
     }
 
@@ -64,6 +69,7 @@ impl Address for AccountAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_ADDRESS_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_ADDRESS_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::ADDRESS_LENGTH,
         }
     }
 
@@ -75,8 +81,13 @@ impl Address for AccountAddress {
         match self {
             Self::Mainnet(_) => Network::Mainnet,
             Self::Stokenet(_) => Network::Stokenet,
+            Self::Custom(id, _) => Network::Custom(*id),
         }
     }
+
+    fn custom(id: u8, address: &str) -> Self {
+        Self::Custom(id, address.as_bytes().into())
+    }
 }
 
 #[cfg(test)]
@@ -85,6 +96,12 @@ impl AccountAddress {
         match network {
             Network::Mainnet => AccountAddress::Mainnet([0; Self::MAINNET_LENGTH]),
             Network::Stokenet => AccountAddress::Stokenet([0; Self::STOKENET_LENGTH]),
+            Network::Custom(id) => {
+                let length = Self::ADDRESS_TYPE_PREFIX_LENGTH
+                    + network.prefix().len()
+                    + Self::ADDRESS_LENGTH;
+                AccountAddress::Custom(id, vec![0; length].into_boxed_slice())
+            }
         }
     }
 }
@@ -128,7 +145,7 @@ impl TryFrom<&[u8]> for AccountAddress {
     type Error = AddressError;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         let s = std::str::from_utf8(value).map_err(|_| AddressError::InvalidUTF8)?;
-        AccountAddress::from_str(s)
+        AccountAddress::from_stored_str(s)
     }
 }
 
@@ -150,6 +167,9 @@ impl Serialize for AccountAddress {
                 "Stokenet",
                 bytes.as_slice(),
             ),
+            AccountAddress::Custom(_, bytes) => {
+                serializer.serialize_newtype_variant("AccountAddress", 2, "Custom", bytes.as_ref())
+            }
         }
     }
 }
@@ -14,21 +14,20 @@ pub static STOKENET_DECODER: Lazy<AddressBech32Decoder> =
 pub struct AddressValidator;
 
 impl AddressValidator {
-    pub fn is_valid_account(network: Network, address: &str) -> bool {
-        match network {
-            Network::Mainnet => {
-                let Ok((entity_type, _)) = MAINNET_DECODER.validate_and_decode(address) else {
-                    return false;
-                };
-                Self::is_entity_type_account(entity_type)
-            }
-            Network::Stokenet => {
-                let Ok((entity_type, _)) = STOKENET_DECODER.validate_and_decode(address) else {
-                    return false;
-                };
-                Self::is_entity_type_account(entity_type)
+    fn decode_entity_type(network: Network, address: &str) -> Option<EntityType> {
+        let decoded = match network {
+            Network::Mainnet => MAINNET_DECODER.validate_and_decode(address),
+            Network::Stokenet => STOKENET_DECODER.validate_and_decode(address),
+            Network::Custom(_) => {
+                AddressBech32Decoder::new(&network.definition()).validate_and_decode(address)
             }
-        }
+        };
+
+        decoded.ok().map(|(entity_type, _)| entity_type)
+    }
+
+    pub fn is_valid_account(network: Network, address: &str) -> bool {
+        Self::decode_entity_type(network, address).is_some_and(Self::is_entity_type_account)
     }
 
     fn is_entity_type_account(entity_type: EntityType) -> bool {
@@ -40,20 +39,7 @@ impl AddressValidator {
     }
 
     pub fn is_valid_identity(network: Network, address: &str) -> bool {
-        match network {
-            Network::Mainnet => {
-                let Ok((entity_type, _)) = MAINNET_DECODER.validate_and_decode(address) else {
-                    return false; 
-                };
-                Self::is_entity_type_identity(entity_type)
-            }
-            Network::Stokenet => {
-                let Ok((entity_type, _)) = STOKENET_DECODER.validate_and_decode(address) else {
-                    return false;
-                };
-                Self::is_entity_type_identity(entity_type)
-            }
-        }
+        Self::decode_entity_type(network, address).is_some_and(Self::is_entity_type_identity)
     }
 
     fn is_entity_type_identity(entity_type: EntityType) -> bool {
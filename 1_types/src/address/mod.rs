@@ -5,6 +5,7 @@ pub(crate) mod address_validator;
 pub(crate) mod resource_address;
 pub(crate) mod transaction_address;
 
+use scrypto::{address::AddressBech32Decoder, network::NetworkDefinition};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -92,6 +93,59 @@ pub trait Address: Sized + FromStr {
 
     fn network(&self) -> Network;
 
+    /// Wraps an address that has been validated for the custom network with the id
+    fn custom(id: u8, address: &str) -> Self;
+
+    /// Whether the bech32 checksum and prefix of the address match the network definition
+    fn is_valid_bech32(definition: &NetworkDefinition, address: &str) -> bool {
+        AddressBech32Decoder::new(definition)
+            .validate_and_decode(address)
+            .is_ok()
+    }
+
+    /// Checks the prefixes, length, characters and bech32 checksum of an address on a custom network
+    fn is_valid_custom_address(network: Network, address: &str) -> bool {
+        let valid_format = address
+            .strip_prefix(Self::ADDRESS_TYPE_PREFIX)
+            .and_then(|address| address.strip_prefix(network.prefix().as_ref()))
+            .is_some_and(|address| {
+                address.len() == Self::ADDRESS_LENGTH
+                    && address
+                        .bytes()
+                        .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+            });
+
+        valid_format && Self::is_valid_bech32(&network.definition(), address)
+    }
+
+    /// Parses an address that should be on `network`. Unlike `from_str`, which only knows
+    /// Mainnet and Stokenet, this also parses addresses on a custom network
+    fn from_str_on(network: Network, address: &str) -> Result<Self, AddressError> {
+        match network {
+            Network::Mainnet | Network::Stokenet => Self::from_str(address)
+                .ok()
+                .filter(|parsed| parsed.network() == network)
+                .ok_or(AddressError::InvalidAddress),
+            Network::Custom(id) => Self::is_valid_address(network, address)
+                .then(|| Self::custom(id, address))
+                .ok_or(AddressError::InvalidAddress),
+        }
+    }
+
+    /// Addresses read back from storage do not say which network they are on, so a custom
+    /// network address is matched against the registered custom networks
+    fn from_stored_str(address: &str) -> Result<Self, AddressError> {
+        if let Ok(parsed) = Self::from_str(address) {
+            return Ok(parsed);
+        }
+
+        Network::custom_networks()
+            .into_iter()
+            .map(|custom_network| Network::Custom(custom_network.id))
+            .find_map(|network| Self::from_str_on(network, address).ok())
+            .ok_or(AddressError::InvalidAddress)
+    }
+
     fn as_str(&self) -> &str {
         std::str::from_utf8(self.as_bytes())
             .unwrap_unreachable(debug_info!("Invalid UTF-8 in address"))
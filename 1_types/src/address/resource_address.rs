@@ -16,6 +16,9 @@ use super::{Address, AddressError, AddressType};
 pub const XRD: &[u8; ResourceAddress::MAINNET_LENGTH] =
     b"resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
 
+pub const STOKENET_XRD: &[u8; ResourceAddress::STOKENET_LENGTH] =
+    b"resource_tdx_2_1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxtfd2jc";
+
 static MAINNET_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(ResourceAddress::MAINNET_REGEX_PATTERN).unwrap());
 
@@ -26,6 +29,8 @@ static STOKENET_REGEX: Lazy<Regex> =
 pub enum ResourceAddress {
     Mainnet([u8; Self::MAINNET_LENGTH]),
     Stokenet([u8; Self::STOKENET_LENGTH]),
+    /// An address on a custom network, holding the network id and the address
+    Custom(u8, Box<[u8]>),
 }
 
 impl Address for ResourceAddress {
@@ -47,6 +52,7 @@ impl Address for ResourceAddress {
         match self {
             Self::Mainnet(bytes) => bytes,
             Self::Stokenet(bytes) => bytes,
+            Self::Custom(_, bytes) => bytes,
         }
     }
 
@@ -54,6 +60,7 @@ impl Address for ResourceAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_LENGTH,
         }
     }
 
@@ -61,6 +68,7 @@ impl Address for ResourceAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_DOUBLE_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_DOUBLE_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_DOUBLE_LENGTH,
         }
     }
 
@@ -68,6 +76,7 @@ impl Address for ResourceAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_ADDRESS_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_ADDRESS_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::ADDRESS_LENGTH,
         }
     }
 
@@ -75,6 +84,7 @@ impl Address for ResourceAddress {
         match network {
             Network::Mainnet => MAINNET_REGEX.is_match(address),
             Network::Stokenet => STOKENET_REGEX.is_match(address),
+            Network::Custom(_) => Self::is_valid_custom_address(network, address),
         }
     }
 
@@ -82,16 +92,38 @@ impl Address for ResourceAddress {
         match self {
             Self::Mainnet(_) => Network::Mainnet,
             Self::Stokenet(_) => Network::Stokenet,
+            Self::Custom(id, _) => Network::Custom(*id),
         }
     }
+
+    fn custom(id: u8, address: &str) -> Self {
+        Self::Custom(id, address.as_bytes().into())
+    }
 }
 
 impl ResourceAddress {
+    /// Returns true if this is the address of XRD on its network
+    pub fn is_xrd(&self) -> bool {
+        match self {
+            Self::Mainnet(bytes) => bytes == XRD,
+            Self::Stokenet(bytes) => bytes == STOKENET_XRD,
+            Self::Custom(id, bytes) => {
+                bytes.as_ref() == Network::Custom(*id).xrd_address().as_bytes()
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn empty(network: Network) -> Self {
         match network {
             Network::Mainnet => ResourceAddress::Mainnet([0; Self::MAINNET_LENGTH]),
             Network::Stokenet => ResourceAddress::Stokenet([0; Self::STOKENET_LENGTH]),
+            Network::Custom(id) => {
+                let length = Self::ADDRESS_TYPE_PREFIX_LENGTH
+                    + network.prefix().len()
+                    + Self::ADDRESS_LENGTH;
+                ResourceAddress::Custom(id, vec![0; length].into_boxed_slice())
+            }
         }
     }
 }
@@ -129,7 +161,7 @@ impl TryFrom<&[u8]> for ResourceAddress {
     type Error = AddressError;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         let s = std::str::from_utf8(value).map_err(|_| AddressError::InvalidUTF8)?;
-        ResourceAddress::from_str(s)
+        ResourceAddress::from_stored_str(s)
     }
 }
 
@@ -151,6 +183,9 @@ impl Serialize for ResourceAddress {
                 "Stokenet",
                 bytes.as_slice(),
             ),
+            ResourceAddress::Custom(_, bytes) => {
+                serializer.serialize_newtype_variant("ResourceAddress", 2, "Custom", bytes.as_ref())
+            }
         }
     }
 }
@@ -6,7 +6,9 @@ use crate::{debug_info, Network};
 use super::{Address, AddressError, AddressType};
 use async_sqlite::rusqlite;
 use once_cell::sync::Lazy;
+use radix_transactions::prelude::{TransactionHashBech32Decoder, TransactionIntentHash};
 use regex::Regex;
+use scrypto::network::NetworkDefinition;
 use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
 use std::str::FromStr;
@@ -21,6 +23,8 @@ static STOKENET_REGEX: Lazy<Regex> =
 pub enum TransactionAddress {
     Mainnet([u8; Self::MAINNET_LENGTH]),
     Stokenet([u8; Self::STOKENET_LENGTH]),
+    /// An address on a custom network, holding the network id and the address
+    Custom(u8, Box<[u8]>),
 }
 
 impl Address for TransactionAddress {
@@ -42,6 +46,7 @@ impl Address for TransactionAddress {
         match self {
             Self::Mainnet(bytes) => bytes,
             Self::Stokenet(bytes) => bytes,
+            Self::Custom(_, bytes) => bytes,
         }
     }
 
@@ -49,6 +54,7 @@ impl Address for TransactionAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_LENGTH,
         }
     }
 
@@ -56,6 +62,7 @@ impl Address for TransactionAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_CHECKSUM_DOUBLE_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_CHECKSUM_DOUBLE_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::CHECKSUM_DOUBLE_LENGTH,
         }
     }
 
@@ -63,6 +70,7 @@ impl Address for TransactionAddress {
         match self {
             Self::Mainnet(_) => Self::MAINNET_ADDRESS_START_INDEX,
             Self::Stokenet(_) => Self::STOKENET_ADDRESS_START_INDEX,
+            Self::Custom(_, bytes) => bytes.len() - Self::ADDRESS_LENGTH,
         }
     }
 
@@ -70,6 +78,7 @@ impl Address for TransactionAddress {
         match network {
             Network::Mainnet => MAINNET_REGEX.is_match(address),
             Network::Stokenet => STOKENET_REGEX.is_match(address),
+            Network::Custom(_) => Self::is_valid_custom_address(network, address),
         }
     }
 
@@ -77,8 +86,20 @@ impl Address for TransactionAddress {
         match self {
             Self::Mainnet(_) => Network::Mainnet,
             Self::Stokenet(_) => Network::Stokenet,
+            Self::Custom(id, _) => Network::Custom(*id),
         }
     }
+
+    fn custom(id: u8, address: &str) -> Self {
+        Self::Custom(id, address.as_bytes().into())
+    }
+
+    /// Transaction ids are encoded as transaction hashes rather than entity addresses
+    fn is_valid_bech32(definition: &NetworkDefinition, address: &str) -> bool {
+        TransactionHashBech32Decoder::new(definition)
+            .validate_and_decode::<TransactionIntentHash>(address)
+            .is_ok()
+    }
 }
 
 impl TransactionAddress {
@@ -87,6 +108,12 @@ impl TransactionAddress {
         match network {
             Network::Mainnet => TransactionAddress::Mainnet([0; Self::MAINNET_LENGTH]),
             Network::Stokenet => TransactionAddress::Stokenet([0; Self::STOKENET_LENGTH]),
+            Network::Custom(id) => {
+                let length = Self::ADDRESS_TYPE_PREFIX_LENGTH
+                    + network.prefix().len()
+                    + Self::ADDRESS_LENGTH;
+                TransactionAddress::Custom(id, vec![0; length].into_boxed_slice())
+            }
         }
     }
 }
@@ -124,7 +151,7 @@ impl TryFrom<&[u8]> for TransactionAddress {
     type Error = AddressError;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         let s = std::str::from_utf8(value).map_err(|_| AddressError::InvalidUTF8)?;
-        TransactionAddress::from_str(s)
+        TransactionAddress::from_stored_str(s)
     }
 }
 
@@ -146,6 +173,12 @@ impl Serialize for TransactionAddress {
                 "Stokenet",
                 bytes.as_slice(),
             ),
+            TransactionAddress::Custom(_, bytes) => serializer.serialize_newtype_variant(
+                "TransactionAddress",
+                2,
+                "Custom",
+                bytes.as_ref(),
+            ),
         }
     }
 }
@@ -0,0 +1,12 @@
+use deps::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::address::AccountAddress;
+
+/// A named recipient, kept in the database of the network of the address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBookEntry {
+    pub address: AccountAddress,
+    pub name: String,
+}
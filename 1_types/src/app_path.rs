@@ -1,6 +1,9 @@
 use deps::*;
 
-use std::path::{Path, PathBuf};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
 
 use once_cell::sync::Lazy;
 use thiserror::Error;
@@ -28,6 +31,7 @@ pub struct AppPathInner {
     mainnet_db_path: Box<Path>,
     stokenet_db_path: Box<Path>,
     icons_directory: Box<Path>,
+    exports_directory: Box<Path>,
     mainnet_icon_cache_path: Box<Path>,
     stokenet_icon_cache_path: Box<Path>,
 }
@@ -42,8 +46,11 @@ impl AppPathInner {
     const STORE_STOKENET_FILE_NAME: &'static str = "stokenet";
     const DB_EXTENSION: &'static str = "db";
     const ICONS_DIRECTORY: &'static str = "icons";
+    const EXPORTS_DIRECTORY: &'static str = "exports";
     const ICONCASHE_MAINNET_FILE_NAME: &'static str = "iconcash_mainnet";
     const ICONCASHE_STOKENET_FILE_NAME: &'static str = "iconcash_stokenet";
+    const CUSTOM_NETWORK_FILE_NAME_PREFIX: &'static str = "network_";
+    const ICONCASHE_FILE_NAME_PREFIX: &'static str = "iconcash_";
 
     pub fn new() -> Result<Self, AppPathError> {
         let app_directory = Self::get_application_root_directory()?;
@@ -69,6 +76,9 @@ impl AppPathInner {
         let mut icons_directory = app_directory.clone();
         icons_directory.push(Self::ICONS_DIRECTORY);
 
+        let mut exports_directory = app_directory.clone();
+        exports_directory.push(Self::EXPORTS_DIRECTORY);
+
         let mut mainnet_icon_cache_path = icons_directory.clone();
         mainnet_icon_cache_path.push(Self::ICONCASHE_MAINNET_FILE_NAME);
         mainnet_icon_cache_path.set_extension(Self::DB_EXTENSION);
@@ -85,6 +95,7 @@ impl AppPathInner {
             mainnet_db_path: mainnet_db_path.into_boxed_path(),
             stokenet_db_path: stokenet_db_path.into_boxed_path(),
             icons_directory: icons_directory.into_boxed_path(),
+            exports_directory: exports_directory.into_boxed_path(),
             mainnet_icon_cache_path: mainnet_icon_cache_path.into_boxed_path(),
             stokenet_icon_cache_path: stokenet_icon_cache_path.into_boxed_path(),
         })
@@ -103,6 +114,11 @@ impl AppPathInner {
                 .create(&self.icons_directory)
                 .map_err(|err| AppPathError::UnableToCreateDirectory(err))?;
         }
+        if !self.exports_directory.exists() {
+            std::fs::DirBuilder::new()
+                .create(&self.exports_directory)
+                .map_err(|err| AppPathError::UnableToCreateDirectory(err))?;
+        }
         if !self.config_directory.exists() {
             std::fs::DirBuilder::new()
                 .create(&self.config_directory)
@@ -132,6 +148,7 @@ impl AppPathInner {
         match network {
             Network::Mainnet => self.mainnet_db_path.to_path_buf(),
             Network::Stokenet => self.stokenet_db_path.to_path_buf(),
+            Network::Custom(id) => self.custom_network_db_path(id),
         }
     }
 
@@ -139,10 +156,15 @@ impl AppPathInner {
         self.icons_directory.to_path_buf()
     }
 
+    pub fn exports_directory(&self) -> PathBuf {
+        self.exports_directory.to_path_buf()
+    }
+
     pub fn icon_cache(&self, network: Network) -> PathBuf {
         match network {
             Network::Mainnet => self.mainnet_icon_cache_path.to_path_buf(),
             Network::Stokenet => self.stokenet_icon_cache_path.to_path_buf(),
+            Network::Custom(id) => self.custom_network_icon_cache_path(id),
         }
     }
 
@@ -162,10 +184,11 @@ impl AppPathInner {
         &self.db_directory
     }
 
-    pub fn db_path_ref(&self, network: Network) -> &Box<Path> {
+    pub fn db_path_ref(&self, network: Network) -> Cow<'_, Path> {
         match network {
-            Network::Mainnet => &self.mainnet_db_path,
-            Network::Stokenet => &self.stokenet_db_path,
+            Network::Mainnet => Cow::Borrowed(&self.mainnet_db_path),
+            Network::Stokenet => Cow::Borrowed(&self.stokenet_db_path),
+            Network::Custom(id) => Cow::Owned(self.custom_network_db_path(id)),
         }
     }
 
@@ -173,13 +196,34 @@ impl AppPathInner {
         &self.icons_directory
     }
 
-    pub fn icon_cache_ref(&self, network: Network) -> &Box<Path> {
+    pub fn icon_cache_ref(&self, network: Network) -> Cow<'_, Path> {
         match network {
-            Network::Mainnet => &self.mainnet_icon_cache_path,
-            Network::Stokenet => &self.stokenet_icon_cache_path,
+            Network::Mainnet => Cow::Borrowed(&self.mainnet_icon_cache_path),
+            Network::Stokenet => Cow::Borrowed(&self.stokenet_icon_cache_path),
+            Network::Custom(id) => Cow::Owned(self.custom_network_icon_cache_path(id)),
         }
     }
 
+    /// Custom networks get their own database named after the network id,
+    /// so the id of a network should not be reused for a different network
+    fn custom_network_db_path(&self, id: u8) -> PathBuf {
+        let mut db_path = self.db_directory.to_path_buf();
+        db_path.push(format!("{}{id}", Self::CUSTOM_NETWORK_FILE_NAME_PREFIX));
+        db_path.set_extension(Self::DB_EXTENSION);
+        db_path
+    }
+
+    fn custom_network_icon_cache_path(&self, id: u8) -> PathBuf {
+        let mut icon_cache_path = self.icons_directory.to_path_buf();
+        icon_cache_path.push(format!(
+            "{}{}{id}",
+            Self::ICONCASHE_FILE_NAME_PREFIX,
+            Self::CUSTOM_NETWORK_FILE_NAME_PREFIX
+        ));
+        icon_cache_path.set_extension(Self::DB_EXTENSION);
+        icon_cache_path
+    }
+
     #[cfg(unix)]
     pub fn get_application_root_directory() -> Result<PathBuf, AppPathError> {
         use std::io::ErrorKind;
@@ -4,7 +4,7 @@ use deps::*;
 
 use serde::{Deserialize, Serialize};
 
-use crate::address::{AccountAddress, ResourceAddress, XRD};
+use crate::address::{AccountAddress, ResourceAddress};
 
 use super::AssetId;
 
@@ -41,10 +41,8 @@ impl PartialOrd for FungibleAsset {
 
 impl Ord for FungibleAsset {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if let ResourceAddress::Mainnet(addr) = other.resource_address {
-            if &addr == XRD {
-                return Ordering::Less;
-            }
+        if other.resource_address.is_xrd() {
+            return Ordering::Less;
         }
         self.resource_address.cmp(&other.resource_address)
     }
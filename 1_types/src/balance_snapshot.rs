@@ -0,0 +1,241 @@
+use deps::*;
+
+use std::{collections::HashMap, str::FromStr};
+
+use scrypto::math::{CheckedAdd, CheckedMul, Decimal as RadixDecimal};
+
+use crate::{
+    PriceTable,
+    address::{AccountAddress, ResourceAddress},
+    assets::FungibleAsset,
+    collections::AccountUpdate,
+};
+
+/// The balance of a fungible in an account at a state version of the ledger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    pub account_address: AccountAddress,
+    pub resource_address: ResourceAddress,
+    pub state_version: i64,
+    /// Unix time in seconds of the sync that recorded the balance
+    pub timestamp: i64,
+    pub amount: String,
+}
+
+impl BalanceSnapshot {
+    /// Every balance the account holds after the update, `unchanged` are the stored balances
+    /// the update did not change. Removed fungibles are recorded with a zero balance.
+    /// Empty if the balances did not move past `previous_state_version`,
+    /// an incomplete update does not advance the state version of the account
+    pub fn from_account_update<'a>(
+        account_update: &AccountUpdate,
+        unchanged: impl IntoIterator<Item = &'a FungibleAsset>,
+        previous_state_version: Option<i64>,
+        timestamp: i64,
+    ) -> Vec<Self> {
+        let account = &account_update.account;
+        let Some(state_version) = account
+            .balances_last_updated
+            .filter(|state_version| Some(*state_version) > previous_state_version)
+        else {
+            return Vec::new();
+        };
+
+        let snapshot = |resource_address: &ResourceAddress, amount: &str| Self {
+            account_address: account.address.clone(),
+            resource_address: resource_address.clone(),
+            state_version,
+            timestamp,
+            amount: amount.to_string(),
+        };
+
+        account_update
+            .fungibles
+            .values()
+            .chain(unchanged)
+            .map(|fungible| snapshot(&fungible.resource_address, &fungible.amount))
+            .chain(
+                account_update
+                    .removed_fungibles
+                    .iter()
+                    .map(|resource_address| snapshot(resource_address, "0")),
+            )
+            .collect()
+    }
+}
+
+/// A point of a balance history, the balance or value from `timestamp` until the next point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryPoint {
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// The balance of the resource after each snapshot, the snapshots must be ordered
+/// by state version
+pub fn balance_history(
+    snapshots: &[BalanceSnapshot],
+    resource_address: &ResourceAddress,
+) -> Vec<HistoryPoint> {
+    snapshots
+        .iter()
+        .filter(|snapshot| &snapshot.resource_address == resource_address)
+        .filter_map(|snapshot| {
+            Some(HistoryPoint {
+                timestamp: snapshot.timestamp,
+                value: snapshot.amount.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// The value of the account after each snapshot, the snapshots must be ordered
+/// by state version. There are no historical prices, so every balance is valued
+/// at the current price and resources without a price are left out
+pub fn value_history(snapshots: &[BalanceSnapshot], prices: &PriceTable) -> Vec<HistoryPoint> {
+    let mut values = HashMap::new();
+    let mut history: Vec<HistoryPoint> = Vec::new();
+
+    for snapshot in snapshots {
+        let Some(price) = prices.prices.get(&snapshot.resource_address) else {
+            continue;
+        };
+        let Ok(amount) = RadixDecimal::from_str(&snapshot.amount) else {
+            continue;
+        };
+        // A value that does not fit in a decimal is left out, like a resource without a price
+        match amount.checked_mul(*price) {
+            Some(value) => values.insert(&snapshot.resource_address, value),
+            None => values.remove(&snapshot.resource_address),
+        };
+
+        let Some(total) = values
+            .values()
+            .try_fold(RadixDecimal::ZERO, |total, value| total.checked_add(*value))
+        else {
+            continue;
+        };
+        let point = HistoryPoint {
+            timestamp: snapshot.timestamp,
+            value: total.to_string().parse().unwrap_or_default(),
+        };
+
+        // Snapshots of the same sync make up a single point
+        match history.last_mut() {
+            Some(last) if last.timestamp == point.timestamp => *last = point,
+            _ => history.push(point),
+        }
+    }
+
+    history
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{Account, Currency};
+
+    use super::*;
+
+    const XRD: &str = "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
+    const OTHER: &str = "resource_rdx1t5rswpc8qurswpc8qurswpc8qurswpc8qurswpc8qurswpc820luy5";
+    const HELD: &str = "resource_rdx1t4h4396mukhpzdrr5sfvegjsxl8q7a34q2vkt4quxcxahna8fucuz4";
+    const ACCOUNT: &str = "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax";
+
+    fn account_update(state_version: Option<i64>) -> AccountUpdate {
+        let mut account = Account::default();
+        account.address = AccountAddress::from_str(ACCOUNT).unwrap();
+        account.balances_last_updated = state_version;
+
+        let xrd = ResourceAddress::from_str(XRD).unwrap();
+        let fungible = FungibleAsset::new(&account.address, "10".to_string(), xrd.clone());
+
+        AccountUpdate {
+            account,
+            fungibles: HashMap::from([(xrd, fungible)]),
+            non_fungibles: HashMap::new(),
+            transactions: Vec::new(),
+            removed_fungibles: BTreeSet::from([ResourceAddress::from_str(OTHER).unwrap()]),
+            removed_non_fungibles: BTreeSet::new(),
+            removed_nfids: HashMap::new(),
+        }
+    }
+
+    fn snapshot(resource_address: &str, timestamp: i64, amount: &str) -> BalanceSnapshot {
+        BalanceSnapshot {
+            account_address: AccountAddress::from_str(ACCOUNT).unwrap(),
+            resource_address: ResourceAddress::from_str(resource_address).unwrap(),
+            state_version: timestamp,
+            timestamp,
+            amount: amount.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_snapshots_of_account_update() {
+        let held = FungibleAsset::new(
+            &AccountAddress::from_str(ACCOUNT).unwrap(),
+            "3".to_string(),
+            ResourceAddress::from_str(HELD).unwrap(),
+        );
+        let mut snapshots =
+            BalanceSnapshot::from_account_update(&account_update(Some(5)), [&held], None, 9);
+        snapshots.sort_by(|a, b| a.amount.cmp(&b.amount));
+
+        assert_eq!(
+            snapshots,
+            vec![
+                snapshot(OTHER, 9, "0"),
+                snapshot(XRD, 9, "10"),
+                snapshot(HELD, 9, "3")
+            ]
+            .into_iter()
+            .map(|snapshot| BalanceSnapshot {
+                state_version: 5,
+                ..snapshot
+            })
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_no_snapshots_if_state_version_did_not_advance() {
+        let update = account_update(Some(5));
+
+        assert!(BalanceSnapshot::from_account_update(&update, [], Some(5), 9).is_empty());
+        assert!(
+            BalanceSnapshot::from_account_update(&account_update(None), [], None, 9).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_value_history_uses_latest_balance_of_each_resource() {
+        let mut prices = PriceTable::new(Currency::default());
+        prices.prices.insert(
+            ResourceAddress::from_str(XRD).unwrap(),
+            RadixDecimal::from_str("0.5").unwrap(),
+        );
+        prices.prices.insert(
+            ResourceAddress::from_str(OTHER).unwrap(),
+            RadixDecimal::from_str("2").unwrap(),
+        );
+
+        let snapshots = [
+            snapshot(XRD, 1, "10"),
+            snapshot(OTHER, 1, "1"),
+            snapshot(XRD, 2, "20"),
+            snapshot(OTHER, 3, "0"),
+        ];
+
+        let values = value_history(&snapshots, &prices)
+            .iter()
+            .map(|point| (point.timestamp, point.value))
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![(1, 7.0), (2, 12.0), (3, 10.0)]);
+
+        let balances = balance_history(&snapshots, &ResourceAddress::from_str(XRD).unwrap());
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[1].value, 20.0);
+    }
+}
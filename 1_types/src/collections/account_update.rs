@@ -1,9 +1,9 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::{
-    address::ResourceAddress,
+    address::{AccountAddress, ResourceAddress},
     assets::{FungibleAsset, NonFungibleAsset},
-    Account, Network, Resource,
+    Account, Network, Resource, Transaction,
 };
 
 #[derive(Debug, Clone)]
@@ -11,6 +11,20 @@ pub struct AccountUpdate {
     pub account: Account,
     pub fungibles: HashMap<ResourceAddress, FungibleAsset>,
     pub non_fungibles: HashMap<ResourceAddress, NonFungibleAsset>,
+    /// Transactions committed since `account.transactions_last_updated` before the update
+    pub transactions: Vec<Transaction>,
+    /// Assets the account held before the update and no longer holds
+    pub removed_fungibles: BTreeSet<ResourceAddress>,
+    pub removed_non_fungibles: BTreeSet<ResourceAddress>,
+    /// Ids that left non-fungibles the account still holds, the updated asset holds the rest
+    pub removed_nfids: HashMap<ResourceAddress, BTreeSet<String>>,
+}
+
+/// The assets stored for an account, the update is compared against them to find removed assets
+#[derive(Debug, Clone, Default)]
+pub struct AccountAssets {
+    pub fungibles: BTreeSet<FungibleAsset>,
+    pub non_fungibles: BTreeSet<NonFungibleAsset>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +33,33 @@ pub struct AccountsUpdate {
     pub account_updates: Vec<AccountUpdate>,
     pub new_resources: HashMap<ResourceAddress, Resource>,
     pub icon_urls: BTreeMap<ResourceAddress, String>,
+    /// Requests that failed, the update only holds what was received. Accounts with
+    /// a failed request keep their previous state version so the rest is fetched again
+    pub failures: Vec<UpdateFailure>,
+}
+
+/// A gateway request that failed during an update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateFailure {
+    /// `None` if the request was not made for a specific account
+    pub account: Option<AccountAddress>,
+    /// What was requested, e.g. "fungible balances"
+    pub request: &'static str,
+    pub error: String,
+}
+
+impl UpdateFailure {
+    pub fn new(
+        account: Option<AccountAddress>,
+        request: &'static str,
+        error: impl ToString,
+    ) -> Self {
+        Self {
+            account,
+            request,
+            error: error.to_string(),
+        }
+    }
 }
 
 impl AccountsUpdate {
@@ -28,6 +69,28 @@ impl AccountsUpdate {
             account_updates: Vec::new(),
             new_resources: HashMap::new(),
             icon_urls: BTreeMap::new(),
+            failures: Vec::new(),
         }
     }
+
+    /// `true` if a request failed for every account, e.g. because the gateway could not be reached
+    pub fn every_account_failed(&self) -> bool {
+        !self.failures.is_empty()
+            && self.account_updates.iter().all(|account_update| {
+                self.failures.iter().any(|failure| {
+                    failure.account.as_ref() == Some(&account_update.account.address)
+                })
+            })
+    }
+}
+
+/// Sent each time the update of an account has finished
+#[derive(Debug, Clone)]
+pub struct UpdateProgress {
+    pub account: AccountAddress,
+    /// Accounts finished so far, including this one
+    pub completed: usize,
+    pub total: usize,
+    /// The requests that failed for this account
+    pub failures: Vec<UpdateFailure>,
 }
@@ -0,0 +1,76 @@
+use deps::*;
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// Where the salt and the encrypted recovery phrase are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialBackend {
+    /// Files in the config directory
+    File,
+    /// The freedesktop Secret Service over D-Bus, e.g. gnome-keyring or KWallet, Linux only
+    SecretService,
+    /// The Windows Credential Manager
+    CredentialManager,
+}
+
+impl CredentialBackend {
+    pub const ALL: [Self; 3] = [Self::File, Self::SecretService, Self::CredentialManager];
+
+    /// If the backend can be used on this platform
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::File => true,
+            Self::SecretService => cfg!(target_os = "linux"),
+            Self::CredentialManager => cfg!(windows),
+        }
+    }
+
+    /// The backends that can be used on this platform
+    pub fn supported() -> impl Iterator<Item = Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|backend| backend.is_supported())
+    }
+}
+
+impl Default for CredentialBackend {
+    /// Where wallets have kept their credentials before the backend could be chosen
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self::CredentialManager
+        } else {
+            Self::File
+        }
+    }
+}
+
+impl Display for CredentialBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File => write!(f, "File"),
+            Self::SecretService => write!(f, "Secret Service"),
+            Self::CredentialManager => write!(f, "Credential Manager"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_backend_is_supported() {
+        assert!(CredentialBackend::default().is_supported());
+        assert!(CredentialBackend::supported().any(|backend| backend == CredentialBackend::File));
+    }
+
+    #[test]
+    fn test_backend_from_settings() {
+        let backend: CredentialBackend = serde_json::from_str(r#""secret_service""#).unwrap();
+
+        assert_eq!(backend, CredentialBackend::SecretService);
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt::Debug;
 
 use bip39::{Mnemonic, Seed};
 use ed25519_dalek_fiat::{PublicKey, SecretKey};
+use radix_transactions::signing::Ed25519PrivateKey;
 use scrypto::{
     address::AddressBech32Encoder, crypto::Ed25519PublicKey,
     types::ComponentAddress,
@@ -79,6 +80,13 @@ impl Ed25519KeyPair {
         Ed25519PublicKey(self.public_key.to_bytes().to_owned())
     }
 
+    /// Creates a signer usable by the radix transaction builder, the returned key should be dropped as soon as signing is done
+    pub fn radixdlt_private_key(&self) -> Ed25519PrivateKey {
+        //The secret key is always 32 bytes, so this can not fail
+        Ed25519PrivateKey::from_bytes(self.secret_key.as_bytes())
+            .unwrap_unreachable(debug_info!("Invalid secret key length"))
+    }
+
     pub fn bech32_address(&self) -> String {
         let network_definition= self.network.definition(); 
 
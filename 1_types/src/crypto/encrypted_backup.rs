@@ -0,0 +1,147 @@
+use deps::*;
+
+use std::num::NonZeroU32;
+
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::{CryptoError, KdfParams, Key, KeySaltPair, KeyType, Password, Salt};
+
+/// A document encrypted with a password of its own, as written to a backup file.
+///
+/// The format and version are authenticated with the document,
+/// so a file can not be passed off as another version of the format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBackup {
+    format: String,
+    version: u16,
+    salt: Salt,
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedBackup {
+    pub const FORMAT: &'static str = "mercurium-wallet-backup";
+    /// The version written by this build, files with a newer version are rejected
+    pub const VERSION: u16 = 1;
+
+    /// Slow, the key is derived with the parameters of [EncryptedBackup::KDF]
+    pub fn seal(plaintext: &[u8], password: &Password) -> Result<Self, CryptoError> {
+        let (key, salt) = KeySaltPair::<Self>::new(password.as_str())?.into_inner();
+
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| CryptoError::FailedToCreateRandomValue)?;
+
+        let mut ciphertext = plaintext.to_vec();
+        Self::aead_key(&key)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Self::aad(Self::VERSION),
+                &mut ciphertext,
+            )
+            .map_err(|_| CryptoError::Encryption)?;
+
+        Ok(Self {
+            format: Self::FORMAT.to_string(),
+            version: Self::VERSION,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Fails with [CryptoError::Decryption] if the password is wrong or the file has been altered
+    pub fn open(&self, password: &Password) -> Result<Vec<u8>, CryptoError> {
+        if self.format != Self::FORMAT {
+            return Err(CryptoError::UnknownFormat(self.format.clone()));
+        }
+        if self.version > Self::VERSION {
+            return Err(CryptoError::UnsupportedVersion(self.version));
+        }
+
+        let key = Key::<Self>::new(password.as_str(), &self.salt);
+        let mut plaintext = self.ciphertext.clone();
+        let opened = Self::aead_key(&key)?
+            .open_in_place(
+                Nonce::assume_unique_for_key(self.nonce),
+                Self::aad(self.version),
+                &mut plaintext,
+            )
+            .map(|opened| opened.len());
+
+        match opened {
+            Ok(len) => plaintext.truncate(len),
+            Err(_) => {
+                plaintext.zeroize();
+                return Err(CryptoError::Decryption);
+            }
+        }
+
+        Ok(plaintext)
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    fn aead_key(key: &Key<Self>) -> Result<LessSafeKey, CryptoError> {
+        UnboundKey::new(&AES_256_GCM, key.as_bytes())
+            .map(LessSafeKey::new)
+            .map_err(|_| CryptoError::Encryption)
+    }
+
+    fn aad(version: u16) -> Aad<Vec<u8>> {
+        let mut aad = Self::FORMAT.as_bytes().to_vec();
+        aad.extend_from_slice(&version.to_be_bytes());
+        Aad::from(aad)
+    }
+}
+
+impl KeyType for EncryptedBackup {
+    const KEY_LENGTH: usize = 32;
+    /// Backups have always stored their key derivation parameters, this is never used
+    const ITERATIONS: NonZeroU32 = NonZeroU32::new(2000000).unwrap();
+    /// A backup file can be copied anywhere, so it is as expensive to attack as the mnemonic
+    const KDF: KdfParams = KdfParams::Argon2id {
+        memory_kib: 128 * 1024,
+        iterations: 4,
+        parallelism: 1,
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open_backup() {
+        let password = Password::from("backup password");
+        let backup = EncryptedBackup::seal(b"wallet document", &password).unwrap();
+
+        assert_ne!(backup.ciphertext[..15], b"wallet document"[..]);
+        assert_eq!(backup.open(&password).unwrap(), b"wallet document");
+    }
+
+    #[test]
+    fn test_open_backup_with_wrong_password_or_altered_version_fails() {
+        let password = Password::from("backup password");
+        let mut backup = EncryptedBackup::seal(b"wallet document", &password).unwrap();
+
+        assert!(matches!(
+            backup.open(&Password::from("wrong password")),
+            Err(CryptoError::Decryption)
+        ));
+
+        backup.version = 0;
+        assert!(matches!(
+            backup.open(&password),
+            Err(CryptoError::Decryption)
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use deps::*;
 use core::str;
 use std::num::NonZeroU32;
 
-use super::{Key, KeySaltPair, KeyType, Password, Salt};
+use super::{KdfParams, Key, KeySaltPair, KeyType, Password, Salt};
 use bip39::Mnemonic;
 use ring::aead::{
     Aad, BoundKey, Nonce, NonceSequence, OpeningKey, UnboundKey, AES_256_GCM, NONCE_LEN,
@@ -147,6 +147,11 @@ impl EncryptedMnemonic {
         })
     }
 
+    /// The parameters the encryption key is derived with
+    pub fn kdf(&self) -> &KdfParams {
+        self.salt.kdf()
+    }
+
     pub fn decrypt_mnemonic(
         &self,
         password: &Password,
@@ -195,6 +200,12 @@ impl EncryptedMnemonic {
 impl KeyType for EncryptedMnemonic {
     const KEY_LENGTH: usize = 32;
     const ITERATIONS: std::num::NonZeroU32 = NonZeroU32::new(2000000).unwrap();
+    /// Derived rarely, so it is more expensive than the database key
+    const KDF: KdfParams = KdfParams::Argon2id {
+        memory_kib: 128 * 1024,
+        iterations: 4,
+        parallelism: 1,
+    };
 }
 
 #[cfg(test)]
@@ -244,4 +255,27 @@ mod test {
         assert_eq!(mnemonic.phrase(), decrypted_mnemonic.phrase());
         assert_eq!(seed_password, decrypted_password.as_str());
     }
+
+    #[test]
+    fn test_decrypting_mnemonic_with_legacy_salt() {
+        let mnemonic = Mnemonic::new(bip39::MnemonicType::Words24, bip39::Language::English);
+        let password = Password::from("password99");
+        let legacy_salt = [3u8; Salt::LENGTH];
+        let key_and_salt = KeySaltPair::from_salt(password.as_str(), Salt::from(legacy_salt));
+        let encrypted_mnemonic =
+            EncryptedMnemonic::new_with_key_and_salt(&mnemonic, "", key_and_salt)
+                .unwrap_or_else(|err| panic!("{err}"));
+
+        // Stored before the key derivation parameters were recorded
+        let mut json = serde_json::to_value(&encrypted_mnemonic).unwrap();
+        json["salt"] = serde_json::to_value(legacy_salt).unwrap();
+        let legacy: EncryptedMnemonic = serde_json::from_value(json).unwrap();
+
+        let (decrypted_mnemonic, _) = legacy
+            .decrypt_mnemonic(&password)
+            .unwrap_or_else(|err| panic!("{err}"));
+
+        assert_eq!(legacy.kdf(), &KdfParams::Pbkdf2);
+        assert_eq!(mnemonic.phrase(), decrypted_mnemonic.phrase());
+    }
 }
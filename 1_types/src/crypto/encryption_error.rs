@@ -8,4 +8,18 @@ use thiserror::Error;
 pub enum CryptoError {
     #[error("Failed to create random value")]
     FailedToCreateRandomValue,
+    #[error("Invalid key derivation parameters: {0}")]
+    InvalidKdfParams(String),
+    #[error("Failed to derive key: {0}")]
+    KeyDerivation(String),
+    #[error("Invalid salt: {0}")]
+    InvalidSalt(String),
+    #[error("Failed to encrypt data")]
+    Encryption,
+    #[error("Failed to decrypt, the password is wrong or the data has been altered")]
+    Decryption,
+    #[error("Unknown format: {0}")]
+    UnknownFormat(String),
+    #[error("Version {0} is not supported, it was written by a newer version of the wallet")]
+    UnsupportedVersion(u16),
 }
\ No newline at end of file
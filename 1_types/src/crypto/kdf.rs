@@ -0,0 +1,149 @@
+use deps::*;
+
+use std::num::NonZeroU32;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use ring::pbkdf2::{self, PBKDF2_HMAC_SHA256};
+use serde::{Deserialize, Serialize};
+
+use super::encryption_error::CryptoError;
+
+/// How a key is derived from a password and a salt. The parameters are stored with the salt,
+/// so keys can still be derived after the defaults change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum KdfParams {
+    /// PBKDF2-HMAC-SHA256 with the iteration count of the key type,
+    /// salts stored before the parameters were recorded use this
+    Pbkdf2,
+    Argon2id {
+        /// Memory cost in KiB
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+}
+
+impl KdfParams {
+    /// Parameters read from disk above these are rejected, so a tampered salt can not make
+    /// the wallet allocate unbounded memory or spend unbounded time deriving a key
+    const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+    const MAX_ITERATIONS: u32 = 64;
+    const MAX_PARALLELISM: u32 = 16;
+
+    /// Checks that the Argon2id costs are within the limits above and accepted by Argon2,
+    /// [KdfParams::Pbkdf2] is always valid as its iteration count comes from the key type
+    pub fn validate(&self) -> Result<(), CryptoError> {
+        match *self {
+            Self::Pbkdf2 => Ok(()),
+            Self::Argon2id { memory_kib, .. } if memory_kib > Self::MAX_MEMORY_KIB => Err(
+                CryptoError::InvalidKdfParams(format!("memory cost of {memory_kib} KiB")),
+            ),
+            Self::Argon2id { iterations, .. } if iterations > Self::MAX_ITERATIONS => Err(
+                CryptoError::InvalidKdfParams(format!("{iterations} iterations")),
+            ),
+            Self::Argon2id { parallelism, .. } if parallelism > Self::MAX_PARALLELISM => Err(
+                CryptoError::InvalidKdfParams(format!("parallelism of {parallelism}")),
+            ),
+            Self::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => Params::new(memory_kib, iterations, parallelism, None)
+                .map(|_| ())
+                .map_err(|err| CryptoError::InvalidKdfParams(err.to_string())),
+        }
+    }
+
+    /// Fills `output` with the key derived from `source` and `salt`,
+    /// [KdfParams::Pbkdf2] runs `pbkdf2_iterations` iterations
+    pub(crate) fn derive(
+        &self,
+        source: &[u8],
+        salt: &[u8],
+        pbkdf2_iterations: NonZeroU32,
+        output: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        match *self {
+            Self::Pbkdf2 => {
+                pbkdf2::derive(PBKDF2_HMAC_SHA256, pbkdf2_iterations, salt, source, output);
+                Ok(())
+            }
+            Self::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                let params = Params::new(memory_kib, iterations, parallelism, Some(output.len()))
+                    .map_err(|err| CryptoError::InvalidKdfParams(err.to_string()))?;
+
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(source, salt, output)
+                    .map_err(|err| CryptoError::KeyDerivation(err.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_PARAMS: KdfParams = KdfParams::Argon2id {
+        memory_kib: 1024,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn derive(params: KdfParams, source: &str, salt: &[u8]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        params
+            .derive(source.as_bytes(), salt, NonZeroU32::MIN, &mut key)
+            .unwrap();
+        key
+    }
+
+    #[test]
+    fn test_argon2id_is_deterministic_and_salted() {
+        let key = derive(TEST_PARAMS, "password", &[1; 32]);
+
+        assert_eq!(key, derive(TEST_PARAMS, "password", &[1; 32]));
+        assert_ne!(key, derive(TEST_PARAMS, "password", &[2; 32]));
+        assert_ne!(key, derive(KdfParams::Pbkdf2, "password", &[1; 32]));
+    }
+
+    #[test]
+    fn test_validate_rejects_excessive_costs() {
+        let memory = KdfParams::Argon2id {
+            memory_kib: u32::MAX,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let iterations = KdfParams::Argon2id {
+            memory_kib: 1024,
+            iterations: u32::MAX,
+            parallelism: 1,
+        };
+        let parallelism = KdfParams::Argon2id {
+            memory_kib: 1024 * 1024,
+            iterations: 1,
+            parallelism: 1024,
+        };
+
+        assert!(memory.validate().is_err());
+        assert!(iterations.validate().is_err());
+        assert!(parallelism.validate().is_err());
+        assert!(TEST_PARAMS.validate().is_ok());
+    }
+
+    #[test]
+    fn test_params_are_tagged_with_the_algorithm() {
+        let json = serde_json::to_value(TEST_PARAMS).unwrap();
+
+        assert_eq!(json["algorithm"], "argon2id");
+        assert_eq!(
+            serde_json::from_value::<KdfParams>(json).unwrap(),
+            TEST_PARAMS
+        );
+    }
+}
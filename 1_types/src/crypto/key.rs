@@ -1,14 +1,18 @@
 use deps::*;
 
-use super::{salt::Salt, KeySaltPair};
-use ring::{aead::NonceSequence, pbkdf2::{self, PBKDF2_HMAC_SHA256}};
+use super::{kdf::KdfParams, salt::Salt, KeySaltPair};
+use crate::{debug_info, unwrap_unreachable::UnwrapUnreachable};
+use ring::aead::NonceSequence;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, marker::PhantomData, num::NonZeroU32};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 pub trait KeyType {
     const KEY_LENGTH: usize;
+    /// PBKDF2 iterations of keys derived from salts stored without key derivation parameters
     const ITERATIONS: NonZeroU32;
+    /// The parameters new keys are derived with
+    const KDF: KdfParams;
 }
 
 
@@ -26,17 +30,15 @@ impl<T: KeyType> Key<T> {
     // pub const LENGTH: usize = 32;
 
 
+    /// Derives the key with the parameters stored in the salt, the parameters of a salt are
+    /// validated when it is read so the derivation can not fail
     pub fn new(source: &str, salt: &Salt) -> Self {
         // Change to generic array length through the KeyType trait when the functionality is stable
         let mut key = [0u8;KEY_LENGTH];
 
-        pbkdf2::derive(
-            PBKDF2_HMAC_SHA256,
-            T::ITERATIONS,
-            salt.as_bytes(),
-            source.as_bytes(),
-            &mut key,
-        );
+        salt.kdf()
+            .derive(source.as_bytes(), salt.as_bytes(), T::ITERATIONS, &mut key)
+            .unwrap_unreachable(debug_info!("Invalid key derivation parameters"));
 
         Self{inner: key, _marker: PhantomData}
     }
@@ -19,8 +19,9 @@ impl<T> KeySaltPair<T>
     where
         T: KeyType,
 {
+    /// Derives the key from a new salt with the current parameters of the key type
     pub fn new(source: &str) -> Result<Self, CryptoError> {
-        let salt = Salt::new()?;
+        let salt = Salt::new(T::KDF)?;
         let key = Key::new(source, &salt);
         Ok(Self { key, salt})
     }
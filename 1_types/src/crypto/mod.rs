@@ -1,7 +1,9 @@
 
 pub(crate) mod ed25519;
+pub(crate) mod encrypted_backup;
 pub(crate) mod encrypted_mnemonic;
 pub(crate) mod encryption_error;
+pub(crate) mod kdf;
 pub(crate) mod key;
 pub(crate) mod password;
 pub(crate) mod public_key;
@@ -15,8 +17,10 @@ pub(crate) mod key_salt_pair;
 pub use bip32_entity::Bip32Entity;
 pub use bip32_key_kind::Bip32KeyKind;
 pub use ed25519::Ed25519KeyPair;
+pub use encrypted_backup::EncryptedBackup;
 pub use encrypted_mnemonic::{EncryptedMnemonic, EncryptedMnemonicError};
 pub use encryption_error::CryptoError;
+pub use kdf::KdfParams;
 pub use key::{Key, KeyType};
 pub use password::{HashedPassword, Password, PasswordError};
 pub use public_key::PublicKey;
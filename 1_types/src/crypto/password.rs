@@ -140,7 +140,7 @@ impl From<String> for Password {
     }
 }
 
-#[derive(PartialEq, Eq, ZeroizeOnDrop)]
+#[derive(Clone, PartialEq, Eq, ZeroizeOnDrop)]
 pub struct HashedPassword([u8; Self::LENGTH]);
 
 impl HashedPassword {
@@ -1,6 +1,6 @@
 use deps::*;
 
-use super::encryption_error::CryptoError;
+use super::{encryption_error::CryptoError, kdf::KdfParams};
 use once_cell::sync::OnceCell;
 use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
@@ -9,48 +9,116 @@ use zeroize::ZeroizeOnDrop;
 
 static SYSTEM_RANDOM: OnceCell<SystemRandom> = OnceCell::new();
 
+/// A random salt and the parameters keys are derived from it with
 #[cfg_attr(debug_assertions, derive(PartialEq, Eq))]
 #[derive(Clone, ZeroizeOnDrop, Serialize, Deserialize)]
-pub struct Salt([u8; Self::LENGTH]);
+#[serde(try_from = "StoredSalt", into = "StoredSalt")]
+pub struct Salt {
+    bytes: [u8; Self::LENGTH],
+    #[zeroize(skip)]
+    kdf: KdfParams,
+}
 
 impl Salt {
 
     pub const LENGTH: usize = 32;
+    /// Version of the stored salt, salts stored before it was recorded have no version
+    pub const VERSION: u8 = 1;
 
-    pub fn new() -> Result<Self, CryptoError> {
+    pub fn new(kdf: KdfParams) -> Result<Self, CryptoError> {
         let mut salt = [0u8; Self::LENGTH];
         // make into a static
         SystemRandom::new()
             .fill(&mut salt)
             .map_err(|_| CryptoError::FailedToCreateRandomValue)?;
-        Ok(Self(salt))
+        Ok(Self { bytes: salt, kdf })
     }
 
     pub fn as_bytes(&self) -> &[u8] {
-        &self.0
+        &self.bytes
+    }
+
+    pub fn kdf(&self) -> &KdfParams {
+        &self.kdf
     }
 
     pub fn to_inner(self) -> [u8; Self::LENGTH] {
-        self.0
+        self.bytes
+    }
+
+    /// The salt with a header recording the key derivation parameters, as it is stored
+    pub fn to_stored_bytes(&self) -> Result<Vec<u8>, CryptoError> {
+        serde_json::to_vec(self).map_err(|err| CryptoError::InvalidSalt(err.to_string()))
+    }
+
+    /// Reads a stored salt, a salt stored before the header was added is only the salt bytes
+    /// and derives keys with PBKDF2
+    pub fn from_stored_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if let Ok(bytes) = <[u8; Self::LENGTH]>::try_from(bytes) {
+            return Ok(Self::from(bytes));
+        }
+        serde_json::from_slice(bytes).map_err(|err| CryptoError::InvalidSalt(err.to_string()))
     }
 }
 
 impl Default for Salt {
     fn default() -> Self {
-        Self([0u8; Self::LENGTH])
+        Self::from([0u8; Self::LENGTH])
     }
 }
 
+/// The bytes of a salt stored before the key derivation parameters were recorded
 impl From<[u8; Salt::LENGTH]> for Salt {
     fn from(value: [u8; Salt::LENGTH]) -> Self {
-        Self(value)
+        Self {
+            bytes: value,
+            kdf: KdfParams::Pbkdf2,
+        }
     }
 }
 
 impl TryFrom<Vec<u8>> for Salt {
     type Error = TryFromSliceError;
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        Ok(Self(value.as_slice().try_into()?))
+        Ok(Self::from(<[u8; Self::LENGTH]>::try_from(value.as_slice())?))
+    }
+}
+
+/// The serialized [Salt], salts serialized before the header was added are a bare array
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredSalt {
+    Versioned {
+        version: u8,
+        kdf: KdfParams,
+        salt: [u8; Salt::LENGTH],
+    },
+    Legacy([u8; Salt::LENGTH]),
+}
+
+impl TryFrom<StoredSalt> for Salt {
+    type Error = CryptoError;
+    fn try_from(value: StoredSalt) -> Result<Self, Self::Error> {
+        match value {
+            StoredSalt::Versioned { version, .. } if version > Salt::VERSION => Err(
+                CryptoError::InvalidSalt(format!("unsupported version {version}")),
+            ),
+            StoredSalt::Versioned { kdf, salt, .. } => {
+                kdf.validate()?;
+                Ok(Self { bytes: salt, kdf })
+            }
+            StoredSalt::Legacy(salt) => Ok(Self::from(salt)),
+        }
+    }
+}
+
+impl From<Salt> for StoredSalt {
+    fn from(value: Salt) -> Self {
+        Self::Versioned {
+            version: Salt::VERSION,
+            kdf: value.kdf,
+            salt: value.bytes,
+        }
     }
 }
 
@@ -58,4 +126,32 @@ impl Debug for Salt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Salt(*)")
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_legacy_salt_derives_keys_with_pbkdf2() {
+        let bytes = [7u8; Salt::LENGTH];
+        let from_blob = Salt::from_stored_bytes(&bytes).unwrap();
+        let from_json: Salt = serde_json::from_slice(&serde_json::to_vec(&bytes).unwrap()).unwrap();
+
+        assert_eq!(from_blob.kdf(), &KdfParams::Pbkdf2);
+        assert!(from_blob == from_json);
+    }
+
+    #[test]
+    fn test_stored_salt_keeps_kdf_params() {
+        let kdf = KdfParams::Argon2id {
+            memory_kib: 1024,
+            iterations: 2,
+            parallelism: 1,
+        };
+        let salt = Salt::new(kdf).unwrap();
+        let stored = Salt::from_stored_bytes(&salt.to_stored_bytes().unwrap()).unwrap();
+
+        assert_eq!(stored.kdf(), &kdf);
+        assert_eq!(stored.as_bytes(), salt.as_bytes());
+    }
+}
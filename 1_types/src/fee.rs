@@ -0,0 +1,88 @@
+use deps::*;
+
+use scrypto::math::Decimal as RadixDecimal;
+
+use crate::address::AccountAddress;
+
+/// The network fee of a transfer, locked from the payer account at the start of the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fee {
+    pub payer: AccountAddress,
+    /// The total fee reported by the transaction preview
+    pub estimate: Option<RadixDecimal>,
+    /// Added on top of the estimate to cover any difference between the preview and the execution
+    pub margin_percent: u8,
+}
+
+impl Fee {
+    /// The amount of XRD locked when no estimate is available
+    pub const DEFAULT_LOCK_AMOUNT: u64 = 5;
+    pub const DEFAULT_MARGIN_PERCENT: u8 = 20;
+
+    pub fn new(payer: AccountAddress) -> Self {
+        Self {
+            payer,
+            estimate: None,
+            margin_percent: Self::DEFAULT_MARGIN_PERCENT,
+        }
+    }
+
+    /// The estimate with the safety margin added, `None` if the transfer has not been previewed
+    pub fn estimate_with_margin(&self) -> Option<RadixDecimal> {
+        self.estimate
+            .filter(|estimate| *estimate > RadixDecimal::ZERO)
+            .map(|estimate| {
+                estimate * RadixDecimal::from(100 + self.margin_percent as u64)
+                    / RadixDecimal::from(100)
+            })
+    }
+
+    /// The amount of XRD to lock for the fee, falls back to the default amount without an estimate
+    pub fn lock_amount(&self) -> RadixDecimal {
+        self.estimate_with_margin()
+            .unwrap_or(RadixDecimal::from(Self::DEFAULT_LOCK_AMOUNT))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn payer() -> AccountAddress {
+        AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lock_amount_defaults_without_estimate() {
+        let fee = Fee::new(payer());
+
+        assert_eq!(fee.estimate_with_margin(), None);
+        assert_eq!(
+            fee.lock_amount(),
+            RadixDecimal::from(Fee::DEFAULT_LOCK_AMOUNT)
+        );
+    }
+
+    #[test]
+    fn test_lock_amount_adds_margin_to_estimate() {
+        let mut fee = Fee::new(payer());
+        fee.estimate = Some(RadixDecimal::from_str("0.5").unwrap());
+        fee.margin_percent = 20;
+
+        assert_eq!(fee.lock_amount(), RadixDecimal::from_str("0.6").unwrap());
+
+        fee.margin_percent = 0;
+        assert_eq!(fee.lock_amount(), RadixDecimal::from_str("0.5").unwrap());
+
+        fee.estimate = Some(RadixDecimal::ZERO);
+        assert_eq!(
+            fee.lock_amount(),
+            RadixDecimal::from(Fee::DEFAULT_LOCK_AMOUNT)
+        );
+    }
+}
@@ -4,37 +4,55 @@
 use deps::*;
 
 pub(crate) mod account;
+pub(crate) mod address_book;
 pub(crate) mod app_error;
 pub(crate) mod app_path;
 pub(crate) mod app_settings;
+pub(crate) mod balance_snapshot;
+pub(crate) mod credential_backend;
 pub(crate) mod decimal;
+pub(crate) mod fee;
 // pub(crate) mod metadata;
 pub(crate) mod account_summary;
 pub mod consts;
 pub(crate) mod network;
 pub(crate) mod notification;
+pub(crate) mod price;
+pub(crate) mod proxy;
+pub(crate) mod request_limits;
 pub(crate) mod resource;
 pub(crate) mod services;
 pub(crate) mod task_response;
 pub(crate) mod theme;
 pub(crate) mod transaction;
+pub(crate) mod transaction_preview;
+pub(crate) mod transfer;
 pub(crate) mod unsafe_reference;
 pub(crate) mod unwrap_unreachable;
 
-pub use account::Account;
+pub use account::{AcceptDeposits, Account, DepositRules, Settings as AccountSettings};
+pub use address_book::*;
 pub use app_error::*;
 pub use app_path::*;
 pub use app_settings::*;
+pub use balance_snapshot::*;
+pub use credential_backend::*;
 pub use decimal::*;
+pub use fee::*;
 // pub use metadata::*;
 pub use account_summary::*;
 pub use network::*;
 pub use notification::*;
+pub use price::*;
+pub use proxy::*;
+pub use request_limits::*;
 pub use resource::*;
 pub use services::*;
 pub use task_response::*;
 pub use theme::*;
 pub use transaction::*;
+pub use transaction_preview::*;
+pub use transfer::*;
 pub use unsafe_reference::*;
 pub use unwrap_unreachable::*;
 
@@ -44,6 +62,8 @@ pub mod assets;
 pub mod collections;
 pub mod crypto;
 pub mod response_models;
+#[cfg(any(test, feature = "proptest"))]
+pub mod strategies;
 
 // Re exporting
 pub use scrypto::crypto::Ed25519PublicKey;
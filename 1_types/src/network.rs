@@ -1,35 +1,95 @@
 use deps::*;
 
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    sync::{PoisonError, RwLock},
+};
+
 use async_sqlite::rusqlite;
-use scrypto::prelude::NetworkDefinition;
+use once_cell::sync::Lazy;
+use scrypto::{address::AddressBech32Encoder, prelude::NetworkDefinition};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::crypto::derivation_path_indexes::{BIP32_NETWORK_ID_MAINNET, BIP32_NETWORK_ID_STOKENET};
 
+/// The custom networks and gateway urls from the settings, registered at startup so networks can
+/// be resolved from their id wherever a `Network` is used
+static NETWORK_REGISTRY: Lazy<RwLock<NetworkRegistry>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default)]
+struct NetworkRegistry {
+    custom_networks: BTreeMap<u8, CustomNetwork>,
+    gateway_urls: BTreeMap<Network, String>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NetworkError {
+    #[error("Network id {0} is reserved")]
+    ReservedId(u8),
+    #[error("A network with id {0} already exists")]
+    DuplicateId(u8),
+    #[error("The network name can not be empty")]
+    EmptyName,
+    #[error("Invalid address prefix \"{0}\", use lowercase letters, digits and underscores")]
+    InvalidHrpSuffix(String),
+    #[error("Invalid gateway url \"{0}\"")]
+    InvalidGatewayUrl(String),
+}
 
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Network {
     #[default]
     Mainnet,
     Stokenet,
+    /// A user defined network, identified by its network id
+    Custom(u8),
+}
+
+/// A user defined network such as a localnet, with its own address prefixes and gateway
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomNetwork {
+    pub id: u8,
+    pub name: String,
+    /// The network part of the address prefixes, `loc` for addresses like `account_loc1...`
+    pub hrp_suffix: String,
+    pub gateway_url: String,
+    /// Base url of a dashboard for the network, if there is one
+    #[serde(default)]
+    pub dashboard_url: Option<String>,
+}
+
+/// Replaces the default gateway of a network with a self-hosted one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GatewayEndpoint {
+    pub network: Network,
+    pub url: String,
 }
 
 impl Network {
     pub const MAINNET_PREFIX: &'static str = "rdx1";
     pub const STOKENET_PREFIX: &'static str = "tdx_2_1";
+    pub const MAINNET_GATEWAY_URL: &'static str = "https://mainnet.radixdlt.com";
+    pub const STOKENET_GATEWAY_URL: &'static str = "https://stokenet.radixdlt.com";
 
     pub fn from_id(id: u32) -> Result<Self, u32> {
         match id {
             BIP32_NETWORK_ID_MAINNET => Ok(Self::Mainnet),
             BIP32_NETWORK_ID_STOKENET => Ok(Self::Stokenet),
-            _ => Err(id),
+            _ => u8::try_from(id)
+                .ok()
+                .filter(|id| Self::custom_network(*id).is_some())
+                .map(Self::Custom)
+                .ok_or(id),
         }
     }
 
-    pub fn prefix(&self) -> &'static str {
+    pub fn prefix(&self) -> Cow<'static, str> {
         match self {
-            Self::Mainnet => Self::MAINNET_PREFIX,
-            Self::Stokenet => Self::STOKENET_PREFIX,
+            Self::Mainnet => Cow::Borrowed(Self::MAINNET_PREFIX),
+            Self::Stokenet => Cow::Borrowed(Self::STOKENET_PREFIX),
+            Self::Custom(id) => Cow::Owned(Self::custom_or_unknown(*id).prefix()),
         }
     }
 
@@ -37,23 +97,172 @@ impl Network {
         match self {
             Self::Mainnet => scrypto::network::NetworkDefinition::mainnet(),
             Self::Stokenet => scrypto::network::NetworkDefinition::stokenet(),
+            Self::Custom(id) => Self::custom_or_unknown(*id).definition(),
+        }
+    }
+
+    /// Base url of the Radix dashboard, transactions are found at
+    /// `{dashboard_url}/transaction/{address}`. Custom networks only have a dashboard if one
+    /// has been configured
+    pub fn dashboard_url(&self) -> Option<Cow<'static, str>> {
+        match self {
+            Self::Mainnet => Some(Cow::Borrowed("https://dashboard.radixdlt.com")),
+            Self::Stokenet => Some(Cow::Borrowed("https://stokenet-dashboard.radixdlt.com")),
+            Self::Custom(id) => Self::custom_or_unknown(*id).dashboard_url.map(Cow::Owned),
         }
     }
 
+    /// Base url of the gateway, a configured gateway url takes precedence over the default one
+    pub fn gateway_url(&self) -> String {
+        let configured = NETWORK_REGISTRY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .gateway_urls
+            .get(self)
+            .cloned();
+
+        configured.unwrap_or_else(|| match self {
+            Self::Mainnet => Self::MAINNET_GATEWAY_URL.to_string(),
+            Self::Stokenet => Self::STOKENET_GATEWAY_URL.to_string(),
+            Self::Custom(id) => Self::custom_or_unknown(*id).gateway_url,
+        })
+    }
+
+    /// The address of XRD on this network
+    pub fn xrd_address(&self) -> String {
+        AddressBech32Encoder::new(&self.definition())
+            .encode(scrypto::prelude::XRD.as_ref())
+            .unwrap_or_default()
+    }
+
     pub fn id(&self) -> u32 {
         match self {
             Self::Mainnet => BIP32_NETWORK_ID_MAINNET,
             Self::Stokenet => BIP32_NETWORK_ID_STOKENET,
+            Self::Custom(id) => *id as u32,
         }
     }
-}
 
-impl Into<radix_gateway_sdk::Network> for Network {
-    fn into(self) -> radix_gateway_sdk::Network {
+    pub fn name(&self) -> String {
         match self {
-            Network::Mainnet => radix_gateway_sdk::Network::Mainnet,
-            Network::Stokenet => radix_gateway_sdk::Network::Stokenet,
+            Self::Mainnet => "Mainnet".to_string(),
+            Self::Stokenet => "Stokenet".to_string(),
+            Self::Custom(id) => Self::custom_or_unknown(*id).name,
+        }
+    }
+
+    /// Replaces the registered custom networks and gateway urls, invalid entries are skipped
+    pub fn register(custom_networks: &[CustomNetwork], gateway_endpoints: &[GatewayEndpoint]) {
+        let mut registry = NETWORK_REGISTRY
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        registry.custom_networks = custom_networks
+            .iter()
+            .filter(|custom_network| custom_network.validate().is_ok())
+            .map(|custom_network| (custom_network.id, custom_network.clone()))
+            .collect();
+
+        registry.gateway_urls = gateway_endpoints
+            .iter()
+            .filter(|endpoint| CustomNetwork::is_valid_url(&endpoint.url))
+            .map(|endpoint| (endpoint.network, endpoint.url.clone()))
+            .collect();
+    }
+
+    pub fn custom_network(id: u8) -> Option<CustomNetwork> {
+        NETWORK_REGISTRY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .custom_networks
+            .get(&id)
+            .cloned()
+    }
+
+    pub fn custom_networks() -> Vec<CustomNetwork> {
+        NETWORK_REGISTRY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .custom_networks
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// A network that has not been registered gets an empty address prefix and gateway url,
+    /// so addresses on it never validate and gateway requests fail
+    fn custom_or_unknown(id: u8) -> CustomNetwork {
+        Self::custom_network(id).unwrap_or_else(|| CustomNetwork {
+            id,
+            name: format!("Unknown network {id}"),
+            hrp_suffix: String::new(),
+            gateway_url: String::new(),
+            dashboard_url: None,
+        })
+    }
+}
+
+impl CustomNetwork {
+    pub fn prefix(&self) -> String {
+        format!("{}1", self.hrp_suffix)
+    }
+
+    pub fn definition(&self) -> NetworkDefinition {
+        NetworkDefinition {
+            id: self.id,
+            logical_name: self.name.clone().into(),
+            hrp_suffix: self.hrp_suffix.clone().into(),
+        }
+    }
+
+    /// Checks the network on its own, use [`Self::validate_against`] to also check it against
+    /// other networks
+    pub fn validate(&self) -> Result<(), NetworkError> {
+        if self.id as u32 == BIP32_NETWORK_ID_MAINNET || self.id as u32 == BIP32_NETWORK_ID_STOKENET
+        {
+            return Err(NetworkError::ReservedId(self.id));
+        }
+        if self.name.trim().is_empty() {
+            return Err(NetworkError::EmptyName);
         }
+
+        let reserved_suffix = [Network::Mainnet, Network::Stokenet]
+            .iter()
+            .any(|network| network.definition().hrp_suffix == self.hrp_suffix.as_str());
+        let valid_suffix = !self.hrp_suffix.is_empty()
+            && self
+                .hrp_suffix
+                .chars()
+                .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '_');
+        if reserved_suffix || !valid_suffix {
+            return Err(NetworkError::InvalidHrpSuffix(self.hrp_suffix.clone()));
+        }
+
+        if !Self::is_valid_url(&self.gateway_url) {
+            return Err(NetworkError::InvalidGatewayUrl(self.gateway_url.clone()));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_against(&self, others: &[CustomNetwork]) -> Result<(), NetworkError> {
+        self.validate()?;
+
+        for other in others {
+            if other.id == self.id {
+                return Err(NetworkError::DuplicateId(self.id));
+            }
+            if other.hrp_suffix == self.hrp_suffix {
+                return Err(NetworkError::InvalidHrpSuffix(self.hrp_suffix.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_valid_url(url: &str) -> bool {
+        reqwest::Url::parse(url)
+            .is_ok_and(|url| (url.scheme() == "http" || url.scheme() == "https") && url.has_host())
     }
 }
 
@@ -74,3 +283,119 @@ impl rusqlite::types::ToSql for Network {
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        mem,
+        panic::{self, AssertUnwindSafe},
+        str::FromStr,
+        sync::Mutex,
+    };
+
+    use super::*;
+    use crate::address::{Address, ResourceAddress};
+
+    fn localnet() -> CustomNetwork {
+        CustomNetwork {
+            id: 240,
+            name: "Localnet".to_string(),
+            hrp_suffix: "loc".to_string(),
+            gateway_url: "http://localhost:5308".to_string(),
+            dashboard_url: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_custom_network() {
+        assert_eq!(localnet().validate(), Ok(()));
+
+        let mut network = localnet();
+        network.id = BIP32_NETWORK_ID_MAINNET as u8;
+        assert_eq!(network.validate(), Err(NetworkError::ReservedId(1)));
+
+        let mut network = localnet();
+        network.hrp_suffix = "rdx".to_string();
+        assert!(matches!(
+            network.validate(),
+            Err(NetworkError::InvalidHrpSuffix(_))
+        ));
+
+        let mut network = localnet();
+        network.gateway_url = "localhost".to_string();
+        assert!(matches!(
+            network.validate(),
+            Err(NetworkError::InvalidGatewayUrl(_))
+        ));
+
+        assert_eq!(
+            localnet().validate_against(&[localnet()]),
+            Err(NetworkError::DuplicateId(240))
+        );
+    }
+
+    /// Registers the networks only while `test` runs and restores the previous registry after,
+    /// tests that register networks run one at a time so they don't see each other's networks
+    fn with_registered_networks(custom_networks: &[CustomNetwork], test: impl FnOnce()) {
+        static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+        let _lock = REGISTRY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let previous = mem::take(
+            &mut *NETWORK_REGISTRY
+                .write()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        Network::register(custom_networks, &[]);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(test));
+
+        *NETWORK_REGISTRY
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = previous;
+
+        if let Err(panic) = result {
+            panic::resume_unwind(panic);
+        }
+    }
+
+    #[test]
+    fn test_registered_custom_network() {
+        with_registered_networks(&[localnet()], || {
+            let network = Network::Custom(240);
+
+            assert_eq!(Network::from_id(240), Ok(network));
+            assert_eq!(Network::from_id(241), Err(241));
+            assert_eq!(network.prefix(), "loc1");
+            assert_eq!(network.gateway_url(), "http://localhost:5308");
+            assert_eq!(network.dashboard_url(), None);
+            assert!(network.xrd_address().starts_with("resource_loc1"));
+
+            let xrd = ResourceAddress::from_str_on(network, &network.xrd_address()).unwrap();
+            assert_eq!(xrd.network(), network);
+            assert!(xrd.is_xrd());
+        });
+
+        assert_eq!(Network::from_id(240), Err(240));
+    }
+
+    #[test]
+    fn test_custom_network_addresses_are_checked_against_the_given_network() {
+        with_registered_networks(&[localnet()], || {
+            let network = Network::Custom(240);
+            let xrd = network.xrd_address();
+
+            // Only Mainnet and Stokenet are known without passing the network
+            assert!(ResourceAddress::from_str(&xrd).is_err());
+            assert!(ResourceAddress::from_str_on(Network::Mainnet, &xrd).is_err());
+            assert!(ResourceAddress::from_stored_str(&xrd).is_ok());
+
+            // Same prefix, length and characters but the bech32 checksum no longer matches
+            let mut tampered = xrd.clone();
+            let last = if tampered.ends_with('q') { 'p' } else { 'q' };
+            tampered.pop();
+            tampered.push(last);
+            assert!(!ResourceAddress::is_valid_address(network, &tampered));
+            assert!(ResourceAddress::from_str_on(network, &tampered).is_err());
+        });
+    }
+}
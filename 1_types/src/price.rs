@@ -0,0 +1,189 @@
+use deps::*;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use scrypto::math::{CheckedAdd, CheckedMul, Decimal as RadixDecimal, RoundingMode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{address::ResourceAddress, assets::FungibleAsset};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("\"{0}\" is not a currency code, use a three letter code like USD")]
+pub struct InvalidCurrency(String);
+
+/// A fiat currency by its ISO 4217 code, e.g. `USD`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    pub const DEFAULT_CODE: &'static str = "USD";
+
+    pub fn new(code: &str) -> Result<Self, InvalidCurrency> {
+        let code = code.trim().to_ascii_uppercase();
+        if code.len() == 3 && code.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            Ok(Self(code))
+        } else {
+            Err(InvalidCurrency(code))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self(Self::DEFAULT_CODE.to_string())
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = InvalidCurrency;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Self::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.0
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where the prices of resources are read from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PriceSourceConfig {
+    /// A service that answers with the prices of the requested resources
+    Http { url: String },
+    /// A file with a fixed set of prices
+    File { path: PathBuf },
+}
+
+/// The price of one unit of each resource in one currency,
+/// a resource without a price has no known value
+#[derive(Debug, Clone, Default)]
+pub struct PriceTable {
+    pub currency: Currency,
+    pub prices: HashMap<ResourceAddress, RadixDecimal>,
+    /// Unix time in seconds of the latest price update, `None` if there are no prices
+    pub updated_at: Option<i64>,
+}
+
+impl PriceTable {
+    pub fn new(currency: Currency) -> Self {
+        Self {
+            currency,
+            prices: HashMap::new(),
+            updated_at: None,
+        }
+    }
+
+    /// `None` if the resource has no price or the value does not fit in a decimal
+    pub fn value(&self, resource_address: &ResourceAddress, amount: &str) -> Option<RadixDecimal> {
+        let price = self.prices.get(resource_address)?;
+        let amount = RadixDecimal::from_str(amount).ok()?;
+        amount.checked_mul(*price)
+    }
+
+    /// The value of the fungibles that have a price, `None` if none of them has one
+    /// or the total does not fit in a decimal
+    pub fn total_value<'a>(
+        &self,
+        fungibles: impl IntoIterator<Item = &'a FungibleAsset>,
+    ) -> Option<RadixDecimal> {
+        let mut values = fungibles
+            .into_iter()
+            .filter_map(|fungible| self.value(&fungible.resource_address, &fungible.amount));
+        let first = values.next()?;
+        values.try_fold(first, |total, value| total.checked_add(value))
+    }
+
+    /// Rounded to two decimals and followed by the currency, e.g. `1234.57 USD`
+    pub fn format(&self, value: RadixDecimal) -> String {
+        let rounded = value
+            .checked_round(2, RoundingMode::ToNearestMidpointAwayFromZero)
+            .unwrap_or(value);
+        format!("{rounded} {}", self.currency)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::address::AccountAddress;
+
+    use super::*;
+
+    const XRD: &str = "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
+    const UNPRICED: &str = "resource_rdx1t5rswpc8qurswpc8qurswpc8qurswpc8qurswpc8qurswpc820luy5";
+
+    fn fungible(resource_address: &str, amount: &str) -> FungibleAsset {
+        let account = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap();
+        FungibleAsset::new(
+            &account,
+            amount.to_string(),
+            ResourceAddress::from_str(resource_address).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_currency_code() {
+        assert_eq!(Currency::new(" eur").unwrap().as_str(), "EUR");
+        assert!(Currency::new("EURO").is_err());
+        assert!(Currency::new("U5D").is_err());
+    }
+
+    #[test]
+    fn test_total_value_skips_resources_without_price() {
+        let mut price_table = PriceTable::new(Currency::default());
+        price_table.prices.insert(
+            ResourceAddress::from_str(XRD).unwrap(),
+            RadixDecimal::from_str("0.015").unwrap(),
+        );
+
+        let fungibles = [fungible(XRD, "1000.5"), fungible(UNPRICED, "10")];
+
+        let total = price_table.total_value(&fungibles).unwrap();
+        assert_eq!(total, RadixDecimal::from_str("15.0075").unwrap());
+        assert_eq!(price_table.format(total), "15.01 USD");
+        assert_eq!(price_table.total_value(&fungibles[1..]), None);
+    }
+
+    #[test]
+    fn test_values_that_overflow_are_none() {
+        let mut price_table = PriceTable::new(Currency::default());
+        price_table
+            .prices
+            .insert(ResourceAddress::from_str(XRD).unwrap(), RadixDecimal::MAX);
+
+        assert_eq!(
+            price_table.value(&ResourceAddress::from_str(XRD).unwrap(), "2"),
+            None
+        );
+
+        let fungibles = [fungible(XRD, "1"), fungible(XRD, "1")];
+        assert_eq!(
+            price_table.total_value(&fungibles[..1]),
+            Some(RadixDecimal::MAX)
+        );
+        assert_eq!(price_table.total_value(&fungibles), None);
+    }
+}
@@ -0,0 +1,48 @@
+use deps::*;
+
+use serde::{Deserialize, Serialize};
+
+/// A proxy every request leaving the wallet is sent through
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Proxy {
+    Http {
+        host: String,
+        port: u16,
+    },
+    /// Host names are resolved by the proxy, so lookups do not leak past it, e.g. when using Tor
+    Socks5 {
+        host: String,
+        port: u16,
+    },
+}
+
+impl Proxy {
+    pub fn url(&self) -> String {
+        match self {
+            Self::Http { host, port } => format!("http://{host}:{port}"),
+            Self::Socks5 { host, port } => format!("socks5h://{host}:{port}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proxy_from_settings() {
+        let proxy: Proxy =
+            serde_json::from_str(r#"{ "kind": "socks5", "host": "127.0.0.1", "port": 9050 }"#)
+                .unwrap();
+
+        assert_eq!(
+            proxy,
+            Proxy::Socks5 {
+                host: "127.0.0.1".to_string(),
+                port: 9050
+            }
+        );
+        assert_eq!(proxy.url(), "socks5h://127.0.0.1:9050");
+    }
+}
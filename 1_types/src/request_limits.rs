@@ -0,0 +1,61 @@
+use deps::*;
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Limits for the requests sent to a gateway, shared by every request to the same network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Requests waiting for a response, further requests wait until one completes
+    pub max_in_flight: usize,
+    /// Retries of a request that was rate limited or failed on the gateway side
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl RequestLimits {
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+    pub const DEFAULT_MAX_RETRIES: u32 = 4;
+    pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+    pub const DEFAULT_MAX_BACKOFF_MS: u64 = 10_000;
+
+    /// The delay before retry number `attempt`, starting at 0, doubles with each attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff_ms = self
+            .initial_backoff_ms
+            .saturating_mul(2u64.saturating_pow(attempt))
+            .min(self.max_backoff_ms);
+
+        Duration::from_millis(backoff_ms)
+    }
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_in_flight: Self::DEFAULT_MAX_IN_FLIGHT,
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            initial_backoff_ms: Self::DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: Self::DEFAULT_MAX_BACKOFF_MS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let limits = RequestLimits::default();
+
+        assert_eq!(limits.backoff(0), Duration::from_millis(500));
+        assert_eq!(limits.backoff(1), Duration::from_millis(1000));
+        assert_eq!(limits.backoff(3), Duration::from_millis(4000));
+        assert_eq!(limits.backoff(5), Duration::from_millis(10_000));
+        assert_eq!(limits.backoff(u32::MAX), Duration::from_millis(10_000));
+    }
+}
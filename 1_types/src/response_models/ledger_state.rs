@@ -4,9 +4,9 @@ use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LedgerState {
-    network: String,
-    state_version: u64,
-    proposer_round_timestamp: String,
-    epoch: u64,
-    round: u32
+    pub network: String,
+    pub state_version: u64,
+    pub proposer_round_timestamp: String,
+    pub epoch: u64,
+    pub round: u32
 }
\ No newline at end of file
@@ -5,6 +5,7 @@ pub mod metadata;
 pub mod non_fungible_collection;
 pub mod non_fungible_id_data;
 pub mod resource;
+pub mod transaction_preview;
 pub mod transactions;
 
 pub use entity_details::*;
@@ -13,4 +14,5 @@ pub use ledger_state::LedgerState;
 pub use metadata::*;
 pub use non_fungible_collection::*;
 pub use resource::*;
+pub use transaction_preview::*;
 pub use transactions::*;
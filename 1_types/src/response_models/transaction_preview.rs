@@ -0,0 +1,44 @@
+use deps::*;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransactionPreviewResponse {
+    pub receipt: PreviewReceipt,
+    #[serde(default)]
+    pub resource_changes: Vec<InstructionResourceChanges>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewReceipt {
+    pub status: String,
+    pub fee_summary: Option<FeeSummary>,
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeeSummary {
+    pub xrd_total_execution_cost: String,
+    pub xrd_total_finalization_cost: String,
+    pub xrd_total_royalty_cost: String,
+    pub xrd_total_storage_cost: String,
+    pub xrd_total_tipping_cost: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstructionResourceChanges {
+    pub index: u32,
+    pub resource_changes: Vec<PreviewResourceChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewResourceChange {
+    pub resource_address: String,
+    pub component_entity: PreviewEntity,
+    pub amount: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewEntity {
+    pub entity_address: String,
+}
@@ -7,6 +7,8 @@ use super::LedgerState;
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionsResponse {
     pub ledger_state: LedgerState,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
     pub items: Vec<TransactionResponse>,
 }
 
@@ -14,7 +16,11 @@ pub struct TransactionsResponse {
 pub struct TransactionResponse {
     pub transaction_status: String,
     pub state_version: u64,
-    pub confirmed_at: String,
+    #[serde(default)]
+    pub intent_hash: Option<String>,
+    pub round_timestamp: String,
+    #[serde(default)]
+    pub confirmed_at: Option<String>,
     pub message: Option<Message>,
     pub balance_changes: Option<BalanceChanges>,
 }
@@ -0,0 +1,86 @@
+//! Proptest strategies for the types, shared by the tests of this and the dependent crates.
+//! Enabled for other crates with the `proptest` feature
+use deps::*;
+
+use std::str::FromStr;
+
+use proptest::{prelude::*, string::string_regex};
+use scrypto::{address::AddressBech32Encoder, crypto::Ed25519PublicKey, types::ComponentAddress};
+
+use crate::{
+    Network, TimeStamp, Transaction, TransactionStatus,
+    address::{AccountAddress, Address, TransactionAddress},
+};
+
+pub fn network() -> impl Strategy<Value = Network> {
+    prop_oneof![Just(Network::Mainnet), Just(Network::Stokenet)]
+}
+
+pub fn account_address() -> impl Strategy<Value = AccountAddress> {
+    (network(), any::<[u8; Ed25519PublicKey::LENGTH]>()).prop_map(|(network, public_key)| {
+        let address =
+            ComponentAddress::preallocated_account_from_public_key(&Ed25519PublicKey(public_key));
+        let address = AddressBech32Encoder::new(&network.definition())
+            .encode(address.as_ref())
+            .unwrap();
+        AccountAddress::from_str(&address).unwrap()
+    })
+}
+
+/// Resource and transaction addresses are only matched against their pattern
+pub fn address<T>() -> impl Strategy<Value = T>
+where
+    T: Address + std::fmt::Debug + 'static,
+    T::Err: std::fmt::Debug,
+{
+    network()
+        .prop_flat_map(|network| {
+            string_regex(&format!(
+                "{}{}[a-z0-9]{{{}}}",
+                T::ADDRESS_TYPE_PREFIX,
+                network.prefix(),
+                T::ADDRESS_LENGTH
+            ))
+            .unwrap()
+        })
+        .prop_map(|address| T::from_str(&address).unwrap())
+}
+
+pub fn timestamp() -> impl Strategy<Value = TimeStamp> {
+    (1970u16..2100, 1u8..=12, 1u8..=28, 0u8..24, 0u8..60, 0u8..60).prop_map(
+        |(year, month, day, hour, minute, second)| {
+            TimeStamp::new(year, month, day, hour, minute, second)
+        },
+    )
+}
+
+pub fn transaction_status() -> impl Strategy<Value = TransactionStatus> {
+    prop_oneof![
+        Just(TransactionStatus::Failed),
+        Just(TransactionStatus::Success),
+        Just(TransactionStatus::Pending),
+    ]
+}
+
+/// Transactions without balance changes and with a low state version,
+/// so transactions with the same state version are common
+pub fn transaction() -> impl Strategy<Value = Transaction> {
+    (
+        timestamp(),
+        0u64..4,
+        account_address(),
+        address::<TransactionAddress>(),
+        transaction_status(),
+    )
+        .prop_map(|(timestamp, state_version, account, address, status)| {
+            Transaction::new(
+                timestamp,
+                state_version,
+                Vec::new(),
+                &account,
+                address,
+                None,
+                status,
+            )
+        })
+}
@@ -19,46 +19,6 @@ pub enum TransactionError {
     InvalidIdLength { expected: usize, found: usize },
 }
 
-// #[derive(Debug, Clone)]
-// pub struct BalanceChange {
-//     id: BalanceChangeID,
-//     transaction_id: TransactionAddress,
-//     account_address: AccountAddress,
-//     resource_address: ResourceAddress,
-//     amount: Decimal,
-// }
-
-// #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-// pub struct BalanceChangeID([u8; Self::LENGTH]);
-
-// impl BalanceChangeID {
-//     const LENGTH: usize = TimeStamp::LENGTH
-//         + TransactionAddress::CHECKSUM_LENGTH
-//         + AccountAddress::CHECKSUM_LENGTH
-//         + ResourceAddress::CHECKSUM_LEN;
-
-//     const STEP3_INDEX: usize =
-//         TransactionAddress::CHECKSUM_LENGTH + AccountAddress::CHECKSUM_LENGTH;
-//     const STEP4_INDEX: usize = Self::LENGTH - TimeStamp::LENGTH;
-
-//     pub fn new(
-//         timestamp: TimeStamp,
-//         transaction_id: &TransactionAddress,
-//         account_address: &AccountAddress,
-//         resource_address: &ResourceAddress,
-//     ) -> Self {
-//         let mut id = [0u8; Self::LENGTH];
-
-//         id[..TransactionAddress::CHECKSUM_LENGTH].copy_from_slice(transaction_id.chechsum_slice());
-//         id[TransactionAddress::CHECKSUM_LENGTH..AccountAddress::CHECKSUM_LENGTH]
-//             .copy_from_slice(account_address.checksum_slice());
-//         id[Self::STEP3_INDEX..Self::STEP4_INDEX].copy_from_slice(resource_address.checksum_slice());
-//         id[Self::STEP4_INDEX..].copy_from_slice(timestamp.as_slice());
-
-//         Self(id)
-//     }
-// }
-
 #[derive(Debug, Clone)]
 pub struct Transaction {
     pub id: TransactionId, //primary key
@@ -67,6 +27,10 @@ pub struct Transaction {
     pub state_version: u64,
     pub balance_changes: Vec<BalanceChange>,
     pub message: Option<String>,
+    pub status: TransactionStatus,
+    /// The fee paid by the account, `None` if the transaction was paid for by someone else
+    /// or has not been synced from the ledger yet
+    pub fee: Option<String>,
 }
 
 impl Transaction {
@@ -77,6 +41,7 @@ impl Transaction {
         account_address: &AccountAddress,
         transaction_address: TransactionAddress,
         message: Option<String>,
+        status: TransactionStatus,
     ) -> Self {
         Self {
             id: TransactionId::new(&account_address, &transaction_address),
@@ -85,8 +50,38 @@ impl Transaction {
             state_version,
             balance_changes,
             message,
+            status,
+            fee: None,
         }
     }
+
+    /// A submitted transaction that has not been committed yet, the state version is set
+    /// when the transaction is committed
+    pub fn pending(
+        account_address: &AccountAddress,
+        transaction_address: TransactionAddress,
+        message: Option<String>,
+    ) -> Self {
+        Self::new(
+            TimeStamp::now(),
+            0,
+            Vec::new(),
+            account_address,
+            transaction_address,
+            message,
+            TransactionStatus::Pending,
+        )
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == TransactionStatus::Pending
+    }
+
+    /// Transactions are ordered by when they happened, the id breaks ties between
+    /// transactions committed in the same second and state version
+    fn sort_key(&self) -> (&TimeStamp, u64, &TransactionId) {
+        (&self.timestamp, self.state_version, &self.id)
+    }
 }
 
 impl FromAsyncIterator<Transaction> for BTreeSet<Transaction> {
@@ -104,22 +99,6 @@ impl FromAsyncIterator<Transaction> for BTreeSet<Transaction> {
 }
 
 impl PartialOrd for Transaction {
-    fn ge(&self, other: &Self) -> bool {
-        self.timestamp >= other.timestamp
-    }
-
-    fn gt(&self, other: &Self) -> bool {
-        self.timestamp > other.timestamp
-    }
-
-    fn le(&self, other: &Self) -> bool {
-        self.timestamp <= other.timestamp
-    }
-
-    fn lt(&self, other: &Self) -> bool {
-        self.timestamp < other.timestamp
-    }
-
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
@@ -127,69 +106,70 @@ impl PartialOrd for Transaction {
 
 impl Ord for Transaction {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.timestamp == other.timestamp {
-            self.id.cmp(&self.id)
-        } else if self.timestamp < other.timestamp {
-            std::cmp::Ordering::Less
-        } else {
-            std::cmp::Ordering::Greater
-        }
+        self.sort_key().cmp(&other.sort_key())
     }
 }
 
+/// Equality uses the same fields as the ordering so the two always agree,
+/// a pending transaction and its committed version are not equal
 impl PartialEq for Transaction {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
-    }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.id != other.id
+        self.sort_key() == other.sort_key()
     }
 }
 
 impl Eq for Transaction {}
 
+/// Identifies a transaction as seen from one account, the same transaction
+/// touching two of the wallets accounts is stored once per account.
+/// Made up of the double checksum of the account address followed by
+/// the double checksum of the transaction address
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TransactionId([u8; Self::LENGTH]);
 
 impl TransactionId {
-    pub const CHECKSUM_LEN: usize = 6;
-    const CHECKSUM_START_INDEX: usize = Self::LENGTH - Self::CHECKSUM_LEN;
-    const FROM_ACCOUNT_ADDRESS_LENGTH: usize = AccountAddress::CHECKSUM_LENGTH * 2;
-    const LENGTH: usize =
-        AccountAddress::CHECKSUM_LENGTH * 2 + TransactionAddress::CHECKSUM_LENGTH * 2;
+    const ACCOUNT_CHECKSUM_END: usize = AccountAddress::CHECKSUM_DOUBLE_LENGTH;
+    pub const LENGTH: usize =
+        AccountAddress::CHECKSUM_DOUBLE_LENGTH + TransactionAddress::CHECKSUM_DOUBLE_LENGTH;
 
     pub fn new(account_address: &AccountAddress, tx_address: &TransactionAddress) -> Self {
         let mut transaction_id = [0u8; Self::LENGTH];
-        transaction_id[..Self::FROM_ACCOUNT_ADDRESS_LENGTH]
+        transaction_id[..Self::ACCOUNT_CHECKSUM_END]
             .copy_from_slice(account_address.checksum_double_slice());
-        transaction_id[Self::FROM_ACCOUNT_ADDRESS_LENGTH..]
+        transaction_id[Self::ACCOUNT_CHECKSUM_END..]
             .copy_from_slice(tx_address.checksum_double_slice());
         Self(transaction_id)
     }
 
-    pub fn checksum(&self) -> [u8; Self::CHECKSUM_LEN] {
-        self.0[Self::CHECKSUM_START_INDEX..]
-            .try_into()
-            .unwrap_unreachable(debug_info!("Invalid checksum length"))
+    pub fn account_checksum(&self) -> &[u8] {
+        &self.0[..Self::ACCOUNT_CHECKSUM_END]
     }
 
-    pub fn checksum_slice(&self) -> &[u8] {
-        &self.0[Self::CHECKSUM_START_INDEX..]
+    pub fn transaction_checksum(&self) -> &[u8] {
+        &self.0[Self::ACCOUNT_CHECKSUM_END..]
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8]> for TransactionId {
+    type Error = rusqlite::types::FromSqlError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(slice.try_into().map_err(|_| {
+            rusqlite::types::FromSqlError::InvalidBlobSize {
+                expected_size: Self::LENGTH,
+                blob_size: slice.len(),
+            }
+        })?))
     }
 }
 
 impl rusqlite::types::FromSql for TransactionId {
     fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        match value {
-            rusqlite::types::ValueRef::Blob(slice) => Ok(Self(slice.try_into().map_err(|_| {
-                rusqlite::types::FromSqlError::InvalidBlobSize {
-                    expected_size: Self::LENGTH,
-                    blob_size: slice.len(),
-                }
-            })?)),
-            _ => Err(rusqlite::types::FromSqlError::InvalidType),
-        }
+        Self::try_from(value.as_blob()?)
     }
 }
 
@@ -213,6 +193,65 @@ impl TimeStamp {
         Self([year_high, year_low, month, day, hour, minute, second])
     }
 
+    /// Converts seconds since the unix epoch to a UTC timestamp
+    pub fn from_unix_seconds(seconds: u64) -> Self {
+        let days = (seconds / 86_400) as i64;
+        let seconds_of_day = seconds % 86_400;
+
+        // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+        let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+        let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as u16;
+
+        Self::new(
+            year,
+            month,
+            day,
+            (seconds_of_day / 3_600) as u8,
+            (seconds_of_day % 3_600 / 60) as u8,
+            (seconds_of_day % 60) as u8,
+        )
+    }
+
+    /// Parses the date and time of an ISO 8601 UTC timestamp like `2024-03-07T14:40:35.123Z`,
+    /// fractions of a second are ignored
+    pub fn from_iso8601(timestamp: &str) -> Option<Self> {
+        let (date, time) = timestamp.split_once('T')?;
+
+        let mut date = date.split('-').map(|part| part.parse::<u16>().ok());
+        let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+
+        let mut time = time
+            .get(..8)?
+            .split(':')
+            .map(|part| part.parse::<u8>().ok());
+        let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+        Some(Self::new(
+            year,
+            month as u8,
+            day as u8,
+            hour,
+            minute,
+            second,
+        ))
+    }
+
+    pub fn now() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Self::from_unix_seconds(seconds)
+    }
+
     pub fn as_slice(&self) -> &[u8] {
         &self.0
     }
@@ -221,12 +260,45 @@ impl TimeStamp {
         self.0[0] as u16 * 100 + self.0[1] as u16
     }
 
+    pub fn month(&self) -> u8 {
+        self.0[2]
+    }
+
+    pub fn day(&self) -> u8 {
+        self.0[3]
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.0[4]
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.0[5]
+    }
+
+    pub fn second(&self) -> u8 {
+        self.0[6]
+    }
+
     pub fn to_string(&self) -> String {
         format!(
             "{}{}-{}-{} {}:{}:{}",
             self.0[0], self.0[1], self.0[2], self.0[3], self.0[4], self.0[5], self.0[6]
         )
     }
+
+    /// UTC in the `YYYY-MM-DDTHH:MM:SSZ` format
+    pub fn to_iso8601(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year(),
+            self.month(),
+            self.day(),
+            self.hour(),
+            self.minute(),
+            self.second()
+        )
+    }
 }
 
 impl std::fmt::Display for TimeStamp {
@@ -280,6 +352,19 @@ impl rusqlite::types::FromSql for TransactionStatus {
     }
 }
 
+impl rusqlite::types::ToSql for TransactionStatus {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        let value = match self {
+            TransactionStatus::Failed => 0,
+            TransactionStatus::Success => 1,
+            TransactionStatus::Pending => 2,
+        };
+        Ok(rusqlite::types::ToSqlOutput::Owned(
+            rusqlite::types::Value::Integer(value),
+        ))
+    }
+}
+
 impl Display for TransactionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -295,58 +380,71 @@ pub struct BalanceChange {
     pub id: BalanceChangeId,
     pub account: AccountAddress,
     pub resource: ResourceAddress,
+    /// The non fungible ids deposited to the account
     pub nfts: Option<NFTs>,
+    /// The non fungible ids withdrawn from the account
+    pub removed_nfts: Option<NFTs>,
     pub amount: Option<String>,
 }
 
 impl BalanceChange {
+    /// The account is already part of the transaction id, it is kept on the balance change
+    /// so it can be queried without joining the transactions table
     pub fn new(
         transaction: TransactionId,
         account: AccountAddress,
         resource: ResourceAddress,
         nfts: Option<NFTs>,
+        removed_nfts: Option<NFTs>,
         amount: Option<String>,
     ) -> Self {
         Self {
-            id: BalanceChangeId::new(
-                transaction.checksum_slice(),
-                account.checksum_slice(),
-                resource.checksum_slice(),
-            ),
+            id: BalanceChangeId::new(&transaction, &resource),
             account,
             resource,
             nfts,
+            removed_nfts,
             amount,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// The full transaction id followed by the double checksum of the resource address,
+/// a transaction has at most one balance change per account and resource
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BalanceChangeId([u8; Self::LENGTH]);
 
 impl BalanceChangeId {
-    const LENGTH: usize = TransactionId::CHECKSUM_LEN
-        + AccountAddress::CHECKSUM_LENGTH
-        + ResourceAddress::CHECKSUM_LENGTH;
+    pub const LENGTH: usize = TransactionId::LENGTH + ResourceAddress::CHECKSUM_DOUBLE_LENGTH;
 
-    const LAST_CHECKSUM_START: usize =
-        TransactionId::CHECKSUM_LEN + AccountAddress::CHECKSUM_LENGTH;
-
-    pub fn new(tx_checksum: &[u8], account_checksum: &[u8], resource_checksum: &[u8]) -> Self {
+    pub fn new(transaction_id: &TransactionId, resource_address: &ResourceAddress) -> Self {
         let mut id = [0u8; Self::LENGTH];
+        id[..TransactionId::LENGTH].copy_from_slice(transaction_id.as_slice());
+        id[TransactionId::LENGTH..].copy_from_slice(resource_address.checksum_double_slice());
+        Self(id)
+    }
 
-        id[..TransactionId::CHECKSUM_LEN].copy_from_slice(tx_checksum);
-        id[TransactionId::CHECKSUM_LEN..AccountAddress::CHECKSUM_LENGTH]
-            .copy_from_slice(account_checksum);
-        id[Self::LAST_CHECKSUM_START..].copy_from_slice(resource_checksum);
+    pub fn transaction_id(&self) -> TransactionId {
+        TransactionId(
+            self.0[..TransactionId::LENGTH]
+                .try_into()
+                .unwrap_unreachable(debug_info!("Invalid transaction id length")),
+        )
+    }
 
-        Self(id)
+    pub fn resource_checksum(&self) -> &[u8] {
+        &self.0[TransactionId::LENGTH..]
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
     }
 }
 
-impl rusqlite::types::FromSql for BalanceChangeId {
-    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        let slice = value.as_blob()?;
+impl TryFrom<&[u8]> for BalanceChangeId {
+    type Error = rusqlite::types::FromSqlError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
         Ok(Self(slice.try_into().map_err(|_| {
             rusqlite::types::FromSqlError::InvalidBlobSize {
                 expected_size: Self::LENGTH,
@@ -356,6 +454,12 @@ impl rusqlite::types::FromSql for BalanceChangeId {
     }
 }
 
+impl rusqlite::types::FromSql for BalanceChangeId {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        Self::try_from(value.as_blob()?)
+    }
+}
+
 impl rusqlite::types::ToSql for BalanceChangeId {
     fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
         Ok(rusqlite::types::ToSqlOutput::Borrowed(
@@ -368,6 +472,103 @@ impl rusqlite::types::ToSql for BalanceChangeId {
 mod test {
 
     use super::*;
+    use crate::strategies::{account_address, address, timestamp, transaction, transaction_status};
+    use proptest::prelude::*;
+    use rusqlite::types::{FromSql, ToSql};
+
+    fn round_trip<T: ToSql + FromSql>(value: &T) -> T {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE round_trip (value BLOB)", [])
+            .unwrap();
+        conn.execute("INSERT INTO round_trip (value) VALUES (?)", [value])
+            .unwrap();
+        conn.query_row("SELECT value FROM round_trip", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn test_transaction_id_round_trip(
+            account in account_address(),
+            transaction_address in address::<TransactionAddress>(),
+        ) {
+            let id = TransactionId::new(&account, &transaction_address);
+
+            prop_assert_eq!(id.account_checksum(), account.checksum_double_slice());
+            prop_assert_eq!(id.transaction_checksum(), transaction_address.checksum_double_slice());
+            prop_assert_eq!(round_trip(&id), id);
+        }
+
+        #[test]
+        fn test_balance_change_id_round_trip(
+            account in account_address(),
+            transaction_address in address::<TransactionAddress>(),
+            resource in address::<ResourceAddress>(),
+        ) {
+            let transaction_id = TransactionId::new(&account, &transaction_address);
+            let id = BalanceChangeId::new(&transaction_id, &resource);
+
+            prop_assert_eq!(id.transaction_id(), transaction_id);
+            prop_assert_eq!(id.resource_checksum(), resource.checksum_double_slice());
+            prop_assert_eq!(round_trip(&id), id);
+        }
+
+        #[test]
+        fn test_balance_change_ids_differ_per_resource(
+            account in account_address(),
+            transaction_address in address::<TransactionAddress>(),
+            first in address::<ResourceAddress>(),
+            second in address::<ResourceAddress>(),
+        ) {
+            prop_assume!(first.checksum_double_slice() != second.checksum_double_slice());
+            let transaction_id = TransactionId::new(&account, &transaction_address);
+
+            prop_assert_ne!(
+                BalanceChangeId::new(&transaction_id, &first),
+                BalanceChangeId::new(&transaction_id, &second)
+            );
+        }
+
+        #[test]
+        fn test_timestamp_and_status_round_trip(
+            timestamp in timestamp(),
+            status in transaction_status(),
+        ) {
+            prop_assert_eq!(round_trip(&timestamp), timestamp);
+            prop_assert_eq!(round_trip(&status), status);
+        }
+
+        #[test]
+        fn test_transaction_ordering_is_total(
+            first in transaction(),
+            second in transaction(),
+            third in transaction(),
+        ) {
+            prop_assert_eq!(first.cmp(&second), second.cmp(&first).reverse());
+            prop_assert_eq!(first.cmp(&second) == std::cmp::Ordering::Equal, first == second);
+
+            let mut sorted = vec![first, second, third];
+            sorted.sort();
+            prop_assert!(sorted[0] <= sorted[1] && sorted[1] <= sorted[2]);
+            prop_assert!(sorted[0] <= sorted[2]);
+        }
+
+        #[test]
+        fn test_transactions_at_same_time_are_distinct(
+            first in transaction(),
+            second in transaction(),
+        ) {
+            prop_assume!(first.id != second.id);
+            let second = Transaction {
+                timestamp: first.timestamp.clone(),
+                state_version: first.state_version,
+                ..second
+            };
+
+            prop_assert_ne!(first.cmp(&second), std::cmp::Ordering::Equal);
+            prop_assert_eq!(BTreeSet::from([first, second]).len(), 2);
+        }
+    }
 
     #[test]
     fn test_timestamp() {
@@ -379,4 +580,37 @@ mod test {
 
         assert_eq!(string, target)
     }
+
+    #[test]
+    fn test_timestamp_from_iso8601() {
+        assert_eq!(
+            TimeStamp::from_iso8601("2024-03-07T14:40:35.123Z"),
+            Some(TimeStamp::new(2024, 3, 7, 14, 40, 35))
+        );
+        assert_eq!(TimeStamp::from_iso8601("2024-03-07"), None);
+    }
+
+    #[test]
+    fn test_timestamp_to_iso8601() {
+        let timestamp = TimeStamp::new(2024, 3, 7, 4, 0, 5);
+
+        assert_eq!(timestamp.to_iso8601(), "2024-03-07T04:00:05Z");
+        assert_eq!(
+            TimeStamp::from_iso8601(&timestamp.to_iso8601()),
+            Some(timestamp)
+        );
+    }
+
+    #[test]
+    fn test_timestamp_from_unix_seconds() {
+        assert_eq!(
+            TimeStamp::from_unix_seconds(0),
+            TimeStamp::new(1970, 1, 1, 0, 0, 0)
+        );
+        // 2024-03-07 14:40:35 UTC
+        assert_eq!(
+            TimeStamp::from_unix_seconds(1_709_822_435),
+            TimeStamp::new(2024, 3, 7, 14, 40, 35)
+        );
+    }
 }
@@ -0,0 +1,62 @@
+use deps::*;
+
+use std::collections::BTreeMap;
+
+use scrypto::math::Decimal as RadixDecimal;
+
+use crate::address::{AccountAddress, ResourceAddress};
+
+/// The outcome of running a transaction through the gateway preview before it is signed
+#[derive(Debug, Clone)]
+pub struct TransactionPreview {
+    pub succeeded: bool,
+    pub error_message: Option<String>,
+    /// Balance changes that are enforced by the manifest and can not differ on submission
+    pub guaranteed_changes: Vec<ResourceChange>,
+    /// Balance changes reported by the preview, these reflect the ledger state at the time of the preview
+    pub estimated_changes: Vec<ResourceChange>,
+    pub fee: FeeBreakdown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceChange {
+    pub account: AccountAddress,
+    pub resource: ResourceAddress,
+    pub amount: RadixDecimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeeBreakdown {
+    pub execution: RadixDecimal,
+    pub finalization: RadixDecimal,
+    pub storage: RadixDecimal,
+    pub royalty: RadixDecimal,
+    pub tipping: RadixDecimal,
+}
+
+impl FeeBreakdown {
+    pub fn total(&self) -> RadixDecimal {
+        self.execution + self.finalization + self.storage + self.royalty + self.tipping
+    }
+}
+
+impl ResourceChange {
+    /// Groups the changes by account and sums up the amount per resource
+    pub fn per_account(
+        changes: &[ResourceChange],
+    ) -> BTreeMap<&AccountAddress, BTreeMap<&ResourceAddress, RadixDecimal>> {
+        let mut per_account: BTreeMap<&AccountAddress, BTreeMap<&ResourceAddress, RadixDecimal>> =
+            BTreeMap::new();
+
+        for change in changes {
+            per_account
+                .entry(&change.account)
+                .or_default()
+                .entry(&change.resource)
+                .and_modify(|total| *total = *total + change.amount)
+                .or_insert(change.amount);
+        }
+
+        per_account
+    }
+}
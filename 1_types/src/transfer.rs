@@ -0,0 +1,347 @@
+use deps::*;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use scrypto::math::Decimal as RadixDecimal;
+
+use crate::{
+    Fee, ResourceChange,
+    address::{AccountAddress, ResourceAddress},
+};
+
+/// A transfer of fungible and non-fungible resources from one or more accounts to one or more
+/// recipients. The from account notarizes the transaction and pays the fee unless another payer
+/// is set, each resource is withdrawn from the source account of the deposit.
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub from_account: AccountAddress,
+    pub recipients: Vec<TransferRecipient>,
+    pub message: Option<String>,
+    pub fee: Fee,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferRecipient {
+    pub address: AccountAddress,
+    pub resources: Vec<TransferResource>,
+    pub non_fungibles: Vec<TransferNonFungibles>,
+}
+
+/// An amount of a resource deposited to a recipient and withdrawn from the source account
+#[derive(Debug, Clone)]
+pub struct TransferResource {
+    pub source: AccountAddress,
+    pub resource: ResourceAddress,
+    pub amount: RadixDecimal,
+}
+
+/// Specific non-fungibles of a resource deposited to a recipient and withdrawn from the source
+/// account
+#[derive(Debug, Clone)]
+pub struct TransferNonFungibles {
+    pub source: AccountAddress,
+    pub resource: ResourceAddress,
+    pub nfids: Vec<String>,
+}
+
+impl Transfer {
+    pub fn new(from_account: AccountAddress, message: Option<String>) -> Self {
+        Self {
+            fee: Fee::new(from_account.clone()),
+            from_account,
+            recipients: Vec::new(),
+            message,
+        }
+    }
+
+    /// Returns true if there is nothing to deposit to any recipient
+    pub fn is_empty(&self) -> bool {
+        self.recipients.iter().all(|recipient| {
+            recipient.resources.is_empty()
+                && recipient
+                    .non_fungibles
+                    .iter()
+                    .all(|non_fungibles| non_fungibles.nfids.is_empty())
+        })
+    }
+
+    /// The total amount of each resource that has to be withdrawn from each source account
+    pub fn withdrawals(&self) -> BTreeMap<(&AccountAddress, &ResourceAddress), RadixDecimal> {
+        let mut withdrawals = BTreeMap::new();
+
+        for recipient in &self.recipients {
+            for resource in &recipient.resources {
+                withdrawals
+                    .entry((&resource.source, &resource.resource))
+                    .and_modify(|total: &mut RadixDecimal| *total = *total + resource.amount)
+                    .or_insert(resource.amount);
+            }
+        }
+
+        withdrawals
+    }
+
+    /// The non-fungible ids of each resource that have to be withdrawn from each source account
+    pub fn non_fungible_withdrawals(
+        &self,
+    ) -> BTreeMap<(&AccountAddress, &ResourceAddress), BTreeSet<&String>> {
+        let mut withdrawals: BTreeMap<_, BTreeSet<&String>> = BTreeMap::new();
+
+        for recipient in &self.recipients {
+            for non_fungibles in &recipient.non_fungibles {
+                if non_fungibles.nfids.is_empty() {
+                    continue;
+                }
+                withdrawals
+                    .entry((&non_fungibles.source, &non_fungibles.resource))
+                    .or_default()
+                    .extend(non_fungibles.nfids.iter());
+            }
+        }
+
+        withdrawals
+    }
+
+    /// A non-fungible id that is deposited to more than one recipient. The withdrawal only takes
+    /// it once, so the second deposit would fail the transaction.
+    pub fn duplicate_non_fungible(&self) -> Option<(&ResourceAddress, &String)> {
+        let mut nfids = BTreeSet::new();
+
+        self.recipients
+            .iter()
+            .flat_map(|recipient| recipient.non_fungibles.iter())
+            .flat_map(|non_fungibles| {
+                non_fungibles
+                    .nfids
+                    .iter()
+                    .map(|nfid| (&non_fungibles.resource, nfid))
+            })
+            .find(|id| !nfids.insert(*id))
+    }
+
+    /// Every account that has to sign the transaction: the from account as notary,
+    /// the fee payer and each account a resource is withdrawn from
+    pub fn signers(&self) -> BTreeSet<&AccountAddress> {
+        let sources = self.recipients.iter().flat_map(|recipient| {
+            recipient
+                .resources
+                .iter()
+                .map(|resource| &resource.source)
+                .chain(
+                    recipient
+                        .non_fungibles
+                        .iter()
+                        .map(|non_fungibles| &non_fungibles.source),
+                )
+        });
+
+        [&self.from_account, &self.fee.payer]
+            .into_iter()
+            .chain(sources)
+            .collect()
+    }
+
+    /// The balance changes enforced by the manifest, the withdrawals from the source accounts
+    /// and the exact amounts deposited to each recipient. Non-fungibles count as one each.
+    pub fn guaranteed_changes(&self) -> Vec<ResourceChange> {
+        let withdrawals =
+            self.withdrawals()
+                .into_iter()
+                .map(|((source, resource_address), amount)| ResourceChange {
+                    account: source.clone(),
+                    resource: resource_address.clone(),
+                    amount: -amount,
+                });
+
+        let non_fungible_withdrawals = self.non_fungible_withdrawals().into_iter().map(
+            |((source, resource_address), nfids)| ResourceChange {
+                account: source.clone(),
+                resource: resource_address.clone(),
+                amount: -RadixDecimal::from(nfids.len() as u64),
+            },
+        );
+
+        let deposits = self.recipients.iter().flat_map(|recipient| {
+            recipient.resources.iter().map(|resource| ResourceChange {
+                account: recipient.address.clone(),
+                resource: resource.resource.clone(),
+                amount: resource.amount,
+            })
+        });
+
+        let non_fungible_deposits = self.recipients.iter().flat_map(|recipient| {
+            recipient
+                .non_fungibles
+                .iter()
+                .filter(|non_fungibles| !non_fungibles.nfids.is_empty())
+                .map(|non_fungibles| ResourceChange {
+                    account: recipient.address.clone(),
+                    resource: non_fungibles.resource.clone(),
+                    amount: RadixDecimal::from(non_fungibles.nfids.len() as u64),
+                })
+        });
+
+        withdrawals
+            .chain(non_fungible_withdrawals)
+            .chain(deposits)
+            .chain(non_fungible_deposits)
+            .collect()
+    }
+}
+
+impl TransferRecipient {
+    pub fn new(address: AccountAddress) -> Self {
+        Self {
+            address,
+            resources: Vec::new(),
+            non_fungibles: Vec::new(),
+        }
+    }
+}
+
+impl TransferResource {
+    pub fn new(source: AccountAddress, resource: ResourceAddress, amount: RadixDecimal) -> Self {
+        Self {
+            source,
+            resource,
+            amount,
+        }
+    }
+}
+
+impl TransferNonFungibles {
+    pub fn new(source: AccountAddress, resource: ResourceAddress, nfids: Vec<String>) -> Self {
+        Self {
+            source,
+            resource,
+            nfids,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_withdrawals_are_summed_per_source_and_resource() {
+        let from = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap();
+        let second_source = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ueq30he638p8u9wum7nuxl65z9pjdeyvc8h",
+        )
+        .unwrap();
+        let to = AccountAddress::from_str(
+            "account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k",
+        )
+        .unwrap();
+        let xrd = ResourceAddress::from_str(
+            "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd",
+        )
+        .unwrap();
+
+        let mut transfer = Transfer::new(from.clone(), None);
+        assert!(transfer.is_empty());
+        assert_eq!(transfer.signers().len(), 1);
+
+        let mut first = TransferRecipient::new(to.clone());
+        first.resources.push(TransferResource::new(
+            from.clone(),
+            xrd.clone(),
+            RadixDecimal::from(10),
+        ));
+        let mut second = TransferRecipient::new(to);
+        second.resources.push(TransferResource::new(
+            from.clone(),
+            xrd.clone(),
+            RadixDecimal::from_str("2.5").unwrap(),
+        ));
+        second.resources.push(TransferResource::new(
+            second_source.clone(),
+            xrd.clone(),
+            RadixDecimal::from(1),
+        ));
+
+        transfer.recipients.push(first);
+        transfer.recipients.push(second);
+
+        let withdrawals = transfer.withdrawals();
+        assert!(!transfer.is_empty());
+        assert_eq!(withdrawals.len(), 2);
+        assert_eq!(
+            withdrawals.get(&(&from, &xrd)),
+            Some(&RadixDecimal::from_str("12.5").unwrap())
+        );
+        assert_eq!(
+            withdrawals.get(&(&second_source, &xrd)),
+            Some(&RadixDecimal::from(1))
+        );
+
+        let signers = transfer.signers();
+        assert_eq!(signers.len(), 2);
+        assert!(signers.contains(&second_source));
+
+        let changes = transfer.guaranteed_changes();
+        assert_eq!(changes.len(), 5);
+        assert_eq!(changes[0].amount, -RadixDecimal::from_str("12.5").unwrap());
+
+        let per_account = ResourceChange::per_account(&changes);
+        assert_eq!(per_account.len(), 3);
+    }
+
+    #[test]
+    fn test_non_fungible_withdrawals() {
+        let from = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap();
+        let to = AccountAddress::from_str(
+            "account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k",
+        )
+        .unwrap();
+        let collection = ResourceAddress::from_str(
+            "resource_rdx1nfxxxxxxxxxxsecpsgxxxxxxxxx004638826440xxxxxxxxxwj8qq5",
+        )
+        .unwrap();
+
+        let mut transfer = Transfer::new(from.clone(), None);
+
+        let mut recipient = TransferRecipient::new(to.clone());
+        recipient.non_fungibles.push(TransferNonFungibles::new(
+            from.clone(),
+            collection.clone(),
+            Vec::new(),
+        ));
+        transfer.recipients.push(recipient);
+        assert!(transfer.is_empty());
+
+        transfer.recipients[0].non_fungibles[0].nfids = vec!["#1#".to_string(), "#2#".to_string()];
+        assert!(!transfer.is_empty());
+
+        let withdrawals = transfer.non_fungible_withdrawals();
+        assert_eq!(withdrawals.get(&(&from, &collection)).unwrap().len(), 2);
+
+        let changes = transfer.guaranteed_changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].amount, -RadixDecimal::from(2));
+        assert_eq!(changes[1].account, to);
+        assert_eq!(changes[1].amount, RadixDecimal::from(2));
+        assert!(transfer.duplicate_non_fungible().is_none());
+
+        let mut recipient = TransferRecipient::new(from.clone());
+        recipient.non_fungibles.push(TransferNonFungibles::new(
+            from.clone(),
+            collection.clone(),
+            vec!["#2#".to_string()],
+        ));
+        transfer.recipients.push(recipient);
+        assert_eq!(
+            transfer.duplicate_non_fungible(),
+            Some((&collection, &"#2#".to_string()))
+        );
+    }
+}
@@ -9,12 +9,20 @@ use crate::{
     app_data_db::statements::CREATE_ALL_MAIN_DB_TABLES_BATCH,
     database::{DataBase, DbError, SyncDataBase},
 };
-use once_cell::sync::OnceCell;
-use std::ops::{Deref, DerefMut};
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
 use types::{AppPath, Network, crypto::Key, repository::Repository};
 
 pub static MAINNET_DB: OnceCell<AppDataDb> = once_cell::sync::OnceCell::new();
 pub static STOKENET_DB: OnceCell<AppDataDb> = once_cell::sync::OnceCell::new();
+/// The cells of custom networks are leaked on first use so they live as long as the ones above
+static CUSTOM_NETWORK_DBS: Lazy<Mutex<BTreeMap<u8, &'static OnceCell<AppDataDb>>>> =
+    Lazy::new(Default::default);
 
 #[derive(Clone)]
 pub struct AppDataDb {
@@ -33,10 +41,20 @@ impl AppDataDb {
     pub async fn initialize(network: Network, key: Key<DataBase>) -> Result<Self, DbError> {
         let app_path = AppPath::get();
         let path = app_path.db_path_ref(network);
-        let db = DataBase::load(path, key).await?;
+        let db = DataBase::load(&path, key).await?;
         Ok(Self { db })
     }
 
+    /// Opens the database at `path` and creates any missing tables,
+    /// the database is not registered as the database of a network
+    pub async fn load_from_path(path: &Path, key: Key<DataBase>) -> Result<Self, DbError> {
+        let app_data_db = Self {
+            db: DataBase::load(path, key).await?,
+        };
+        app_data_db.create_tables_if_not_exist().await?;
+        Ok(app_data_db)
+    }
+
     pub async fn get_or_init(
         network: Network,
         key: Key<DataBase>,
@@ -51,6 +69,25 @@ impl AppDataDb {
         Self::get_static(network).get()
     }
 
+    /// The databases that are open, for every network
+    pub fn loaded() -> Vec<(Network, &'static Self)> {
+        let custom = CUSTOM_NETWORK_DBS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, cell)| (Network::Custom(*id), *cell))
+            .collect::<Vec<_>>();
+
+        [
+            (Network::Mainnet, &MAINNET_DB),
+            (Network::Stokenet, &STOKENET_DB),
+        ]
+        .into_iter()
+        .chain(custom)
+        .filter_map(|(network, cell)| Some((network, cell.get()?)))
+        .collect()
+    }
+
     pub fn exists(network: Network) -> bool {
         AppPath::get().db_path_ref(network).exists()
     }
@@ -59,6 +96,11 @@ impl AppDataDb {
         match network {
             Network::Mainnet => &MAINNET_DB,
             Network::Stokenet => &STOKENET_DB,
+            Network::Custom(id) => *CUSTOM_NETWORK_DBS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(id)
+                .or_insert_with(|| Box::leak(Box::new(OnceCell::new()))),
         }
     }
 }
@@ -1,9 +1,62 @@
-use super::statements::CREATE_ALL_MAIN_DB_TABLES_BATCH;
+use deps::*;
+
+use async_sqlite::rusqlite::params;
+use types::BalanceChangeId;
+
+use super::statements::{CREATE_ALL_MAIN_DB_TABLES_BATCH, accounts, balance_changes, transaction};
 use crate::database::DbError;
 use crate::AppDataDb;
 
 impl AppDataDb {
     pub async fn create_tables_if_not_exist(&self) -> Result<(), DbError> {
-        self.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).await
+        self.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).await?;
+        self.migrate_tables().await
+    }
+
+    /// Brings tables created by earlier versions up to date with the current schema
+    async fn migrate_tables(&self) -> Result<(), DbError> {
+        self.conn_mut(|conn| {
+            let mut has_column =
+                conn.prepare("SELECT 1 FROM pragma_table_info('transactions') WHERE name = ?")?;
+
+            if !has_column.exists(["status"])? {
+                conn.execute(transaction::ADD_STATUS_COLUMN_TO_TRANSACTIONS, [])?;
+            }
+            if !has_column.exists(["fee"])? {
+                conn.execute(transaction::ADD_FEE_COLUMN_TO_TRANSACTIONS, [])?;
+            }
+            drop(has_column);
+
+            let has_removed_nfids = conn
+                .prepare("SELECT 1 FROM pragma_table_info('balance_changes') WHERE name = ?")?
+                .exists(["removed_nfids"])?;
+
+            if !has_removed_nfids {
+                // In one transaction so the outdated changes can't be left behind with the new column
+                let tx = conn.transaction()?;
+                tx.execute(
+                    balance_changes::ADD_REMOVED_NFIDS_COLUMN_TO_BALANCE_CHANGES,
+                    [],
+                )?;
+                let deleted =
+                    tx.execute(balance_changes::DELETE_NON_FUNGIBLE_BALANCE_CHANGES, [])?;
+                if deleted > 0 {
+                    tx.execute(accounts::RESET_TRANSACTIONS_LAST_UPDATED, [])?;
+                }
+                tx.commit()?;
+            }
+
+            // The history is synced again from the start to replace the deleted balance changes
+            let tx = conn.transaction()?;
+            let deleted = tx.execute(
+                balance_changes::DELETE_BALANCE_CHANGES_WITH_OUTDATED_ID,
+                params![BalanceChangeId::LENGTH as i64],
+            )?;
+            if deleted > 0 {
+                tx.execute(accounts::RESET_TRANSACTIONS_LAST_UPDATED, [])?;
+            }
+            tx.commit()
+        })
+        .await
     }
 }
@@ -2,18 +2,19 @@ use deps::*;
 
 use std::collections::{BTreeSet, HashMap};
 
-use async_sqlite::rusqlite::{self, Row};
+use async_sqlite::rusqlite::{self, Row, params};
 use asynciter::{AsyncIterator, FromAsyncIterator, IntoAsyncIterator};
 use types::{
-    Account, BalanceChange, Ed25519PublicKey, Resource, Transaction, TransactionId,
-    address::AccountAddress,
+    Account, AddressBookEntry, BalanceChange, BalanceSnapshot, Currency, Decimal, Ed25519PublicKey,
+    PriceTable, Resource, TimeStamp, Transaction, TransactionId, TransactionStatus,
+    address::{AccountAddress, Address, ResourceAddress},
     assets::{FungibleAsset, NonFungibleAsset},
     crypto::HashedPassword,
 };
 
 use crate::DbError;
 
-use super::AppDataDb;
+use super::{AppDataDb, statements};
 
 impl AppDataDb {
     pub async fn get_db_password_hash(&self) -> Result<HashedPassword, DbError> {
@@ -164,6 +165,56 @@ impl AppDataDb {
             .await
     }
 
+    /// The prices in the currency saved by the previous price updates
+    pub async fn get_price_table(&self, currency: Currency) -> Result<PriceTable, DbError> {
+        let prices: Vec<(ResourceAddress, Decimal, i64)> = self
+            .query_map(
+                statements::prices::SELECT_PRICES_FOR_CURRENCY,
+                [currency.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .await?;
+
+        let mut price_table = PriceTable::new(currency);
+        for (resource_address, price, updated_at) in prices {
+            price_table.prices.insert(resource_address, price.0);
+            price_table.updated_at = price_table.updated_at.max(Some(updated_at));
+        }
+        Ok(price_table)
+    }
+
+    /// Ordered by state version
+    pub async fn get_balance_snapshots_for_account(
+        &self,
+        account_address: AccountAddress,
+    ) -> Result<Vec<BalanceSnapshot>, DbError> {
+        self.query_map(
+            statements::balance_snapshots::SELECT_BALANCE_SNAPSHOTS_FOR_ACCOUNT,
+            [account_address],
+            |row| {
+                Ok(BalanceSnapshot {
+                    account_address: row.get(0)?,
+                    resource_address: row.get(1)?,
+                    state_version: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    amount: row.get(4)?,
+                })
+            },
+        )
+        .await
+    }
+
+    /// Ordered by name
+    pub async fn get_address_book(&self) -> Result<Vec<AddressBookEntry>, DbError> {
+        self.query_map(statements::address_book::SELECT_ADDRESS_BOOK, [], |row| {
+            Ok(AddressBookEntry {
+                address: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })
+        .await
+    }
+
     pub async fn get_last_transaction_for_account(
         &self,
         account_address: AccountAddress,
@@ -204,7 +255,7 @@ impl AppDataDb {
         balance_changes: Vec<BalanceChange>,
     ) -> Option<Transaction> {
         self.query_row(
-            "SELECT * FROM transactions WHERE transaction_id = ?",
+            "SELECT * FROM transactions WHERE id = ?",
             [transaction_id],
             |row| Self::get_transaction_from_row(row, balance_changes),
         )
@@ -217,7 +268,7 @@ impl AppDataDb {
         account_address: AccountAddress,
     ) -> Result<HashMap<TransactionId, Vec<BalanceChange>>, DbError> {
         self.prepare_cached_statement(
-            "SELECT * FROM balance_changes WHERE account_address = ?",
+            "SELECT * FROM balance_changes WHERE account = ?",
             |cached_stmt| {
                 let balance_changes = cached_stmt
                     .query_map(
@@ -256,12 +307,100 @@ impl AppDataDb {
         Ok(transactions)
     }
 
+    /// Returns up to `limit` transactions that changed the balance of the account, newest first,
+    /// with only the balance changes of that account. If `resource` is set, only transactions
+    /// that changed the balance of that resource are included
+    pub async fn get_transactions_page_for_account(
+        &self,
+        account_address: AccountAddress,
+        resource: Option<ResourceAddress>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Transaction>, DbError> {
+        self.conn(move |conn| {
+            let mut transactions = conn
+                .prepare_cached(statements::transaction::SELECT_TRANSACTIONS_PAGE_FOR_ACCOUNT)?
+                .query_map(
+                    params![
+                        account_address,
+                        resource,
+                        limit as i64,
+                        offset as i64,
+                        TransactionStatus::Pending,
+                        account_address.checksum_double_slice(),
+                    ],
+                    |row| Self::get_transaction_from_row(row, Vec::new()),
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut balance_changes_stmt = conn
+                .prepare_cached("SELECT * FROM balance_changes WHERE tx_id = ? AND account = ?")?;
+
+            for transaction in &mut transactions {
+                transaction.balance_changes = balance_changes_stmt
+                    .query_map(
+                        params![transaction.id, account_address],
+                        Self::get_balance_change_from_row,
+                    )?
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+
+            Ok(transactions)
+        })
+        .await
+    }
+
+    /// Committed transactions between `from` and `to`, both included, oldest first.
+    /// With an account only the transactions that changed the balance of that account are returned,
+    /// otherwise the transactions of every account in the wallet
+    pub async fn get_transactions_in_range(
+        &self,
+        account_address: Option<AccountAddress>,
+        from: TimeStamp,
+        to: TimeStamp,
+    ) -> Result<Vec<Transaction>, DbError> {
+        self.conn(move |conn| {
+            let mut transactions = conn
+                .prepare_cached(statements::transaction::SELECT_TRANSACTIONS_IN_RANGE)?
+                .query_map(params![account_address, from, to], |row| {
+                    Self::get_transaction_from_row(row, Vec::new())
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            transactions.retain(|transaction| !transaction.is_pending());
+
+            let mut balance_changes_stmt =
+                conn.prepare_cached("SELECT * FROM balance_changes WHERE tx_id = ?")?;
+
+            for transaction in &mut transactions {
+                transaction.balance_changes = balance_changes_stmt
+                    .query_map(params![transaction.id], Self::get_balance_change_from_row)?
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+
+            Ok(transactions)
+        })
+        .await
+    }
+
+    /// Transactions that have been submitted but not yet been committed or rejected
+    pub async fn get_pending_transactions<T>(&self) -> Result<T, DbError>
+    where
+        T: FromIterator<Transaction> + Send + 'static,
+    {
+        self.query_map(
+            statements::transaction::SELECT_TRANSACTIONS_WITH_STATUS,
+            [TransactionStatus::Pending],
+            |row| Self::get_transaction_from_row(row, Vec::new()),
+        )
+        .await
+    }
+
     async fn get_balance_changes_for_transaction<T>(&self, transaction_id: TransactionId) -> T
     where
         T: FromIterator<BalanceChange> + Default + Send + 'static,
     {
         self.query_map(
-            "SELECT * FROM balance_changes WHERE transaction_id = ?",
+            "SELECT * FROM balance_changes WHERE tx_id = ?",
             [transaction_id],
             Self::get_balance_change_from_row,
         )
@@ -325,6 +464,8 @@ impl AppDataDb {
             state_version: row.get(3)?,
             balance_changes,
             message: row.get(4)?,
+            status: row.get(5)?,
+            fee: row.get(6)?,
         })
     }
 
@@ -337,6 +478,7 @@ impl AppDataDb {
             account: row.get(1)?,
             resource: row.get(2)?,
             nfts: row.get(3)?,
+            removed_nfts: row.get(6)?,
             amount: row.get(4)?,
         };
         Ok((transaction_id, balance_change))
@@ -358,6 +500,7 @@ impl AppDataDb {
             account: row.get(1)?,
             resource: row.get(2)?,
             nfts: row.get(3)?,
+            removed_nfts: row.get(6)?,
             amount: row.get(4)?,
         })
     }
@@ -39,3 +39,7 @@ pub const UPSERT_ACCOUNT: &'static str = "INSERT INTO
         balances_last_updated = excluded.balances_last_updated,
         transactions_last_updated = excluded.transactions_last_updated
 ";
+
+/// Makes the next history sync start from the first transaction of every account
+pub const RESET_TRANSACTIONS_LAST_UPDATED: &'static str =
+    "UPDATE accounts SET transactions_last_updated = NULL";
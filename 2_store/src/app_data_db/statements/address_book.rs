@@ -0,0 +1,24 @@
+pub const CREATE_TABLE_ADDRESS_BOOK: &'static str = "CREATE TABLE IF NOT EXISTS
+    address_book (
+        address BLOB NOT NULL PRIMARY KEY,
+        name TEXT NOT NULL
+    )
+";
+
+pub const UPSERT_ADDRESS_BOOK_ENTRY: &'static str = "INSERT INTO
+    address_book (
+        address,
+        name
+    )
+    VALUES (?, ?)
+    ON CONFLICT (address)
+    DO UPDATE SET
+        name = excluded.name
+";
+
+pub const SELECT_ADDRESS_BOOK: &'static str = "SELECT
+    address,
+    name
+    FROM address_book
+    ORDER BY name
+";
@@ -6,6 +6,7 @@ pub const CREATE_TABLE_BALANCE_CHANGES: &'static str = "CREATE TABLE IF NOT EXIS
         nfids BLOB,
         amount TEXT,
         tx_id BLOB NOT NULL,
+        removed_nfids BLOB,
         FOREIGN KEY(tx_id) REFERENCES transactions(id)
     )
 ";
@@ -16,7 +17,23 @@ pub const INSERT_BALANCE_CHANGE: &'static str = "INSERT INTO
         resource,
         nfids,
         amount,
-        tx_id
+        tx_id,
+        removed_nfids
     )
-    VALUES (?,?,?,?,?,?)
+    VALUES (?,?,?,?,?,?,?)
+    ON CONFLICT (id) DO NOTHING
 ";
+
+/// Added after the nfids column, which used to hold both the deposited and the withdrawn ids
+pub const ADD_REMOVED_NFIDS_COLUMN_TO_BALANCE_CHANGES: &'static str =
+    "ALTER TABLE balance_changes ADD COLUMN removed_nfids BLOB";
+
+/// The ids of non fungible changes stored before the removed_nfids column can't be told apart,
+/// these changes are synced again
+pub const DELETE_NON_FUNGIBLE_BALANCE_CHANGES: &'static str =
+    "DELETE FROM balance_changes WHERE nfids IS NOT NULL";
+
+/// Balance change ids used to hold only the short checksums of the transaction, account and resource.
+/// Takes the current id length as parameter
+pub const DELETE_BALANCE_CHANGES_WITH_OUTDATED_ID: &'static str =
+    "DELETE FROM balance_changes WHERE length(id) != ?";
@@ -0,0 +1,35 @@
+pub const CREATE_TABLE_BALANCE_SNAPSHOTS: &'static str = "CREATE TABLE IF NOT EXISTS
+    balance_snapshots (
+        account_address BLOB NOT NULL,
+        resource_address BLOB NOT NULL,
+        state_version INTEGER NOT NULL,
+        timestamp INTEGER NOT NULL,
+        amount TEXT NOT NULL,
+        PRIMARY KEY (account_address, resource_address, state_version)
+    )
+";
+
+pub const UPSERT_BALANCE_SNAPSHOT: &'static str = "INSERT INTO
+    balance_snapshots (
+        account_address,
+        resource_address,
+        state_version,
+        timestamp,
+        amount
+    )
+    VALUES (?, ?, ?, ?, ?)
+    ON CONFLICT (account_address, resource_address, state_version)
+    DO UPDATE SET
+        amount = excluded.amount
+";
+
+pub const SELECT_BALANCE_SNAPSHOTS_FOR_ACCOUNT: &'static str = "SELECT
+    account_address,
+    resource_address,
+    state_version,
+    timestamp,
+    amount
+    FROM balance_snapshots
+    WHERE account_address = ?
+    ORDER BY state_version
+";
@@ -21,3 +21,7 @@ pub const UPSERT_FUNGIBLE_ASSET: &'static str = "INSERT INTO
     DO UPDATE SET
         amount = excluded.amount
 ";
+
+pub const DELETE_FUNGIBLE_ASSET: &'static str = "DELETE FROM fungible_assets
+    WHERE account_address = ? AND resource_address = ?
+";
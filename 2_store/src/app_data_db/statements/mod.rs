@@ -1,19 +1,24 @@
 use deps::const_format;
 
 pub mod accounts;
+pub mod address_book;
 pub mod balance_changes;
+pub mod balance_snapshots;
 pub mod fungible_assets;
 pub mod non_fungible_assets;
 pub mod password_hash;
+pub mod prices;
 pub mod resources;
 pub mod transaction;
 
 use self::{
-    accounts::CREATE_TABLE_ACCOUNTS, balance_changes::CREATE_TABLE_BALANCE_CHANGES,
+    accounts::CREATE_TABLE_ACCOUNTS, address_book::CREATE_TABLE_ADDRESS_BOOK,
+    balance_changes::CREATE_TABLE_BALANCE_CHANGES,
+    balance_snapshots::CREATE_TABLE_BALANCE_SNAPSHOTS,
     fungible_assets::CREATE_TABLE_FUNGIBLE_ASSETS,
     non_fungible_assets::CREATE_TABLE_NON_FUNGIBLE_ASSETS,
-    password_hash::CREATE_TABLE_PASSWORD_HASH, resources::CREATE_TABLE_RESOURCES,
-    transaction::CREATE_TABLE_TRANSACTIONS,
+    password_hash::CREATE_TABLE_PASSWORD_HASH, prices::CREATE_TABLE_PRICES,
+    resources::CREATE_TABLE_RESOURCES, transaction::CREATE_TABLE_TRANSACTIONS,
 };
 
 pub const CREATE_ALL_MAIN_DB_TABLES_BATCH: &'static str = const_format::formatcp!(
@@ -25,12 +30,26 @@ pub const CREATE_ALL_MAIN_DB_TABLES_BATCH: &'static str = const_format::formatcp
     {CREATE_TABLE_NON_FUNGIBLE_ASSETS};
     {CREATE_TABLE_TRANSACTIONS};
     {CREATE_TABLE_BALANCE_CHANGES};
+    {CREATE_TABLE_PRICES};
+    {CREATE_TABLE_BALANCE_SNAPSHOTS};
+    {CREATE_TABLE_ADDRESS_BOOK};
     COMMIT;"
 );
 
 #[cfg(test)]
 mod test {
 
+    use std::str::FromStr;
+
+    use async_sqlite::rusqlite::{self, params};
+    use deps::*;
+    use proptest::prelude::*;
+    use types::{
+        BalanceChange, BalanceChangeId, TimeStamp, Transaction, TransactionId, TransactionStatus,
+        address::{AccountAddress, Address, ResourceAddress, TransactionAddress},
+        strategies::{account_address, address},
+    };
+
     use crate::database::test::{execute_batch_stmt, execute_stmt};
 
     use super::*;
@@ -90,4 +109,329 @@ mod test {
         println!("{:?}", result);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_create_table_prices() {
+        let result = execute_stmt(CREATE_TABLE_PRICES);
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_upsert_price_replaces_price_of_same_currency() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).unwrap();
+        for (currency, price, updated_at) in [("USD", "1", 1), ("EUR", "0.9", 1), ("USD", "2", 2)] {
+            conn.execute(
+                prices::UPSERT_PRICE,
+                params!["resource", currency, price, updated_at],
+            )
+            .unwrap();
+        }
+
+        let prices: Vec<(String, String, i64)> = conn
+            .prepare(prices::SELECT_PRICES_FOR_CURRENCY)
+            .unwrap()
+            .query_map(params!["USD"], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(prices, vec![("resource".to_string(), "2".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_create_table_address_book() {
+        let result = execute_stmt(CREATE_TABLE_ADDRESS_BOOK);
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_create_table_balance_snapshots() {
+        let result = execute_stmt(CREATE_TABLE_BALANCE_SNAPSHOTS);
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_balance_snapshots_are_ordered_by_state_version() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).unwrap();
+        for (account, state_version, amount) in [("a", 20, "2"), ("b", 10, "5"), ("a", 10, "1")] {
+            conn.execute(
+                balance_snapshots::UPSERT_BALANCE_SNAPSHOT,
+                params![account, "resource", state_version, state_version, amount],
+            )
+            .unwrap();
+        }
+
+        let amounts: Vec<(i64, String)> = conn
+            .prepare(balance_snapshots::SELECT_BALANCE_SNAPSHOTS_FOR_ACCOUNT)
+            .unwrap()
+            .query_map(params!["a"], |row| Ok((row.get(2)?, row.get(4)?)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(amounts, vec![(10, "1".to_string()), (20, "2".to_string())]);
+    }
+
+    #[test]
+    fn test_add_status_column_to_transactions() {
+        let stmt = const_format::formatcp!(
+            "CREATE TABLE transactions (
+                id BLOB NOT NULL PRIMARY KEY,
+                transaction_address BLOB NOT NULL,
+                timestamp BLOB NOT NULL,
+                state_version INTEGER NOT NULL,
+                message TEXT
+            );
+            {};",
+            transaction::ADD_STATUS_COLUMN_TO_TRANSACTIONS
+        );
+        let result = execute_batch_stmt(stmt);
+        println!("{:?}", result);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_delete_fungible_asset() {
+        let account = "account";
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).unwrap();
+        for (id, resource) in [("1", "kept"), ("2", "removed")] {
+            conn.execute(
+                fungible_assets::UPSERT_FUNGIBLE_ASSET,
+                params![id, resource, "1", account],
+            )
+            .unwrap();
+        }
+
+        conn.execute(
+            fungible_assets::DELETE_FUNGIBLE_ASSET,
+            params![account, "removed"],
+        )
+        .unwrap();
+
+        let resources: Vec<String> = conn
+            .prepare("SELECT resource_address FROM fungible_assets")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(resources, vec!["kept".to_string()]);
+    }
+
+    #[test]
+    fn test_transactions_page_includes_pending_transactions() {
+        let account = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap();
+        let other_account = AccountAddress::from_str(
+            "account_rdx12xn8d9ykr8pmch33v0q66vhpvt98afalg0tfgctrqenkly96sgdx5n",
+        )
+        .unwrap();
+        let xrd = ResourceAddress::from_str(
+            "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd",
+        )
+        .unwrap();
+        let transaction_address = |address: &str| TransactionAddress::from_str(address).unwrap();
+
+        let committed_address = transaction_address(
+            "txid_rdx1v5ma7z8u6tn8jwhypwlt5eyjzuq9ch4c5k8ejftsn0xkjpqzfjysvl0q6y",
+        );
+        let committed_id = TransactionId::new(&account, &committed_address);
+        let committed = Transaction::new(
+            TimeStamp::new(2024, 3, 7, 14, 40, 35),
+            100,
+            vec![BalanceChange::new(
+                committed_id.clone(),
+                account.clone(),
+                xrd.clone(),
+                None,
+                None,
+                Some("1".to_string()),
+            )],
+            &account,
+            committed_address,
+            None,
+            TransactionStatus::Success,
+        );
+        let pending = Transaction::pending(
+            &account,
+            transaction_address(
+                "txid_rdx1x4n6z9ex7xvnlwjw2ypc3cuqu0u5eu8gkhrttz8d7pnfqq7gk4qs7zzydq",
+            ),
+            None,
+        );
+        let other_pending = Transaction::pending(
+            &other_account,
+            transaction_address(
+                "txid_rdx1ktmdf3dmzwzc4dvy6xvtqk9gusuy5uyrlpfnm0pjq7hmrqtqkx2qjqc2hx",
+            ),
+            None,
+        );
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).unwrap();
+        for transaction in [&committed, &pending, &other_pending] {
+            conn.execute(
+                transaction::UPSERT_TRANSACTION,
+                params![
+                    transaction.id,
+                    transaction.transaction_address,
+                    transaction.timestamp,
+                    transaction.state_version as i64,
+                    transaction.message,
+                    transaction.status,
+                    transaction.fee,
+                ],
+            )
+            .unwrap();
+        }
+        let balance_change = &committed.balance_changes[0];
+        conn.execute(
+            balance_changes::INSERT_BALANCE_CHANGE,
+            params![
+                balance_change.id,
+                balance_change.account,
+                balance_change.resource,
+                balance_change.nfts,
+                balance_change.amount,
+                committed.id,
+                balance_change.removed_nfts,
+            ],
+        )
+        .unwrap();
+
+        let page = |resource: Option<&ResourceAddress>| {
+            conn.prepare(transaction::SELECT_TRANSACTIONS_PAGE_FOR_ACCOUNT)
+                .unwrap()
+                .query_map(
+                    params![
+                        account,
+                        resource,
+                        10,
+                        0,
+                        TransactionStatus::Pending,
+                        account.checksum_double_slice(),
+                    ],
+                    |row| row.get::<_, TransactionId>(0),
+                )
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(page(None), vec![pending.id.clone(), committed_id.clone()]);
+        assert_eq!(page(Some(&xrd)), vec![committed_id]);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_transaction_round_trip(
+            account in account_address(),
+            transaction_address in address::<TransactionAddress>(),
+            resources in proptest::collection::btree_set(address::<ResourceAddress>(), 1..4),
+            state_version in 0u64..i64::MAX as u64,
+            message in proptest::option::of("[a-zA-Z0-9 ]{0,30}"),
+        ) {
+            let id = TransactionId::new(&account, &transaction_address);
+            let balance_changes = resources
+                .into_iter()
+                .map(|resource| {
+                    let amount = Some("1".to_string());
+                    BalanceChange::new(id.clone(), account.clone(), resource, None, None, amount)
+                })
+                .collect::<Vec<_>>();
+            let transaction = Transaction::new(
+                TimeStamp::new(2024, 3, 7, 14, 40, 35),
+                state_version,
+                balance_changes,
+                &account,
+                transaction_address,
+                message,
+                TransactionStatus::Pending,
+            );
+
+            let conn = rusqlite::Connection::open_in_memory().unwrap();
+            conn.execute_batch(CREATE_ALL_MAIN_DB_TABLES_BATCH).unwrap();
+            conn.execute(
+                transaction::UPSERT_TRANSACTION,
+                params![
+                    transaction.id,
+                    transaction.transaction_address,
+                    transaction.timestamp,
+                    transaction.state_version as i64,
+                    transaction.message,
+                    transaction.status,
+                    transaction.fee,
+                ],
+            )
+            .unwrap();
+            for balance_change in &transaction.balance_changes {
+                // Inserting twice must not fail, history sync can see the same transaction again
+                for _ in 0..2 {
+                    conn.execute(
+                        balance_changes::INSERT_BALANCE_CHANGE,
+                        params![
+                            balance_change.id,
+                            balance_change.account,
+                            balance_change.resource,
+                            balance_change.nfts,
+                            balance_change.amount,
+                            transaction.id,
+                            balance_change.removed_nfts,
+                        ],
+                    )
+                    .unwrap();
+                }
+            }
+
+            let (id, address, timestamp, read_state_version, read_message, status) = conn
+                .query_row(
+                    transaction::SELECT_TRANSACTIONS_WITH_STATUS,
+                    params![TransactionStatus::Pending],
+                    |row| {
+                        Ok((
+                            row.get::<_, TransactionId>(0)?,
+                            row.get::<_, TransactionAddress>(1)?,
+                            row.get::<_, TimeStamp>(2)?,
+                            row.get::<_, i64>(3)?,
+                            row.get::<_, Option<String>>(4)?,
+                            row.get::<_, TransactionStatus>(5)?,
+                        ))
+                    },
+                )
+                .unwrap();
+
+            prop_assert_eq!(id, transaction.id.clone());
+            prop_assert_eq!(address, transaction.transaction_address.clone());
+            prop_assert_eq!(timestamp, transaction.timestamp.clone());
+            prop_assert_eq!(read_state_version as u64, transaction.state_version);
+            prop_assert_eq!(read_message, transaction.message.clone());
+            prop_assert_eq!(status, TransactionStatus::Pending);
+
+            let balance_change_ids: Vec<BalanceChangeId> = conn
+                .prepare("SELECT id FROM balance_changes WHERE tx_id = ?")
+                .unwrap()
+                .query_map(params![transaction.id], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let expected = transaction
+                .balance_changes
+                .iter()
+                .map(|balance_change| balance_change.id.clone())
+                .collect::<Vec<_>>();
+
+            prop_assert_eq!(balance_change_ids.len(), expected.len());
+            prop_assert!(expected.iter().all(|id| balance_change_ids.contains(id)));
+        }
+    }
 }
@@ -20,3 +20,7 @@ pub const UPSERT_NON_FUNGIBLE_ASSET: &'static str = "INSERT INTO
     DO UPDATE SET
         nfts = excluded.nfts
 ";
+
+pub const DELETE_NON_FUNGIBLE_ASSET: &'static str = "DELETE FROM non_fungible_assets
+    WHERE account_address = ? AND resource_address = ?
+";
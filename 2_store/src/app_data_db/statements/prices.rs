@@ -0,0 +1,26 @@
+pub const CREATE_TABLE_PRICES: &'static str = "CREATE TABLE IF NOT EXISTS
+    prices (
+        resource_address BLOB NOT NULL,
+        currency TEXT NOT NULL,
+        price TEXT NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (resource_address, currency)
+    )
+";
+
+pub const UPSERT_PRICE: &'static str = "INSERT INTO
+    prices (
+        resource_address,
+        currency,
+        price,
+        updated_at
+    )
+    VALUES (?, ?, ?, ?)
+    ON CONFLICT (resource_address, currency)
+    DO UPDATE SET
+        price = excluded.price,
+        updated_at = excluded.updated_at
+";
+
+pub const SELECT_PRICES_FOR_CURRENCY: &'static str =
+    "SELECT resource_address, price, updated_at FROM prices WHERE currency = ?";
@@ -4,20 +4,76 @@ pub const CREATE_TABLE_TRANSACTIONS: &'static str = "CREATE TABLE IF NOT EXISTS
         transaction_address BLOB NOT NULL,
         timestamp BLOB NOT NULL,
         state_version INTEGER NOT NULL,
-        message TEXT
+        message TEXT,
+        status INTEGER NOT NULL DEFAULT 1,
+        fee TEXT
     )
 ";
 
+/// Databases created before the status column was introduced only hold committed transactions
+pub const ADD_STATUS_COLUMN_TO_TRANSACTIONS: &'static str =
+    "ALTER TABLE transactions ADD COLUMN status INTEGER NOT NULL DEFAULT 1";
+
+pub const ADD_FEE_COLUMN_TO_TRANSACTIONS: &'static str =
+    "ALTER TABLE transactions ADD COLUMN fee TEXT";
+
 pub const UPSERT_TRANSACTION: &'static str = "INSERT INTO
     transactions (
         id,
         transaction_address,
         timestamp,
         state_version,
-        status
+        message,
+        status,
+        fee
     )
-    VALUES (?, ?, ?, ?, ?)
+    VALUES (?, ?, ?, ?, ?, ?, ?)
     ON CONFLICT (id)
     DO UPDATE SET 
-        status = excluded.status
+        timestamp = excluded.timestamp,
+        state_version = excluded.state_version,
+        status = excluded.status,
+        fee = COALESCE(excluded.fee, fee)
+";
+
+pub const UPDATE_TRANSACTION_STATUS: &'static str = "UPDATE transactions
+    SET
+        status = ?,
+        state_version = ?
+    WHERE transaction_address = ?
+";
+
+pub const SELECT_TRANSACTIONS_WITH_STATUS: &'static str =
+    "SELECT * FROM transactions WHERE status = ?";
+
+/// Parameters are the account, an optional resource to filter on, limit, offset, the pending status
+/// and the double checksum of the account.
+/// Pending transactions have no balance changes yet, they are found by the account part of their id
+/// and listed first when no resource is filtered on
+pub const SELECT_TRANSACTIONS_PAGE_FOR_ACCOUNT: &'static str = "SELECT * FROM (
+        SELECT transactions.*
+        FROM transactions
+        JOIN balance_changes ON balance_changes.tx_id = transactions.id
+        WHERE balance_changes.account = ?1
+            AND (?2 IS NULL OR balance_changes.resource = ?2)
+        UNION
+        SELECT *
+        FROM transactions
+        WHERE ?2 IS NULL
+            AND status = ?5
+            AND substr(id, 1, length(?6)) = ?6
+    )
+    ORDER BY status = ?5 DESC, state_version DESC
+    LIMIT ?3 OFFSET ?4
+";
+
+/// Parameters are an optional account and the first and last timestamp to include.
+/// Without an account, the transactions of all accounts are returned
+pub const SELECT_TRANSACTIONS_IN_RANGE: &'static str = "SELECT * FROM transactions
+    WHERE (?1 IS NULL OR EXISTS (
+            SELECT 1 FROM balance_changes
+            WHERE balance_changes.tx_id = transactions.id AND balance_changes.account = ?1
+        ))
+        AND timestamp BETWEEN ?2 AND ?3
+    ORDER BY state_version ASC
 ";
@@ -4,14 +4,31 @@ use super::statements::*;
 use crate::DbError;
 use async_sqlite::rusqlite::params;
 use types::{
-    Account, Resource, Transaction,
-    address::AccountAddress,
+    Account, AddressBookEntry, BalanceSnapshot, Decimal, PriceTable, Resource, Transaction,
+    TransactionStatus,
+    address::{AccountAddress, ResourceAddress, TransactionAddress},
     assets::{FungibleAsset, NonFungibleAsset},
     crypto::HashedPassword,
 };
 
 use super::AppDataDb;
 
+/// The rows an accounts update changes, saved together by [`AppDataDb::save_accounts_update`]
+#[derive(Debug, Default)]
+pub struct AccountsUpdateRows {
+    pub transactions: Vec<Transaction>,
+    /// `(account, resource)` pairs of the assets the accounts no longer hold,
+    /// the ids of a non-fungible asset are deleted with it
+    pub removed_fungibles: Vec<(AccountAddress, ResourceAddress)>,
+    pub removed_non_fungibles: Vec<(AccountAddress, ResourceAddress)>,
+    pub accounts: Vec<Account>,
+    pub resources: Vec<Resource>,
+    pub fungibles: Vec<(AccountAddress, FungibleAsset)>,
+    /// Each asset holds every id the account holds, removed ids are left out
+    pub non_fungibles: Vec<(AccountAddress, NonFungibleAsset)>,
+    pub snapshots: Vec<BalanceSnapshot>,
+}
+
 impl AppDataDb {
     pub async fn upsert_password_hash(&self, hash: HashedPassword) -> Result<(), DbError> {
         self.transaction(password_hash::UPSERT_PASSWORD_HASH, move |cached_stmt| {
@@ -85,6 +102,43 @@ impl AppDataDb {
         .await
     }
 
+    pub async fn upsert_address_book_entries<
+        Entries: IntoIterator<Item = AddressBookEntry> + Send + 'static,
+    >(
+        &self,
+        entries: Entries,
+    ) -> Result<(), DbError> {
+        self.transaction(
+            address_book::UPSERT_ADDRESS_BOOK_ENTRY,
+            move |cached_stmt| {
+                for entry in entries {
+                    cached_stmt.execute(params![entry.address, entry.name])?;
+                }
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Prices of resources that are not in the table are kept
+    pub async fn upsert_prices(&self, price_table: PriceTable) -> Result<(), DbError> {
+        let updated_at = price_table.updated_at.unwrap_or_default();
+        let currency = price_table.currency.to_string();
+
+        self.transaction(prices::UPSERT_PRICE, move |cached_stmt| {
+            for (resource_address, price) in price_table.prices {
+                cached_stmt.execute(params![
+                    resource_address,
+                    currency,
+                    Decimal(price),
+                    updated_at
+                ])?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn upsert_fungible_assets_for_account<
         Fungibles: IntoIterator<Item = FungibleAsset> + Send + 'static,
     >(
@@ -130,6 +184,116 @@ impl AppDataDb {
         .await
     }
 
+    /// Saves everything in one transaction, so a failed save leaves the accounts as they were
+    /// and an account is never stored with a cursor past transactions that are not stored
+    pub async fn save_accounts_update(&self, rows: AccountsUpdateRows) -> Result<(), DbError> {
+        self.conn_mut(|conn| {
+            let tx = conn.transaction()?;
+
+            {
+                let mut transaction_stmt = tx.prepare_cached(transaction::UPSERT_TRANSACTION)?;
+                let mut balance_changes_stmt =
+                    tx.prepare_cached(balance_changes::INSERT_BALANCE_CHANGE)?;
+                for transaction in rows.transactions {
+                    transaction_stmt.execute(params![
+                        transaction.id,
+                        transaction.transaction_address,
+                        transaction.timestamp,
+                        transaction.state_version as i64,
+                        transaction.message,
+                        transaction.status,
+                        transaction.fee,
+                    ])?;
+
+                    for balance_change in &transaction.balance_changes {
+                        balance_changes_stmt.execute(params![
+                            balance_change.id,
+                            balance_change.account,
+                            balance_change.resource,
+                            balance_change.nfts,
+                            balance_change.amount,
+                            transaction.id,
+                            balance_change.removed_nfts,
+                        ])?;
+                    }
+                }
+
+                let mut stmt = tx.prepare_cached(fungible_assets::DELETE_FUNGIBLE_ASSET)?;
+                for (account_address, resource_address) in rows.removed_fungibles {
+                    stmt.execute(params![account_address, resource_address])?;
+                }
+
+                let mut stmt = tx.prepare_cached(non_fungible_assets::DELETE_NON_FUNGIBLE_ASSET)?;
+                for (account_address, resource_address) in rows.removed_non_fungibles {
+                    stmt.execute(params![account_address, resource_address])?;
+                }
+
+                let mut stmt = tx.prepare_cached(accounts::UPSERT_ACCOUNT)?;
+                for account in rows.accounts {
+                    stmt.execute(params![
+                        account.address,
+                        account.id as i64,
+                        account.name,
+                        account.network,
+                        account.derivation_path,
+                        account.public_key.0,
+                        account.hidden,
+                        account.settings,
+                        account.balances_last_updated,
+                        account.transactions_last_updated,
+                    ])?;
+                }
+
+                let mut stmt = tx.prepare_cached(resources::UPSERT_RESOURCE)?;
+                for resource in rows.resources {
+                    stmt.execute(params![
+                        resource.address,
+                        resource.name,
+                        resource.symbol,
+                        resource.description,
+                        resource.current_supply,
+                        resource.divisibility,
+                        resource.tags,
+                    ])?;
+                }
+
+                let mut stmt = tx.prepare_cached(fungible_assets::UPSERT_FUNGIBLE_ASSET)?;
+                for (account_address, fungible_asset) in rows.fungibles {
+                    stmt.execute(params![
+                        fungible_asset.id,
+                        fungible_asset.resource_address,
+                        fungible_asset.amount,
+                        account_address,
+                    ])?;
+                }
+
+                let mut stmt = tx.prepare_cached(non_fungible_assets::UPSERT_NON_FUNGIBLE_ASSET)?;
+                for (account_address, non_fungible_asset) in rows.non_fungibles {
+                    stmt.execute(params![
+                        non_fungible_asset.id,
+                        non_fungible_asset.resource_address,
+                        non_fungible_asset.nfids,
+                        account_address,
+                    ])?;
+                }
+
+                let mut stmt = tx.prepare_cached(balance_snapshots::UPSERT_BALANCE_SNAPSHOT)?;
+                for snapshot in rows.snapshots {
+                    stmt.execute(params![
+                        snapshot.account_address,
+                        snapshot.resource_address,
+                        snapshot.state_version,
+                        snapshot.timestamp,
+                        snapshot.amount,
+                    ])?;
+                }
+            }
+
+            tx.commit().map_err(|err| err.into())
+        })
+        .await
+    }
+
     // pub async fn upsert_non_fungible_assets_for_account_v2<'a, T>(
     //     &self,
     //     account_address: AccountAddress,
@@ -156,10 +320,7 @@ impl AppDataDb {
     //     Ok(())
     // }
 
-    pub async fn update_transaction_status(
-        &self,
-        transactions: Vec<Transaction>,
-    ) -> Result<(), DbError> {
+    pub async fn upsert_transactions(&self, transactions: Vec<Transaction>) -> Result<(), DbError> {
         self.transaction(transaction::UPSERT_TRANSACTION, move |cached_stmt| {
             for transaction in transactions {
                 cached_stmt.execute(params![
@@ -167,6 +328,9 @@ impl AppDataDb {
                     transaction.transaction_address,
                     transaction.timestamp,
                     transaction.state_version as i64,
+                    transaction.message,
+                    transaction.status,
+                    transaction.fee,
                 ])?;
             }
             Ok(())
@@ -174,6 +338,19 @@ impl AppDataDb {
         .await
     }
 
+    pub async fn update_transaction_status(
+        &self,
+        transaction_address: TransactionAddress,
+        status: TransactionStatus,
+        state_version: u64,
+    ) -> Result<(), DbError> {
+        self.transaction(transaction::UPDATE_TRANSACTION_STATUS, move |cached_stmt| {
+            cached_stmt.execute(params![status, state_version as i64, transaction_address])?;
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn insert_transactions(&self, transactions: Vec<Transaction>) -> Result<(), DbError> {
         self.conn_mut(|conn| {
             let tx = conn.transaction()?;
@@ -189,6 +366,9 @@ impl AppDataDb {
                         transaction.transaction_address,
                         transaction.timestamp,
                         transaction.state_version as i64,
+                        transaction.message,
+                        transaction.status,
+                        transaction.fee,
                     ])?;
 
                     for balance_change in &transaction.balance_changes {
@@ -198,7 +378,8 @@ impl AppDataDb {
                             balance_change.resource,
                             balance_change.nfts,
                             balance_change.amount,
-                            transaction.transaction_address,
+                            transaction.id,
+                            balance_change.removed_nfts,
                         ])?;
                     }
                 }
@@ -9,7 +9,7 @@ use debug_print::debug_println;
 use thiserror::Error;
 use types::{
     AppPathError,
-    crypto::{Key, KeyType},
+    crypto::{KdfParams, Key, KeyType},
 };
 
 use crate::sqlite_key::SqliteKey;
@@ -73,6 +73,22 @@ impl DataBase {
             .await
     }
 
+    /// Re-encrypts the database with the new key, the connection keeps working with the new key.
+    /// Other connections to the same file can no longer read it
+    pub async fn rekey(&self, key: Key<DataBase>) -> Result<(), DbError> {
+        self.conn(move |conn| conn.pragma_update(None, "rekey", SqliteKey::from_key(&key)))
+            .await
+    }
+
+    /// Writes a copy of the database to `path` with `VACUUM INTO` through this connection,
+    /// encrypted with the same key. Writes on the connection wait until the copy is complete,
+    /// so the copy is always consistent. Fails if `path` exists
+    pub async fn back_up_into(&self, path: &Path) -> Result<(), DbError> {
+        let path = path.to_string_lossy().into_owned();
+        self.conn(move |conn| conn.execute("VACUUM INTO ?1", [path]).map(|_| ()))
+            .await
+    }
+
     pub(crate) async fn conn<T, F>(&self, f: F) -> Result<T, DbError>
     where
         T: Send + 'static,
@@ -181,6 +197,11 @@ impl Debug for DataBase {
 impl KeyType for DataBase {
     const KEY_LENGTH: usize = 32;
     const ITERATIONS: std::num::NonZeroU32 = NonZeroU32::new(200000).unwrap();
+    const KDF: KdfParams = KdfParams::Argon2id {
+        memory_kib: 64 * 1024,
+        iterations: 3,
+        parallelism: 1,
+    };
 }
 
 #[cfg(test)]
@@ -255,6 +276,70 @@ pub mod test {
             .await
             .expect("Unable to create table, fungibles");
     }
+
+    #[tokio::test]
+    async fn test_rekey_database() {
+        let path = Path::new("./mock_rekey.db");
+        File::create(path).unwrap();
+
+        let old_key = KeySaltPair::new(Password::from("SomePasswordtype").as_str())
+            .unwrap()
+            .into_key();
+        let new_key = KeySaltPair::new(Password::from("SomeOtherPassword").as_str())
+            .unwrap()
+            .into_key();
+        {
+            let db = DataBase::load(path, old_key.clone()).await.unwrap();
+            db.conn(|conn| conn.execute(accounts::CREATE_TABLE_ACCOUNTS, []))
+                .await
+                .unwrap();
+            db.rekey(new_key.clone())
+                .await
+                .expect("Failed to rekey database");
+        }
+
+        let with_old_key = DataBase::load(path, old_key).await.unwrap();
+        let old_key_result = with_old_key
+            .conn(|conn| conn.execute(fungible_assets::CREATE_TABLE_FUNGIBLE_ASSETS, []))
+            .await;
+        let with_new_key = DataBase::load(path, new_key).await.unwrap();
+        let new_key_result = with_new_key
+            .conn(|conn| conn.execute(fungible_assets::CREATE_TABLE_FUNGIBLE_ASSETS, []))
+            .await;
+        std::fs::remove_file(path).ok();
+
+        assert!(old_key_result.is_err());
+        assert!(new_key_result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_back_up_into() {
+        let path = Path::new("./mock_back_up.db");
+        let backup = Path::new("./mock_back_up.db.bak");
+        File::create(path).unwrap();
+
+        let key = KeySaltPair::new(Password::from("SomePasswordtype").as_str())
+            .unwrap()
+            .into_key();
+        {
+            let db = DataBase::load(path, key.clone()).await.unwrap();
+            db.conn(|conn| conn.execute(accounts::CREATE_TABLE_ACCOUNTS, []))
+                .await
+                .unwrap();
+            db.back_up_into(backup)
+                .await
+                .expect("Failed to back up database");
+        }
+
+        let restored = DataBase::load(backup, key).await.unwrap();
+        let accounts = restored
+            .conn(|conn| conn.query_row("SELECT COUNT(*) FROM accounts", [], |row| row.get(0)))
+            .await;
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(backup).ok();
+
+        assert_eq!(accounts.unwrap(), 0usize);
+    }
 }
 
 pub struct SyncDataBase {
@@ -10,9 +10,14 @@ pub mod read;
 pub mod statements;
 pub mod update;
 
-use std::{collections::HashMap, ops::Deref};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
 
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use types::{AppPath, Network, address::ResourceAddress, crypto::Key};
 
 use crate::{
@@ -22,6 +27,9 @@ use crate::{
 
 pub static MAINNET_ICONCACHE: OnceCell<IconsDb> = OnceCell::new();
 pub static STOKENET_ICONCACHE: OnceCell<IconsDb> = OnceCell::new();
+/// The cells of custom networks are leaked on first use so they live as long as the ones above
+static CUSTOM_NETWORK_ICONCACHES: Lazy<Mutex<BTreeMap<u8, &'static OnceCell<IconsDb>>>> =
+    Lazy::new(Default::default);
 
 pub struct IconsDb {
     db: DataBase,
@@ -40,10 +48,20 @@ impl IconsDb {
     pub async fn initialize(network: Network, key: Key<DataBase>) -> Result<Self, DbError> {
         let app_path = AppPath::get();
         let path = app_path.icon_cache_ref(network);
-        let db = DataBase::load(path, key).await?;
+        let db = DataBase::load(&path, key).await?;
         Ok(Self { db })
     }
 
+    /// Opens the icon cache at `path` and creates any missing tables,
+    /// the database is not registered as the icon cache of a network
+    pub async fn load_from_path(path: &Path, key: Key<DataBase>) -> Result<Self, DbError> {
+        let icons_db = Self {
+            db: DataBase::load(path, key).await?,
+        };
+        icons_db.create_tables_if_not_exist().await?;
+        Ok(icons_db)
+    }
+
     pub async fn get_or_init(
         network: Network,
         key: Key<DataBase>,
@@ -55,16 +73,37 @@ impl IconsDb {
     }
 
     pub fn get(network: Network) -> Option<&'static Self> {
-        match network {
-            Network::Mainnet => MAINNET_ICONCACHE.get(),
-            Network::Stokenet => STOKENET_ICONCACHE.get(),
-        }
+        Self::get_static(network).get()
+    }
+
+    /// The databases that are open, for every network
+    pub fn loaded() -> Vec<(Network, &'static Self)> {
+        let custom = CUSTOM_NETWORK_ICONCACHES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, cell)| (Network::Custom(*id), *cell))
+            .collect::<Vec<_>>();
+
+        [
+            (Network::Mainnet, &MAINNET_ICONCACHE),
+            (Network::Stokenet, &STOKENET_ICONCACHE),
+        ]
+        .into_iter()
+        .chain(custom)
+        .filter_map(|(network, cell)| Some((network, cell.get()?)))
+        .collect()
     }
 
     fn get_static(network: Network) -> &'static OnceCell<IconsDb> {
         match network {
             Network::Mainnet => &MAINNET_ICONCACHE,
             Network::Stokenet => &STOKENET_ICONCACHE,
+            Network::Custom(id) => *CUSTOM_NETWORK_ICONCACHES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(id)
+                .or_insert_with(|| Box::leak(Box::new(OnceCell::new()))),
         }
     }
 }
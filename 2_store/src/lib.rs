@@ -4,7 +4,7 @@ mod icons_db;
 mod sqlite_key;
 mod sync_app_data_db;
 
-pub use app_data_db::AppDataDb;
+pub use app_data_db::{AppDataDb, update::AccountsUpdateRows};
 pub use database::DataBase;
 pub use database::DbError;
 pub use icons_db::{IconsDb, SyncIconsDb};
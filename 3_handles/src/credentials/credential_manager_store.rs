@@ -0,0 +1,117 @@
+use deps::*;
+
+use ports::credential_store::{CredentialStore, Error};
+use windows::{
+    Win32::{
+        Foundation::{E_POINTER, ERROR_NOT_FOUND},
+        Security::Credentials::{
+            CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC, CREDENTIALW, CredDeleteW, CredFree,
+            CredReadW, CredWriteW,
+        },
+    },
+    core::{PCWSTR, PWSTR},
+};
+use zeroize::Zeroize;
+
+use super::{ENCRYPTED_MNEMONIC_TARGET_NAME, SALT_TARGET_NAME};
+
+/// Keeps the blobs as generic credentials in the Windows Credential Manager
+#[derive(Debug, Clone, Default)]
+pub struct CredentialManagerStore;
+
+impl CredentialManagerStore {
+    const SALT_CREDENTIAL_NAME: &'static str = "l4h4c5aPo1ULu3dLQjCYrq2TJNY3wZiYwGL4jTOZ1Lk=";
+    const ENCRYPTED_MNEMONIC_CREDENTIAL_NAME: &'static str =
+        "Bk3oMH8tphurhYE3b/U/a4k03oefVrATNCFvWKz6FxA=";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The credentials of the wallet keep the names they have always been stored under,
+    /// as null terminated UTF-16
+    fn wide(target_name: &str) -> Vec<u16> {
+        let target_name = match target_name {
+            SALT_TARGET_NAME => Self::SALT_CREDENTIAL_NAME,
+            ENCRYPTED_MNEMONIC_TARGET_NAME => Self::ENCRYPTED_MNEMONIC_CREDENTIAL_NAME,
+            other => other,
+        };
+        let mut target_name = target_name.encode_utf16().collect::<Vec<u16>>();
+        target_name.push(0);
+        target_name
+    }
+
+    fn error(target_name: &str, err: windows::core::Error) -> Error {
+        if err.code() == ERROR_NOT_FOUND.to_hresult() {
+            Error::NotFound(target_name.to_string())
+        } else {
+            Error::Backend(err.to_string())
+        }
+    }
+}
+
+impl CredentialStore for CredentialManagerStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        let wide_target_name = Self::wide(target_name);
+        let mut cred_ptr: *mut CREDENTIALW = std::ptr::null_mut();
+
+        unsafe {
+            CredReadW(
+                PCWSTR(wide_target_name.as_ptr()),
+                CRED_TYPE_GENERIC,
+                None,
+                &mut cred_ptr,
+            )
+            .and_then(|_| {
+                if !cred_ptr.is_null() {
+                    let cred = &*cred_ptr;
+                    let blob = std::slice::from_raw_parts(
+                        cred.CredentialBlob,
+                        cred.CredentialBlobSize as usize / 2,
+                    )
+                    .to_vec();
+
+                    CredFree(cred_ptr as *mut _);
+                    Ok(blob)
+                } else {
+                    Err(windows::core::Error::new(
+                        E_POINTER,
+                        "Null pointer received for credentials",
+                    ))
+                }
+            })
+        }
+        .map_err(|err| Self::error(target_name, err))
+    }
+
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        let mut wide_target_name = Self::wide(target_name);
+        // The stored size has always been twice the blob, the second half is padding
+        // that reading skips, kept so credentials stored by earlier versions still read back
+        let mut padded_blob = blob.to_vec();
+        padded_blob.resize(blob.len() * 2, 0);
+
+        let result = unsafe {
+            let credentials = CREDENTIALW {
+                Type: CRED_TYPE_GENERIC,
+                TargetName: PWSTR(wide_target_name.as_mut_ptr()),
+                CredentialBlob: padded_blob.as_mut_ptr(),
+                CredentialBlobSize: padded_blob.len() as u32,
+                Persist: CRED_PERSIST_LOCAL_MACHINE,
+                ..Default::default()
+            };
+
+            CredWriteW(&credentials, 0)
+        };
+        padded_blob.zeroize();
+
+        result.map_err(|err| Self::error(target_name, err))
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        let wide_target_name = Self::wide(target_name);
+
+        unsafe { CredDeleteW(PCWSTR(wide_target_name.as_ptr()), CRED_TYPE_GENERIC, None) }
+            .map_err(|err| Self::error(target_name, err))
+    }
+}
@@ -0,0 +1,53 @@
+use deps::*;
+
+use std::{io::ErrorKind, path::PathBuf};
+
+use ports::credential_store::{CredentialStore, Error};
+
+/// Keeps every blob in a file named after its target in one directory
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    directory: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self, target_name: &str) -> PathBuf {
+        self.directory.join(target_name)
+    }
+
+    fn not_found(target_name: &str, err: std::io::Error) -> Error {
+        match err.kind() {
+            ErrorKind::NotFound => Error::NotFound(target_name.to_string()),
+            _ => Error::Io(err),
+        }
+    }
+}
+
+impl CredentialStore for FileStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        std::fs::read(self.path(target_name)).map_err(|err| Self::not_found(target_name, err))
+    }
+
+    /// Written to a temporary file first, so a crash never leaves half a blob behind
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        let path = self.path(target_name);
+        let tmp_path = self.path(&format!("{target_name}.tmp"));
+
+        std::fs::write(&tmp_path, blob)?;
+        std::fs::rename(&tmp_path, &path).map_err(|err| {
+            std::fs::remove_file(&tmp_path).ok();
+            Error::Io(err)
+        })
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        std::fs::remove_file(self.path(target_name))
+            .map_err(|err| Self::not_found(target_name, err))
+    }
+}
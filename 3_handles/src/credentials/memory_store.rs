@@ -0,0 +1,69 @@
+use deps::*;
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use ports::credential_store::{CredentialStore, Error};
+use zeroize::Zeroize;
+
+/// Keeps the blobs in memory until the store is dropped, clones share the blobs.
+/// Only built for tests, nothing is persisted
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    blobs: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for MemoryStore {
+    /// Leaves the blobs out
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryStore").finish_non_exhaustive()
+    }
+}
+
+impl CredentialStore for MemoryStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        self.blobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(target_name)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(target_name.to_string()))
+    }
+
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        let mut blobs = self.blobs.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(mut previous) = blobs.insert(target_name.to_string(), blob.to_vec()) {
+            previous.zeroize();
+        }
+        Ok(())
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        let mut blob = self
+            .blobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(target_name)
+            .ok_or_else(|| Error::NotFound(target_name.to_string()))?;
+        blob.zeroize();
+        Ok(())
+    }
+}
+
+impl Drop for MemoryStore {
+    fn drop(&mut self) {
+        if let Some(blobs) = Arc::get_mut(&mut self.blobs) {
+            let blobs = blobs.get_mut().unwrap_or_else(PoisonError::into_inner);
+            blobs.values_mut().for_each(|blob| blob.zeroize());
+        }
+    }
+}
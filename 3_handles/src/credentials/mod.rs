@@ -1,20 +1,238 @@
+#[cfg(windows)]
+pub mod credential_manager_store;
 mod delete;
+pub mod file_store;
 mod get_credentials;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod memory_store;
+#[cfg(target_os = "linux")]
+pub mod secret_service_store;
 mod store_credentials;
 #[cfg(test)]
 mod tests;
 
+use deps::*;
+
+use std::sync::{PoisonError, RwLock};
+
+use once_cell::sync::Lazy;
+use ports::credential_store::{CredentialStore, Error};
+use types::{AppPath, CredentialBackend, UnwrapUnreachable};
+use zeroize::Zeroize;
+
 pub use delete::{delete_encrypted_mnemonic, delete_salt};
 pub use get_credentials::{get_db_encryption_salt, get_encrypted_mnemonic};
 pub use store_credentials::{store_db_encryption_salt, store_encrypted_mnemonic};
 
 #[cfg(windows)]
-pub(crate) const SALT_TARGET_NAME: &'static str = "l4h4c5aPo1ULu3dLQjCYrq2TJNY3wZiYwGL4jTOZ1Lk=";
-#[cfg(windows)]
-pub(crate) const ENCRYPTED_MNEMONIC_TARGET_NAME: &'static str =
-    "Bk3oMH8tphurhYE3b/U/a4k03oefVrATNCFvWKz6FxA=";
+use self::credential_manager_store::CredentialManagerStore;
+use self::file_store::FileStore;
+#[cfg(any(test, feature = "test-utils"))]
+use self::memory_store::MemoryStore;
+#[cfg(target_os = "linux")]
+use self::secret_service_store::SecretServiceStore;
 
-#[cfg(unix)]
 pub(crate) const SALT_TARGET_NAME: &'static str = "db_salt.json";
-#[cfg(unix)]
 pub(crate) const ENCRYPTED_MNEMONIC_TARGET_NAME: &'static str = "mnemonic.json";
+
+/// Every credential of the wallet, in the order they are copied
+const TARGET_NAMES: [&'static str; 2] = [SALT_TARGET_NAME, ENCRYPTED_MNEMONIC_TARGET_NAME];
+
+static CREDENTIAL_STORE: Lazy<RwLock<ConfiguredCredentialStore>> = Lazy::new(|| {
+    RwLock::new(
+        ConfiguredCredentialStore::new(CredentialBackend::default())
+            .unwrap_unreachable("The default credential backend is supported on every platform"),
+    )
+});
+
+/// The credential store set in the settings
+#[derive(Debug, Clone)]
+pub enum ConfiguredCredentialStore {
+    File(FileStore),
+    #[cfg(target_os = "linux")]
+    SecretService(SecretServiceStore),
+    #[cfg(windows)]
+    CredentialManager(CredentialManagerStore),
+    #[cfg(any(test, feature = "test-utils"))]
+    Memory(MemoryStore),
+}
+
+impl ConfiguredCredentialStore {
+    /// Fails if the backend is not available on this platform
+    pub fn new(backend: CredentialBackend) -> Result<Self, Error> {
+        match backend {
+            CredentialBackend::File => Ok(Self::File(FileStore::new(
+                AppPath::get().config_directory(),
+            ))),
+            #[cfg(target_os = "linux")]
+            CredentialBackend::SecretService => Ok(Self::SecretService(SecretServiceStore::new())),
+            #[cfg(windows)]
+            CredentialBackend::CredentialManager => {
+                Ok(Self::CredentialManager(CredentialManagerStore::new()))
+            }
+            backend => Err(Error::Unsupported(backend.to_string())),
+        }
+    }
+
+    /// If every credential of the wallet is stored here
+    pub fn holds_credentials(&self) -> Result<bool, Error> {
+        holds_credentials(self)
+    }
+
+    fn inner(&self) -> &dyn CredentialStore {
+        match self {
+            Self::File(store) => store,
+            #[cfg(target_os = "linux")]
+            Self::SecretService(store) => store,
+            #[cfg(windows)]
+            Self::CredentialManager(store) => store,
+            #[cfg(any(test, feature = "test-utils"))]
+            Self::Memory(store) => store,
+        }
+    }
+}
+
+impl CredentialStore for ConfiguredCredentialStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        self.inner().get(target_name)
+    }
+
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        self.inner().store(target_name, blob)
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        self.inner().delete(target_name)
+    }
+}
+
+/// The credentials are read from and written to this store from now on
+pub fn set_credential_store(store: ConfiguredCredentialStore) {
+    *CREDENTIAL_STORE
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = store;
+}
+
+pub fn credential_store() -> ConfiguredCredentialStore {
+    CREDENTIAL_STORE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Uses the store of `backend`. If it does not hold the credentials yet, they are moved to it
+/// from the first other supported backend that does. If they can not be copied they are left
+/// where they are and that store is used instead, so the wallet can still be unlocked
+pub fn use_credential_backend(backend: CredentialBackend) -> Result<(), Error> {
+    let store = ConfiguredCredentialStore::new(backend)?;
+    match store.holds_credentials() {
+        Ok(false) => {}
+        Ok(true) => {
+            set_credential_store(store);
+            return Ok(());
+        }
+        // The store is still used, so the wallet does not look empty while e.g. the keyring
+        // daemon is not running
+        Err(err) => {
+            set_credential_store(store);
+            return Err(err);
+        }
+    }
+
+    let previous = CredentialBackend::supported()
+        .filter(|other| *other != backend)
+        .filter_map(|other| Some((other, ConfiguredCredentialStore::new(other).ok()?)))
+        .find(|(_, other)| other.holds_credentials().unwrap_or(false));
+
+    let Some((previous_backend, previous)) = previous else {
+        // No wallet has been created yet
+        set_credential_store(store);
+        return Ok(());
+    };
+
+    if let Err(err) = copy_credentials(&previous, &store) {
+        set_credential_store(previous);
+        return Err(Error::Backend(format!(
+            "Unable to move the credentials from {previous_backend} to {backend}, \
+            {previous_backend} is used instead: {err}"
+        )));
+    }
+    set_credential_store(store);
+
+    remove_credentials(&previous).map_err(|err| {
+        Error::Backend(format!(
+            "The credentials were moved to {backend}, \
+            but could not be removed from {previous_backend}: {err}"
+        ))
+    })
+}
+
+/// Copies every credential from `from` to `to` and removes them from `from` once all of them
+/// read back the same from `to`. Nothing is removed if a copy fails, so the credentials are
+/// always complete in at least one of the stores
+pub fn migrate_credentials(
+    from: &impl CredentialStore,
+    to: &impl CredentialStore,
+) -> Result<(), Error> {
+    copy_credentials(from, to)?;
+    remove_credentials(from)
+}
+
+fn holds_credentials(store: &impl CredentialStore) -> Result<bool, Error> {
+    for target_name in TARGET_NAMES {
+        match store.get(target_name) {
+            Ok(mut blob) => blob.zeroize(),
+            Err(Error::NotFound(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(true)
+}
+
+/// The credentials copied before a failed copy are deleted from `to`,
+/// so it never holds an incomplete set
+fn copy_credentials(from: &impl CredentialStore, to: &impl CredentialStore) -> Result<(), Error> {
+    for (copied, target_name) in TARGET_NAMES.into_iter().enumerate() {
+        if let Err(err) = copy_credential(from, to, target_name) {
+            // The failed target may have been written before it failed to read back
+            for target_name in &TARGET_NAMES[..=copied] {
+                to.delete(target_name).ok();
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn copy_credential(
+    from: &impl CredentialStore,
+    to: &impl CredentialStore,
+    target_name: &str,
+) -> Result<(), Error> {
+    let mut blob = from.get(target_name)?;
+    let copied = to
+        .store(target_name, &blob)
+        .and_then(|_| to.get(target_name));
+    let result = match copied {
+        Ok(mut copy) => {
+            let matches = copy == blob;
+            copy.zeroize();
+            matches
+                .then_some(())
+                .ok_or_else(|| Error::Backend(format!("{target_name} did not read back the same")))
+        }
+        Err(err) => Err(err),
+    };
+    blob.zeroize();
+    result
+}
+
+fn remove_credentials(store: &impl CredentialStore) -> Result<(), Error> {
+    for target_name in TARGET_NAMES {
+        match store.delete(target_name) {
+            Ok(()) | Err(Error::NotFound(_)) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
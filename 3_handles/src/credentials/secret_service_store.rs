@@ -0,0 +1,110 @@
+use deps::*;
+
+use std::{collections::HashMap, future::Future};
+
+use ports::credential_store::{CredentialStore, Error};
+use secret_service::{EncryptionType, SecretService};
+use types::consts::APPLICATION_NAME;
+
+/// Keeps the blobs as items in the default collection of the freedesktop Secret Service,
+/// e.g. gnome-keyring or KWallet. Every call connects to the session bus
+#[derive(Debug, Clone, Default)]
+pub struct SecretServiceStore;
+
+impl SecretServiceStore {
+    const APPLICATION_ATTRIBUTE: &'static str = "application";
+    const TARGET_ATTRIBUTE: &'static str = "target";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn attributes(target_name: &str) -> HashMap<&'static str, &str> {
+        HashMap::from([
+            (Self::APPLICATION_ATTRIBUTE, APPLICATION_NAME),
+            (Self::TARGET_ATTRIBUTE, target_name),
+        ])
+    }
+
+    /// The credentials are read from synchronous code that can already be running on the
+    /// async runtime, which can not be blocked on, so every request runs on its own thread
+    fn block_on<T, F>(request: impl FnOnce() -> F + Send) -> Result<T, Error>
+    where
+        T: Send,
+        F: Future<Output = Result<T, secret_service::Error>>,
+    {
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()?;
+                    runtime
+                        .block_on(request())
+                        .map_err(|err| Error::Backend(err.to_string()))
+                })
+                .join()
+                .map_err(|_| Error::Backend("Secret Service request panicked".to_string()))?
+        })
+    }
+}
+
+impl CredentialStore for SecretServiceStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        Self::block_on(|| async {
+            let service = SecretService::connect(EncryptionType::Dh).await?;
+            let collection = service.get_default_collection().await?;
+            collection.ensure_unlocked().await?;
+
+            match collection
+                .search_items(Self::attributes(target_name))
+                .await?
+                .first()
+            {
+                Some(item) => item.get_secret().await.map(Some),
+                None => Ok(None),
+            }
+        })?
+        .ok_or_else(|| Error::NotFound(target_name.to_string()))
+    }
+
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        Self::block_on(|| async {
+            let service = SecretService::connect(EncryptionType::Dh).await?;
+            let collection = service.get_default_collection().await?;
+            collection.ensure_unlocked().await?;
+
+            collection
+                .create_item(
+                    &format!("{APPLICATION_NAME} {target_name}"),
+                    Self::attributes(target_name),
+                    blob,
+                    true,
+                    "application/octet-stream",
+                )
+                .await
+                .map(|_| ())
+        })
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        let deleted = Self::block_on(|| async {
+            let service = SecretService::connect(EncryptionType::Dh).await?;
+            let collection = service.get_default_collection().await?;
+            collection.ensure_unlocked().await?;
+
+            let items = collection
+                .search_items(Self::attributes(target_name))
+                .await?;
+            for item in &items {
+                item.delete().await?;
+            }
+            Ok::<_, secret_service::Error>(items.len())
+        })?;
+
+        match deleted {
+            0 => Err(Error::NotFound(target_name.to_string())),
+            _ => Ok(()),
+        }
+    }
+}
@@ -2,34 +2,168 @@
 
 use deps::*;
 
+use std::sync::Once;
+
 use bip39::Mnemonic;
+use ports::credential_store::{CredentialStore, Error};
 use store::DataBase;
 use types::crypto::{EncryptedMnemonic, KeySaltPair, Password};
 
 use crate::credentials::{
-    delete_encrypted_mnemonic, delete_salt, get_db_encryption_salt, get_encrypted_mnemonic,
+    ConfiguredCredentialStore, ENCRYPTED_MNEMONIC_TARGET_NAME, SALT_TARGET_NAME,
+    delete_encrypted_mnemonic, delete_salt, file_store::FileStore, get_db_encryption_salt,
+    get_encrypted_mnemonic, memory_store::MemoryStore, migrate_credentials, set_credential_store,
     store_db_encryption_salt, store_encrypted_mnemonic,
 };
 
-use super::{delete::tests::*, get_credentials::tests::*, store_credentials::tests::*};
+/// Stores every target except `failing` in memory
+struct FailingStore {
+    inner: MemoryStore,
+    failing: &'static str,
+}
 
-#[test]
-fn test_store_get_delete_blob() {
+impl CredentialStore for FailingStore {
+    fn get(&self, target_name: &str) -> Result<Vec<u8>, Error> {
+        self.inner.get(target_name)
+    }
+
+    fn store(&self, target_name: &str, blob: &[u8]) -> Result<(), Error> {
+        if target_name == self.failing {
+            return Err(Error::Backend(format!("Unable to store {target_name}")));
+        }
+        self.inner.store(target_name, blob)
+    }
+
+    fn delete(&self, target_name: &str) -> Result<(), Error> {
+        self.inner.delete(target_name)
+    }
+}
+
+/// Points the global store to one store in memory, so the tests never touch the credentials
+/// of the user running them
+fn use_memory_store() {
+    static MEMORY_STORE: Once = Once::new();
+    MEMORY_STORE
+        .call_once(|| set_credential_store(ConfiguredCredentialStore::Memory(MemoryStore::new())));
+}
+
+fn store_get_delete_blobs(store: &impl CredentialStore) {
     for i in 0..TEST_TARGETS.len() {
         let target = TEST_TARGETS[i];
-        let mut blob = TEST_PASSWORDS[i].as_bytes().to_vec();
-        #[cfg(windows)]
-        store_blob_test(blob.as_mut_ptr(), blob.len(), target);
-        #[cfg(unix)]
-        store_blob_test(blob.as_slice(), target);
-        let retrieved_blob = get_blob_test(target);
+        let blob = TEST_PASSWORDS[i].as_bytes();
+        store.store(target, blob).expect("Failed to store blob");
+        let retrieved_blob = store.get(target).expect("Failed to get blob");
         assert!(blob == retrieved_blob, "Blob mismatch");
-        delete_credentials_test(target)
+        store.delete(target).expect("Failed to delete blob");
+
+        assert!(matches!(store.get(target), Err(Error::NotFound(_))));
+        assert!(matches!(store.delete(target), Err(Error::NotFound(_))));
     }
 }
 
+#[test]
+fn test_memory_store() {
+    store_get_delete_blobs(&MemoryStore::new());
+}
+
+#[test]
+fn test_file_store() {
+    let directory = std::env::temp_dir().join(format!("credentials_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+
+    store_get_delete_blobs(&FileStore::new(&directory));
+
+    std::fs::remove_dir_all(directory).ok();
+}
+
+#[cfg(windows)]
+#[test]
+fn test_credential_manager_store() {
+    store_get_delete_blobs(&super::credential_manager_store::CredentialManagerStore::new());
+}
+
+/// Run with `cargo test -- --ignored` in a session with gnome-keyring running and unlocked
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore = "needs a D-Bus session with a Secret Service, e.g. gnome-keyring"]
+fn test_secret_service_store() {
+    store_get_delete_blobs(&super::secret_service_store::SecretServiceStore::new());
+}
+
+#[test]
+fn test_migrate_credentials() {
+    let from = MemoryStore::new();
+    let to = MemoryStore::new();
+    from.store(SALT_TARGET_NAME, b"salt").unwrap();
+    from.store(ENCRYPTED_MNEMONIC_TARGET_NAME, b"mnemonic")
+        .unwrap();
+
+    migrate_credentials(&from, &to).expect("Failed to migrate credentials");
+
+    assert_eq!(to.get(SALT_TARGET_NAME).unwrap(), b"salt");
+    assert_eq!(to.get(ENCRYPTED_MNEMONIC_TARGET_NAME).unwrap(), b"mnemonic");
+    assert!(matches!(
+        from.get(SALT_TARGET_NAME),
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        from.get(ENCRYPTED_MNEMONIC_TARGET_NAME),
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_failed_migration_keeps_credentials() {
+    let from = MemoryStore::new();
+    let unwritable = FileStore::new(std::env::temp_dir().join("missing_credentials_directory"));
+    from.store(SALT_TARGET_NAME, b"salt").unwrap();
+    from.store(ENCRYPTED_MNEMONIC_TARGET_NAME, b"mnemonic")
+        .unwrap();
+
+    assert!(migrate_credentials(&from, &unwritable).is_err());
+
+    assert_eq!(from.get(SALT_TARGET_NAME).unwrap(), b"salt");
+    assert_eq!(
+        from.get(ENCRYPTED_MNEMONIC_TARGET_NAME).unwrap(),
+        b"mnemonic"
+    );
+}
+
+#[test]
+fn test_failed_copy_removes_copied_credentials() {
+    let from = MemoryStore::new();
+    let to = FailingStore {
+        inner: MemoryStore::new(),
+        failing: ENCRYPTED_MNEMONIC_TARGET_NAME,
+    };
+    from.store(SALT_TARGET_NAME, b"salt").unwrap();
+    from.store(ENCRYPTED_MNEMONIC_TARGET_NAME, b"mnemonic")
+        .unwrap();
+
+    assert!(migrate_credentials(&from, &to).is_err());
+
+    assert!(matches!(to.get(SALT_TARGET_NAME), Err(Error::NotFound(_))));
+    assert_eq!(from.get(SALT_TARGET_NAME).unwrap(), b"salt");
+}
+
+#[test]
+fn test_holds_credentials_only_if_complete() {
+    let store = MemoryStore::new();
+    let configured = ConfiguredCredentialStore::Memory(store.clone());
+
+    store.store(SALT_TARGET_NAME, b"salt").unwrap();
+    let with_salt_only = configured.holds_credentials().unwrap();
+    store
+        .store(ENCRYPTED_MNEMONIC_TARGET_NAME, b"mnemonic")
+        .unwrap();
+
+    assert!(!with_salt_only);
+    assert!(configured.holds_credentials().unwrap());
+}
+
 #[test]
 fn test_store_get_delete_salt() {
+    use_memory_store();
     for i in 0..TEST_PASSWORDS.len() {
         let password = Password::from(TEST_PASSWORDS[i]);
         let salt = KeySaltPair::<DataBase>::new(password.as_str())
@@ -46,6 +180,7 @@ fn test_store_get_delete_salt() {
 
 #[test]
 fn test_store_get_delete_encrypted_mnemonic() {
+    use_memory_store();
     for i in 0..TEST_PASSWORDS.len() {
         let password = Password::from(TEST_PASSWORDS[i]);
         let mnemonic = Mnemonic::new(bip39::MnemonicType::Words24, bip39::Language::English);
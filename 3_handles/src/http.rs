@@ -0,0 +1,43 @@
+use deps::*;
+
+use std::sync::{PoisonError, RwLock};
+
+use once_cell::sync::Lazy;
+use types::Proxy;
+
+static PROXY: Lazy<RwLock<Option<Proxy>>> = Lazy::new(Default::default);
+
+/// Applies to clients created after this call
+pub fn set_proxy(proxy: Option<Proxy>) {
+    *PROXY.write().unwrap_or_else(PoisonError::into_inner) = proxy;
+}
+
+pub fn proxy() -> Option<Proxy> {
+    PROXY.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// A client that sends every request through `proxy`. Fails if the proxy can not be used,
+/// so no request is sent past a configured proxy
+pub fn client(proxy: Option<&Proxy>) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy.url())?);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_fails_with_invalid_proxy() {
+        let proxy = Proxy::Socks5 {
+            host: "not a host".to_string(),
+            port: 9050,
+        };
+
+        assert!(client(Some(&proxy)).is_err());
+        assert!(client(None).is_ok());
+    }
+}
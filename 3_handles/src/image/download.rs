@@ -3,6 +3,7 @@ use deps::*;
 use std::{
     collections::{BTreeMap, HashMap},
     io::Cursor,
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use bytes::Bytes;
@@ -13,7 +14,22 @@ use image::DynamicImage;
 use store::IconsDb;
 use types::{Network, address::ResourceAddress};
 
-use crate::image::resize::{resize_small_dimensions, resize_standard_dimensions};
+use crate::{
+    http,
+    image::resize::{resize_small_dimensions, resize_standard_dimensions},
+};
+
+static ICON_DOWNLOADS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Icons are hosted wherever the creator of a resource chose, so downloading them
+/// tells those hosts which resources the wallet holds
+pub fn set_icon_downloads_enabled(enabled: bool) {
+    ICON_DOWNLOADS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn icon_downloads_enabled() -> bool {
+    ICON_DOWNLOADS_ENABLED.load(Ordering::Relaxed)
+}
 
 // pub async fn download_resize_and_store_resource_icons_as_handle(
 //     icon_urls: BTreeMap<ResourceAddress, String>,
@@ -74,8 +90,14 @@ use crate::image::resize::{resize_small_dimensions, resize_standard_dimensions};
 //     icons
 // }
 
+/// Sent through the configured proxy, nothing is downloaded if icon downloads are turned off
 async fn download_image(url: &str) -> Option<DynamicImage> {
-    let response = reqwest::get(url).await.ok()?;
+    if !icon_downloads_enabled() {
+        return None;
+    }
+
+    let client = http::client(http::proxy().as_ref()).ok()?;
+    let response = client.get(url).send().await.ok()?;
 
     let bytes = response.bytes().await.ok()?;
     let reader = image::ImageReader::new(Cursor::new(&bytes));
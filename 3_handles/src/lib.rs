@@ -1,8 +1,11 @@
 pub mod credentials;
+pub mod http;
 pub mod image;
+pub mod prices;
 pub mod app_settings;
 pub mod radix_dlt;
 pub mod statics;
 pub mod store;
+pub mod transaction_export;
 pub mod wallet;
 pub mod database_handle;
@@ -0,0 +1,70 @@
+use deps::*;
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ports::price_source::{Error, PriceSource};
+use scrypto::math::Decimal as RadixDecimal;
+
+use super::PriceList;
+
+/// Reads the prices from a [`PriceList`] file, the file is read again on every request
+/// so it can be edited while the wallet is running
+#[derive(Debug, Clone)]
+pub struct FilePriceSource {
+    path: Arc<Path>,
+}
+
+impl FilePriceSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Arc::from(path.into()),
+        }
+    }
+}
+
+impl PriceSource for FilePriceSource {
+    async fn prices(
+        &self,
+        resource_addresses: &[&str],
+        currency: &str,
+    ) -> Result<HashMap<String, RadixDecimal>, Error> {
+        let content = tokio::fs::read(&*self.path).await?;
+        let price_list: PriceList = serde_json::from_slice(&content)?;
+        price_list.into_prices(resource_addresses, currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::prices::tests::XRD;
+
+    #[tokio::test]
+    async fn test_prices_from_file() {
+        let path = std::env::temp_dir().join(format!("prices_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            format!(r#"{{ "currency": "EUR", "prices": {{ "{XRD}": 0.0125 }} }}"#),
+        )
+        .unwrap();
+
+        let source = FilePriceSource::new(&path);
+        let prices = source.prices(&[XRD], "EUR").await;
+        let missing = FilePriceSource::new(path.with_extension("missing"))
+            .prices(&[XRD], "EUR")
+            .await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            prices.unwrap()[XRD],
+            RadixDecimal::from_str("0.0125").unwrap()
+        );
+        assert!(matches!(missing, Err(Error::Io(_))));
+    }
+}
@@ -0,0 +1,128 @@
+use deps::*;
+
+use std::{collections::HashMap, sync::Arc};
+
+use ports::price_source::{Error, PriceSource};
+use scrypto::math::Decimal as RadixDecimal;
+use serde_json::json;
+
+use crate::http;
+
+use super::PriceList;
+
+/// Posts the requested resources and currency as JSON to the url,
+/// `{ "currency": "USD", "resources": ["<resource address>"] }`.
+/// The service answers with a [`PriceList`]. Requests are sent through the configured proxy
+#[derive(Debug, Clone)]
+pub struct HttpPriceSource {
+    url: Arc<str>,
+}
+
+impl HttpPriceSource {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: Arc::from(url),
+        }
+    }
+}
+
+impl PriceSource for HttpPriceSource {
+    async fn prices(
+        &self,
+        resource_addresses: &[&str],
+        currency: &str,
+    ) -> Result<HashMap<String, RadixDecimal>, Error> {
+        let body = json!({
+            "currency": currency,
+            "resources": resource_addresses,
+        });
+
+        let response = http::client(http::proxy().as_ref())?
+            .post(&*self.url)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Error::Status(status.as_u16()));
+        }
+
+        let price_list: PriceList = serde_json::from_slice(&response.bytes().await?)?;
+        price_list.into_prices(resource_addresses, currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::prices::tests::{UNPRICED, XRD};
+
+    /// Answers a single request with `status` and `body`, returns the url of the server
+    /// and the request it received
+    async fn stub_server(
+        status: &'static str,
+        body: String,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/prices", listener.local_addr().unwrap());
+
+        let request = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            // Reads until the whole JSON body of the request has arrived
+            while !request.ends_with(b"]}") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\n\
+                content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (url, request)
+    }
+
+    #[tokio::test]
+    async fn test_prices_from_stub_server() {
+        let body = format!(r#"{{ "currency": "USD", "prices": {{ "{XRD}": "0.015" }} }}"#);
+        let (url, request) = stub_server("200 OK", body).await;
+
+        let prices = HttpPriceSource::new(&url)
+            .prices(&[XRD, UNPRICED], "USD")
+            .await
+            .unwrap();
+
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[XRD], RadixDecimal::from_str("0.015").unwrap());
+
+        let request = request.await.unwrap();
+        assert!(request.starts_with("POST /prices"));
+        assert!(request.contains(UNPRICED));
+    }
+
+    #[tokio::test]
+    async fn test_error_status_from_stub_server() {
+        let (url, _) = stub_server("503 Service Unavailable", String::new()).await;
+
+        let result = HttpPriceSource::new(&url).prices(&[XRD], "USD").await;
+
+        assert!(matches!(result, Err(Error::Status(503))));
+    }
+}
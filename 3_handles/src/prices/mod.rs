@@ -0,0 +1,162 @@
+use deps::*;
+
+pub mod file_source;
+pub mod http_source;
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ports::price_source::{Error, PriceSource};
+use scrypto::math::Decimal as RadixDecimal;
+use serde::Deserialize;
+use types::{
+    Currency, PriceSourceConfig, PriceTable,
+    address::{Address, ResourceAddress},
+};
+
+use self::{file_source::FilePriceSource, http_source::HttpPriceSource};
+
+/// The price source set in the settings
+#[derive(Debug, Clone)]
+pub enum ConfiguredPriceSource {
+    Http(HttpPriceSource),
+    File(FilePriceSource),
+}
+
+impl From<&PriceSourceConfig> for ConfiguredPriceSource {
+    fn from(config: &PriceSourceConfig) -> Self {
+        match config {
+            PriceSourceConfig::Http { url } => Self::Http(HttpPriceSource::new(url)),
+            PriceSourceConfig::File { path } => Self::File(FilePriceSource::new(path)),
+        }
+    }
+}
+
+impl PriceSource for ConfiguredPriceSource {
+    async fn prices(
+        &self,
+        resource_addresses: &[&str],
+        currency: &str,
+    ) -> Result<HashMap<String, RadixDecimal>, Error> {
+        match self {
+            Self::Http(source) => source.prices(resource_addresses, currency).await,
+            Self::File(source) => source.prices(resource_addresses, currency).await,
+        }
+    }
+}
+
+/// Asks the source for the prices of the resources, addresses the source returns
+/// that are not valid resource addresses are left out
+pub async fn update_prices<S: PriceSource>(
+    source: S,
+    currency: Currency,
+    resource_addresses: &[ResourceAddress],
+) -> Result<PriceTable, Error> {
+    let addresses = resource_addresses
+        .iter()
+        .map(|address| address.as_str())
+        .collect::<Vec<_>>();
+
+    let prices = source
+        .prices(&addresses, currency.as_str())
+        .await?
+        .into_iter()
+        .filter_map(|(address, price)| Some((ResourceAddress::from_str(&address).ok()?, price)))
+        .collect();
+
+    let updated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+
+    Ok(PriceTable {
+        currency,
+        prices,
+        updated_at: Some(updated_at),
+    })
+}
+
+/// The prices as read by both the http and the file source:
+/// `{ "currency": "USD", "prices": { "<resource address>": "1.23" } }`,
+/// a price can also be a JSON number
+#[derive(Debug, Deserialize)]
+pub(crate) struct PriceList {
+    currency: String,
+    prices: HashMap<String, PriceValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PriceValue {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+impl PriceList {
+    /// The prices of the requested resources, fails if the list is in another currency
+    pub(crate) fn into_prices(
+        mut self,
+        resource_addresses: &[&str],
+        currency: &str,
+    ) -> Result<HashMap<String, RadixDecimal>, Error> {
+        if !self.currency.eq_ignore_ascii_case(currency) {
+            return Err(Error::WrongCurrency {
+                expected: currency.to_string(),
+                found: self.currency,
+            });
+        }
+
+        resource_addresses
+            .iter()
+            .filter_map(|address| Some((*address, self.prices.remove(*address)?)))
+            .map(|(address, value)| {
+                let price = match value {
+                    PriceValue::Text(price) => price,
+                    PriceValue::Number(price) => price.to_string(),
+                };
+                RadixDecimal::from_str(&price)
+                    .map(|decimal| (address.to_string(), decimal))
+                    .map_err(|_| Error::InvalidPrice {
+                        resource_address: address.to_string(),
+                        price,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) const XRD: &str =
+        "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
+    pub(crate) const UNPRICED: &str =
+        "resource_rdx1t5rswpc8qurswpc8qurswpc8qurswpc8qurswpc8qurswpc820luy5";
+
+    #[test]
+    fn test_price_list_keeps_requested_resources() {
+        let price_list: PriceList = serde_json::from_str(&format!(
+            r#"{{ "currency": "usd", "prices": {{ "{XRD}": 0.015, "other": "2" }} }}"#
+        ))
+        .unwrap();
+
+        let prices = price_list.into_prices(&[XRD, UNPRICED], "USD").unwrap();
+
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[XRD], RadixDecimal::from_str("0.015").unwrap());
+    }
+
+    #[test]
+    fn test_price_list_in_other_currency_fails() {
+        let price_list: PriceList =
+            serde_json::from_str(r#"{ "currency": "EUR", "prices": {} }"#).unwrap();
+
+        assert!(matches!(
+            price_list.into_prices(&[XRD], "USD"),
+            Err(Error::WrongCurrency { .. })
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use deps::*;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ports::network_gateway::{Error, NetworkGateway};
+use radix_gateway_sdk::generated::model::{
+    StateEntityDetailsResponse, StateEntityFungiblesPageResponse,
+    StateEntityNonFungibleIdsPageResponse, StateEntityNonFungiblesPageResponse,
+    StateNonFungibleDataResponse, StreamTransactionsResponse, TransactionConstructionResponse,
+    TransactionPreviewResponse, TransactionStatusResponse, TransactionSubmitResponse,
+};
+use scrypto::crypto::Ed25519PublicKey;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+/// A [`NetworkGateway`] that answers every request from a fixture instead of the network.
+///
+/// The fixture holds the responses in the json format of the Gateway API. Paged responses are
+/// listed in order for each address, the cursor is the index of the page so `next_cursor` of a page
+/// should hold the index of the page that follows it. Submitted transactions are only recorded,
+/// as are the ledger states the balance pages are requested at.
+#[derive(Clone)]
+pub struct FakeGateway {
+    fixture: Arc<Fixture>,
+    submitted: Arc<Mutex<Vec<String>>>,
+    balance_requests: Arc<Mutex<Vec<BalanceRequest>>>,
+}
+
+/// A requested page of fungible or non-fungible balances
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BalanceRequest {
+    Fungibles(Option<i64>),
+    NonFungibles(Option<i64>),
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Fixture {
+    /// A single response holding the items of every known entity
+    entity_details: Option<Value>,
+    /// Keyed by account address
+    fungibles: HashMap<String, Vec<Value>>,
+    /// Keyed by account address
+    non_fungibles: HashMap<String, Vec<Value>>,
+    /// Keyed by vault address
+    non_fungible_ids: HashMap<String, Vec<Value>>,
+    /// Keyed by resource address, holding every known id of the resource
+    non_fungible_data: HashMap<String, Value>,
+    /// Keyed by account address
+    transactions: HashMap<String, Vec<Value>>,
+    transaction_construction: Option<Value>,
+    transaction_preview: Option<Value>,
+    /// Keyed by intent hash
+    transaction_status: HashMap<String, Value>,
+}
+
+impl FakeGateway {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            fixture: Arc::new(serde_json::from_str(json)?),
+            submitted: Arc::new(Mutex::new(Vec::new())),
+            balance_requests: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// The hex encoded transactions submitted through this gateway, in the order of submission
+    pub fn submitted_transactions(&self) -> Vec<String> {
+        self.submitted
+            .lock()
+            .map(|submitted| submitted.clone())
+            .unwrap_or_default()
+    }
+
+    /// The balance pages requested through this gateway, in the order they were requested
+    pub fn balance_requests(&self) -> Vec<BalanceRequest> {
+        self.balance_requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+
+    fn record_balance_request(&self, request: BalanceRequest) {
+        if let Ok(mut requests) = self.balance_requests.lock() {
+            requests.push(request);
+        }
+    }
+
+    fn page<T: DeserializeOwned>(
+        pages: &HashMap<String, Vec<Value>>,
+        key: &str,
+        cursor: Option<String>,
+    ) -> Result<T, Error> {
+        let index = match cursor {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| Error::NotFound(format!("cursor {cursor}")))?,
+            None => 0,
+        };
+
+        let page = pages
+            .get(key)
+            .and_then(|pages| pages.get(index))
+            .ok_or_else(|| Error::NotFound(format!("page {index} of {key}")))?;
+
+        Ok(serde_json::from_value(page.clone())?)
+    }
+
+    fn single<T: DeserializeOwned>(response: Option<&Value>, request: &str) -> Result<T, Error> {
+        let response = response.ok_or_else(|| Error::NotFound(request.to_string()))?;
+
+        Ok(serde_json::from_value(response.clone())?)
+    }
+
+    /// Keeps the elements of the array at `key` where `field` is one of `values`
+    fn retain_in_array(response: &mut Value, key: &str, field: &str, values: &[&str]) {
+        if let Some(items) = response.get_mut(key).and_then(Value::as_array_mut) {
+            items.retain(|item| {
+                item.get(field)
+                    .and_then(Value::as_str)
+                    .is_some_and(|value| values.contains(&value))
+            });
+        }
+    }
+}
+
+impl NetworkGateway for FakeGateway {
+    async fn entity_details(
+        &self,
+        addresses: &[&str],
+    ) -> Result<StateEntityDetailsResponse, Error> {
+        let mut response = self
+            .fixture
+            .entity_details
+            .clone()
+            .ok_or_else(|| Error::NotFound("entity details".to_string()))?;
+
+        Self::retain_in_array(&mut response, "items", "address", addresses);
+
+        Ok(serde_json::from_value(response)?)
+    }
+
+    async fn fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> Result<StateEntityFungiblesPageResponse, Error> {
+        self.record_balance_request(BalanceRequest::Fungibles(at_state_version));
+        Self::page(&self.fixture.fungibles, address, cursor)
+    }
+
+    async fn non_fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> Result<StateEntityNonFungiblesPageResponse, Error> {
+        self.record_balance_request(BalanceRequest::NonFungibles(at_state_version));
+        Self::page(&self.fixture.non_fungibles, address, cursor)
+    }
+
+    async fn non_fungible_ids_page(
+        &self,
+        _account_address: &str,
+        _resource_address: &str,
+        vault_address: &str,
+        cursor: Option<String>,
+        _at_state_version: Option<i64>,
+    ) -> Result<StateEntityNonFungibleIdsPageResponse, Error> {
+        Self::page(&self.fixture.non_fungible_ids, vault_address, cursor)
+    }
+
+    async fn non_fungible_data(
+        &self,
+        resource_address: &str,
+        non_fungible_ids: &[String],
+    ) -> Result<StateNonFungibleDataResponse, Error> {
+        let mut response = self
+            .fixture
+            .non_fungible_data
+            .get(resource_address)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("non-fungible data of {resource_address}")))?;
+
+        let ids = non_fungible_ids
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        Self::retain_in_array(&mut response, "non_fungible_ids", "non_fungible_id", &ids);
+
+        Ok(serde_json::from_value(response)?)
+    }
+
+    async fn transactions_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        _from_state_version: Option<i64>,
+        _at_state_version: Option<i64>,
+    ) -> Result<StreamTransactionsResponse, Error> {
+        Self::page(&self.fixture.transactions, address, cursor)
+    }
+
+    async fn transaction_construction(&self) -> Result<TransactionConstructionResponse, Error> {
+        Self::single(
+            self.fixture.transaction_construction.as_ref(),
+            "transaction construction",
+        )
+    }
+
+    async fn preview_transaction(
+        &self,
+        _manifest: &str,
+        _start_epoch_inclusive: u64,
+        _end_epoch_exclusive: u64,
+        _message: Option<serde_json::Value>,
+        _signer_public_keys: &[Ed25519PublicKey],
+    ) -> Result<TransactionPreviewResponse, Error> {
+        Self::single(
+            self.fixture.transaction_preview.as_ref(),
+            "transaction preview",
+        )
+    }
+
+    async fn submit_transaction(
+        &self,
+        notarized_transaction_hex: &str,
+    ) -> Result<TransactionSubmitResponse, Error> {
+        if let Ok(mut submitted) = self.submitted.lock() {
+            submitted.push(notarized_transaction_hex.to_string());
+        }
+
+        Ok(serde_json::from_value(
+            serde_json::json!({ "duplicate": false }),
+        )?)
+    }
+
+    async fn transaction_status(
+        &self,
+        intent_hash: &str,
+    ) -> Result<TransactionStatusResponse, Error> {
+        Self::single(
+            self.fixture.transaction_status.get(intent_hash),
+            intent_hash,
+        )
+    }
+}
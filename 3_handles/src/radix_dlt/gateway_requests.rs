@@ -1,195 +1,515 @@
 use deps::*;
 
-use std::future::IntoFuture;
-
-use futures::future::join_all;
-use radix_gateway_sdk::{
-    generated::{
-        model::{
-            LedgerStateSelector, ResourceAggregationLevel, StateEntityDetailsOptIns,
-            StateEntityDetailsResponse, StateEntityFungiblesPageResponse,
-            StateEntityNonFungibleIdsPageResponse, StateEntityNonFungiblesPageResponse,
-            StateNonFungibleDataResponse, StreamTransactionsResponse, TransactionDetailsOptIns,
-        },
-        request::StreamTransactionsRequired,
-    },
-    Client,
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
 };
-use types::{Network, UnsafeRef};
 
-pub const ENTITY_DETAILS_MAX_ADDRESSES: usize = 20;
+use futures::future::try_join_all;
+use once_cell::sync::Lazy;
+use ports::network_gateway::{Error, NetworkGateway};
+use radix_gateway_sdk::generated::model::{
+    LedgerStateSelector, ResourceAggregationLevel, StateEntityDetailsOptIns,
+    StateEntityDetailsResponse, StateEntityFungiblesPageResponse,
+    StateEntityNonFungibleIdsPageResponse, StateEntityNonFungiblesPageResponse,
+    StateNonFungibleDataResponse, StreamTransactionsResponse, TransactionConstructionResponse,
+    TransactionDetailsOptIns, TransactionPreviewRequestFlags, TransactionPreviewResponse,
+    TransactionStatusResponse, TransactionSubmitResponse,
+};
+use reqwest::{StatusCode, header::RETRY_AFTER};
+use scrypto::crypto::Ed25519PublicKey;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use tokio::sync::Semaphore;
+use types::{Network, Proxy, RequestLimits, UnwrapUnreachable, debug_info};
+
+use crate::http;
 
-/// Takes a maximum of 20 addresses, otherwise it will panic
-pub async fn get_entity_details(
+pub const ENTITY_DETAILS_MAX_ADDRESSES: usize = 20;
+pub const NON_FUNGIBLE_DATA_MAX_IDS: usize = 100;
+/// How long [`RadixGateway::is_reachable`] waits for the gateway to respond
+pub const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One gateway per network, so every request to a network shares the connection pool
+/// and the limit on requests in flight
+static SHARED_GATEWAYS: Lazy<Mutex<HashMap<Network, RadixGateway>>> = Lazy::new(Default::default);
+
+/// The Radix gateway of a network, requests are sent to the gateway url configured for the network
+/// through the configured proxy.
+/// Rate limited and failed requests are retried with an exponential backoff
+#[derive(Debug, Clone)]
+pub struct RadixGateway {
     network: Network,
-    addresses: &[&str],
-) -> Result<StateEntityDetailsResponse, radix_gateway_sdk::Error> {
-    assert!(addresses.len() <= ENTITY_DETAILS_MAX_ADDRESSES);
+    base_url: Arc<str>,
+    /// The error if the client could not be created with the proxy
+    client: Result<reqwest::Client, Arc<str>>,
+    in_flight: Arc<Semaphore>,
+    limits: RequestLimits,
+    proxy: Option<Proxy>,
+}
 
-    let opt_ins = StateEntityDetailsOptIns {
-        explicit_metadata: Some(vec!["symbol".to_string()]),
-        ..Default::default()
-    };
+impl RadixGateway {
+    /// Returns the shared gateway of the network, a new one is created if the gateway url,
+    /// the request limits or the proxy have changed since the last one was created
+    pub fn new(network: Network, limits: RequestLimits) -> Self {
+        let base_url = network.gateway_url();
+        let base_url = base_url.trim_end_matches('/');
+        let proxy = http::proxy();
+
+        let mut gateways = SHARED_GATEWAYS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        match gateways.get(&network) {
+            Some(gateway)
+                if &*gateway.base_url == base_url
+                    && gateway.limits == limits
+                    && gateway.proxy == proxy =>
+            {
+                gateway.clone()
+            }
+            _ => {
+                let gateway = Self::with_settings(network, base_url, limits, proxy);
+                gateways.insert(network, gateway.clone());
+                gateway
+            }
+        }
+    }
 
-    Client::new(network.into(), None, None)?
-        .get_inner_client()
-        .state_entity_details(addresses, ResourceAggregationLevel::Vault, opt_ins)
-        .into_future()
-        .await
-}
+    fn with_settings(
+        network: Network,
+        base_url: &str,
+        limits: RequestLimits,
+        proxy: Option<Proxy>,
+    ) -> Self {
+        Self {
+            network,
+            base_url: Arc::from(base_url),
+            client: http::client(proxy.as_ref()).map_err(|err| Arc::from(err.to_string())),
+            in_flight: Arc::new(Semaphore::new(limits.max_in_flight.max(1))),
+            limits,
+            proxy,
+        }
+    }
 
-pub async fn get_fungible_balances_for_entity(
-    network: Network,
-    address: &str,
-    cursor: Option<String>,
-    at_state_version: Option<i64>,
-) -> Result<StateEntityFungiblesPageResponse, radix_gateway_sdk::Error> {
-    let client = Client::new(network.into(), None, None)?;
-    let mut fluent_request = client.get_inner_client().entity_fungibles_page(
-        address,
-        ResourceAggregationLevel::Global,
-        Default::default(),
-    );
-
-    if at_state_version.is_some() {
-        fluent_request.params.at_ledger_state = Some(LedgerStateSelector {
-            state_version: at_state_version,
-            ..Default::default()
-        });
-        fluent_request.params.cursor = cursor;
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
     }
 
-    fluent_request.into_future().await
+    /// Whether the gateway responds at all, any response counts even if it is an error.
+    /// Sent once without retries, so an unreachable gateway is noticed quickly
+    pub async fn is_reachable(&self) -> bool {
+        let Ok(client) = &self.client else {
+            return false;
+        };
+
+        client
+            .post(format!("{}/status/gateway-status", self.base_url))
+            .json(&json!({}))
+            .timeout(REACHABILITY_TIMEOUT)
+            .send()
+            .await
+            .is_ok()
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &(impl Serialize + Sync),
+    ) -> Result<T, Error> {
+        let url = format!("{}/{path}", self.base_url);
+        let mut attempt = 0;
+
+        loop {
+            let (error, retry_after) = match self.send(&url, body).await {
+                Ok(response) => return Ok(response),
+                Err(failure) => failure,
+            };
+
+            if attempt >= self.limits.max_retries || !Self::is_retryable(&error) {
+                return Err(error);
+            }
+
+            let backoff = retry_after
+                .unwrap_or_else(|| self.limits.backoff(attempt))
+                .min(Duration::from_millis(self.limits.max_backoff_ms));
+            tokio::time::sleep(backoff + Self::jitter(backoff)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Holds a permit only while the request is in flight, so waiting for a retry
+    /// does not hold up other requests. Returns the error together with any `Retry-After` delay
+    async fn send<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &(impl Serialize + Sync),
+    ) -> Result<T, (Error, Option<Duration>)> {
+        let client = self
+            .client
+            .as_ref()
+            .map_err(|err| (Error::Proxy(err.to_string()), None))?;
+
+        let _permit = self
+            .in_flight
+            .acquire()
+            .await
+            .unwrap_unreachable(debug_info!("Gateway semaphore closed"));
+
+        let response = client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|err| (Error::from(err), None))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(Duration::from_secs);
+
+            let error = Error::Status {
+                status: status.as_u16(),
+                message: response.text().await.unwrap_or_default(),
+            };
+            return Err((error, retry_after));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|err| (Error::from(err), None))?;
+
+        serde_json::from_slice(&bytes).map_err(|err| (Error::from(err), None))
+    }
+
+    /// Rate limits, gateway errors and connection problems are worth another try,
+    /// any other error would fail the same way again
+    fn is_retryable(error: &Error) -> bool {
+        match error {
+            Error::Status { status, .. } => StatusCode::from_u16(*status).is_ok_and(|status| {
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }),
+            Error::Request(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Up to a quarter of the backoff, so requests that failed together are not retried together
+    fn jitter(backoff: Duration) -> Duration {
+        let max_jitter_ms = backoff.as_millis() as u64 / 4;
+        Duration::from_millis(rand::random::<u64>() % (max_jitter_ms + 1))
+    }
 }
 
-/// If cursor is provided, a ledger state is also required, else the cursor will do nothing
-pub async fn get_non_fungible_balances_for_entity(
-    network: Network,
-    address: &str,
-    cursor: Option<String>,
-    at_state_version: Option<i64>,
-) -> Result<StateEntityNonFungiblesPageResponse, radix_gateway_sdk::Error> {
-    let client = Client::new(network.into(), None, None)?;
-    let mut fluent_request = client.get_inner_client().entity_non_fungibles_page(
-        address,
-        ResourceAggregationLevel::Vault,
-        Default::default(),
-    );
-
-    if at_state_version.is_some() {
-        fluent_request.params.at_ledger_state = Some(LedgerStateSelector {
-            state_version: at_state_version,
-            ..Default::default()
-        });
-        fluent_request.params.cursor = cursor;
+impl NetworkGateway for RadixGateway {
+    /// Requests at most 20 addresses at once, larger batches are split over several requests
+    async fn entity_details(
+        &self,
+        addresses: &[&str],
+    ) -> Result<StateEntityDetailsResponse, Error> {
+        let requests = addresses
+            .chunks(ENTITY_DETAILS_MAX_ADDRESSES)
+            .map(|chunk| async move {
+                let request = EntityDetailsRequest {
+                    addresses: chunk,
+                    aggregation_level: ResourceAggregationLevel::Vault,
+                    opt_ins: StateEntityDetailsOptIns {
+                        explicit_metadata: Some(vec!["symbol".to_string()]),
+                        ..Default::default()
+                    },
+                };
+                self.post::<StateEntityDetailsResponse>("state/entity/details", &request)
+                    .await
+            });
+
+        let mut responses = try_join_all(requests).await?.into_iter();
+        let mut response = responses
+            .next()
+            .ok_or_else(|| Error::NotFound("entity details".to_string()))?;
+
+        for next_response in responses {
+            response.items.extend(next_response.items);
+        }
+
+        Ok(response)
+    }
+
+    async fn fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> Result<StateEntityFungiblesPageResponse, Error> {
+        let request = EntityPageRequest {
+            aggregation_level: Some(ResourceAggregationLevel::Global),
+            ..EntityPageRequest::new(address, cursor, at_state_version)
+        };
+
+        self.post("state/entity/page/fungibles/", &request).await
+    }
+
+    async fn non_fungibles_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> Result<StateEntityNonFungiblesPageResponse, Error> {
+        let request = EntityPageRequest {
+            aggregation_level: Some(ResourceAggregationLevel::Vault),
+            ..EntityPageRequest::new(address, cursor, at_state_version)
+        };
+
+        self.post("state/entity/page/non-fungibles/", &request)
+            .await
+    }
+
+    async fn non_fungible_ids_page(
+        &self,
+        account_address: &str,
+        resource_address: &str,
+        vault_address: &str,
+        cursor: Option<String>,
+        at_state_version: Option<i64>,
+    ) -> Result<StateEntityNonFungibleIdsPageResponse, Error> {
+        let request = EntityPageRequest {
+            resource_address: Some(resource_address),
+            vault_address: Some(vault_address),
+            ..EntityPageRequest::new(account_address, cursor, at_state_version)
+        };
+
+        self.post("state/entity/page/non-fungible-vault/ids", &request)
+            .await
+    }
+
+    async fn non_fungible_data(
+        &self,
+        resource_address: &str,
+        non_fungible_ids: &[String],
+    ) -> Result<StateNonFungibleDataResponse, Error> {
+        let requests = non_fungible_ids
+            .chunks(NON_FUNGIBLE_DATA_MAX_IDS)
+            .map(|chunk| async move {
+                let request = NonFungibleDataRequest {
+                    resource_address,
+                    non_fungible_ids: chunk,
+                };
+                self.post::<StateNonFungibleDataResponse>("state/non-fungible/data", &request)
+                    .await
+            });
+
+        let mut responses = try_join_all(requests).await?.into_iter();
+        let mut response = responses
+            .next()
+            .ok_or_else(|| Error::NotFound(resource_address.to_string()))?;
+
+        for next_response in responses {
+            response
+                .non_fungible_ids
+                .extend(next_response.non_fungible_ids);
+        }
+
+        Ok(response)
+    }
+
+    async fn transactions_page(
+        &self,
+        address: &str,
+        cursor: Option<String>,
+        from_state_version: Option<i64>,
+        at_state_version: Option<i64>,
+    ) -> Result<StreamTransactionsResponse, Error> {
+        let request = StreamTransactionsRequest {
+            affected_global_entities_filter: &[address],
+            opt_ins: TransactionDetailsOptIns {
+                balance_changes: Some(true),
+                ..Default::default()
+            },
+            order: "Asc",
+            kind_filter: "User",
+            from_ledger_state: ledger_state(from_state_version),
+            at_ledger_state: ledger_state(at_state_version),
+            cursor,
+        };
+
+        self.post("stream/transactions", &request).await
+    }
+
+    /// Returns the current ledger state, used to get the epoch a new transaction is valid from
+    async fn transaction_construction(&self) -> Result<TransactionConstructionResponse, Error> {
+        self.post("transaction/construction", &json!({})).await
     }
 
-    fluent_request.into_future().await
+    /// Runs the manifest against the current ledger state without committing it.
+    /// Signatures are not checked, so the preview can be done before the user has entered the password
+    async fn preview_transaction(
+        &self,
+        manifest: &str,
+        start_epoch_inclusive: u64,
+        end_epoch_exclusive: u64,
+        message: Option<serde_json::Value>,
+        signer_public_keys: &[Ed25519PublicKey],
+    ) -> Result<TransactionPreviewResponse, Error> {
+        let request = TransactionPreviewRequest {
+            manifest,
+            start_epoch_inclusive: start_epoch_inclusive as i64,
+            end_epoch_exclusive: end_epoch_exclusive as i64,
+            flags: TransactionPreviewRequestFlags {
+                assume_all_signature_proofs: true,
+                skip_epoch_check: false,
+                use_free_credit: false,
+                ..Default::default()
+            },
+            nonce: rand::random::<u32>() as i64,
+            signer_public_keys: signer_public_keys
+                .iter()
+                .map(SignerPublicKey::eddsa_ed25519)
+                .collect(),
+            tip_percentage: 0,
+            message,
+        };
+
+        self.post("transaction/preview", &request).await
+    }
+
+    async fn submit_transaction(
+        &self,
+        notarized_transaction_hex: &str,
+    ) -> Result<TransactionSubmitResponse, Error> {
+        let request = TransactionSubmitRequest {
+            notarized_transaction_hex,
+        };
+
+        self.post("transaction/submit", &request).await
+    }
+
+    async fn transaction_status(
+        &self,
+        intent_hash: &str,
+    ) -> Result<TransactionStatusResponse, Error> {
+        let request = TransactionStatusRequest { intent_hash };
+
+        self.post("transaction/status", &request).await
+    }
 }
 
-pub async fn get_non_fungible_ids_from_vault(
-    network: Network,
-    account_address: &str,
-    resource_address: &str,
-    vault_address: &str,
+/// A cursor should be sent with the state version of the page it was returned with,
+/// see [`paginator::drain_pages`](super::paginator::drain_pages)
+fn ledger_state(state_version: Option<i64>) -> Option<LedgerStateSelector> {
+    state_version.map(|state_version| LedgerStateSelector {
+        state_version: Some(state_version),
+        ..Default::default()
+    })
+}
+
+// The request bodies of the Gateway API, built from the option models of the gateway sdk.
+// The sdk client itself only reaches the public gateway of a network, so requests are sent
+// through our own client to be able to use a configured gateway url, the proxy and retries
+
+#[derive(Serialize)]
+struct EntityDetailsRequest<'a> {
+    addresses: &'a [&'a str],
+    aggregation_level: ResourceAggregationLevel,
+    opt_ins: StateEntityDetailsOptIns,
+}
+
+#[derive(Serialize)]
+struct EntityPageRequest<'a> {
+    address: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aggregation_level: Option<ResourceAggregationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_address: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vault_address: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at_ledger_state: Option<LedgerStateSelector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     cursor: Option<String>,
-    at_state_version: Option<i64>,
-) -> Result<StateEntityNonFungibleIdsPageResponse, radix_gateway_sdk::Error> {
-    let client = Client::new(network.into(), None, None)?;
-    let mut fluent_request = client.get_inner_client().entity_non_fungible_ids_page(
-        account_address,
-        resource_address,
-        vault_address,
-    );
-
-    if at_state_version.is_some() {
-        fluent_request.params.at_ledger_state = Some(LedgerStateSelector {
-            state_version: at_state_version,
-            ..Default::default()
-        });
-        fluent_request.params.cursor = cursor;
-    }
+}
 
-    fluent_request.into_future().await
+impl<'a> EntityPageRequest<'a> {
+    fn new(address: &'a str, cursor: Option<String>, at_state_version: Option<i64>) -> Self {
+        Self {
+            address,
+            aggregation_level: None,
+            resource_address: None,
+            vault_address: None,
+            at_ledger_state: ledger_state(at_state_version),
+            cursor,
+        }
+    }
 }
 
-pub async fn get_non_fungible_data(
-    network: Network,
-    resource_address: &str,
-    non_fungible_ids: &[String],
-) -> Result<StateNonFungibleDataResponse, radix_gateway_sdk::Error> {
-    let tasks = non_fungible_ids.chunks(100).map(|chunk| {
-        let resource_address = unsafe{UnsafeRef::new(resource_address)};
-        let chunk = chunk.to_owned();
-
-        tokio::task::spawn(async move {
-            let addresses: Vec<&str> = chunk.iter().map(|address| address.as_str()).collect();
-            Client::new(network.into(), None, None)?
-                .get_inner_client()
-                .non_fungible_data(addresses.as_slice(), &*resource_address)
-                .into_future()
-                .await
-        })
-    });
-
-    join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|join_result| join_result.ok())
-        .fold(
-            Err(radix_gateway_sdk::Error::NetworkInvalid),
-            |acc, response_result| match response_result {
-                Ok(response) => match acc {
-                    Ok(mut ok_acc) => {
-                        ok_acc.non_fungible_ids.extend(response.non_fungible_ids);
-                        Ok(ok_acc)
-                    }
-                    Err(_) => Ok(response),
-                },
-                Err(e) => acc.map_err(|_| e),
-            },
-        )
+#[derive(Serialize)]
+struct NonFungibleDataRequest<'a> {
+    resource_address: &'a str,
+    non_fungible_ids: &'a [String],
 }
 
-pub async fn get_transactions_for_entity_from_ledger_state_version(
-    network: Network,
-    address: String,
+#[derive(Serialize)]
+struct StreamTransactionsRequest<'a> {
+    affected_global_entities_filter: &'a [&'a str],
+    opt_ins: TransactionDetailsOptIns,
+    order: &'static str,
+    kind_filter: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_ledger_state: Option<LedgerStateSelector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    at_ledger_state: Option<LedgerStateSelector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     cursor: Option<String>,
-    at_state_version: Option<i64>,
-) -> Result<StreamTransactionsResponse, radix_gateway_sdk::Error> {
-    let opt_ins = TransactionDetailsOptIns {
-        balance_changes: Some(true),
-        ..Default::default()
-    };
+}
 
-    let stream_transactions_required = StreamTransactionsRequired {
-        affected_global_entities_filter: &[address.as_str()],
-        opt_ins,
-        order: "Desc",
-        accounts_with_manifest_owner_method_calls: &[],
-        accounts_without_manifest_owner_method_calls: &[],
-        events_filter: Vec::new(),
-        kind_filter: "",
-        manifest_accounts_deposited_into_filter: &[],
-        manifest_accounts_withdrawn_from_filter: &[],
-        manifest_badges_presented_filter: &[],
-        manifest_class_filter: serde_json::Value::Null,
-        manifest_resources_filter: &[],
-    };
-    let client = Client::new(network.into(), None, None)?;
-    let mut fluent_request = client
-        .get_inner_client()
-        .stream_transactions(stream_transactions_required);
-
-    if at_state_version.is_some() {
-        fluent_request.params.at_ledger_state = Some(LedgerStateSelector {
-            state_version: at_state_version,
-            ..Default::default()
-        });
-        fluent_request.params.cursor = cursor;
+#[derive(Serialize)]
+struct TransactionPreviewRequest<'a> {
+    manifest: &'a str,
+    start_epoch_inclusive: i64,
+    end_epoch_exclusive: i64,
+    flags: TransactionPreviewRequestFlags,
+    nonce: i64,
+    signer_public_keys: Vec<SignerPublicKey>,
+    tip_percentage: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct SignerPublicKey {
+    key_type: &'static str,
+    key_hex: String,
+}
+
+impl SignerPublicKey {
+    fn eddsa_ed25519(public_key: &Ed25519PublicKey) -> Self {
+        Self {
+            key_type: "EddsaEd25519",
+            key_hex: public_key
+                .0
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+        }
     }
+}
+
+#[derive(Serialize)]
+struct TransactionSubmitRequest<'a> {
+    notarized_transaction_hex: &'a str,
+}
 
-    fluent_request.into_future().await
+#[derive(Serialize)]
+struct TransactionStatusRequest<'a> {
+    intent_hash: &'a str,
 }
 
 #[cfg(test)]
@@ -197,21 +517,121 @@ mod tests {
     use std::str::FromStr;
 
     use types::{
-        address::{AccountAddress, Address, ResourceAddress},
         Network,
+        address::{AccountAddress, Address, ResourceAddress},
     };
 
     use super::*;
+    use crate::radix_dlt::updates::update_accounts;
+    use types::{Account, Ed25519PublicKey};
+
+    /// Nothing listens on the discard port, so connections are refused right away
+    const UNREACHABLE_GATEWAY: &str = "http://127.0.0.1:9";
+
+    fn unreachable_gateway() -> RadixGateway {
+        let limits = RequestLimits {
+            max_retries: 0,
+            ..Default::default()
+        };
+        RadixGateway::with_settings(Network::Mainnet, UNREACHABLE_GATEWAY, limits, None)
+    }
+
+    #[test]
+    fn test_page_request_body() {
+        let request = EntityPageRequest {
+            aggregation_level: Some(ResourceAggregationLevel::Global),
+            ..EntityPageRequest::new("account_rdx1", None, None)
+        };
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["address"], "account_rdx1");
+        assert!(body.get("cursor").is_none());
+        assert!(body.get("at_ledger_state").is_none());
+        assert!(body.get("vault_address").is_none());
+
+        let request = EntityPageRequest::new("account_rdx1", Some("cursor".to_string()), Some(10));
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["cursor"], "cursor");
+        assert_eq!(body["at_ledger_state"]["state_version"], 10);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_gateway_is_not_reachable() {
+        assert!(!unreachable_gateway().is_reachable().await);
+    }
 
     #[tokio::test]
+    async fn test_entity_details_of_a_large_batch_fails_without_panicking() {
+        let addresses = ["account_rdx1"; ENTITY_DETAILS_MAX_ADDRESSES + 1];
+
+        assert!(matches!(
+            unreachable_gateway().entity_details(&addresses).await,
+            Err(Error::Request(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requests_fail_if_the_proxy_can_not_be_used() {
+        let proxy = Proxy::Http {
+            host: "not a host".to_string(),
+            port: 8080,
+        };
+        let gateway = RadixGateway::with_settings(
+            Network::Mainnet,
+            UNREACHABLE_GATEWAY,
+            RequestLimits::default(),
+            Some(proxy),
+        );
+
+        assert!(!gateway.is_reachable().await);
+        assert!(matches!(
+            gateway.transaction_construction().await,
+            Err(Error::Proxy(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_accounts_with_unreachable_gateway() {
+        let account = Account::new(
+            1,
+            "test".to_string(),
+            Network::Mainnet,
+            [0; 6],
+            AccountAddress::from_str(
+                "account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k",
+            )
+            .unwrap(),
+            Ed25519PublicKey([0; Ed25519PublicKey::LENGTH]),
+        );
+
+        let accounts_update = update_accounts(
+            unreachable_gateway(),
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![account],
+        )
+        .await;
+
+        assert!(accounts_update.every_account_failed());
+        assert!(
+            accounts_update
+                .failures
+                .iter()
+                .all(|failure| failure.error.starts_with("Request failed"))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live mainnet gateway"]
     async fn test_get_entities_details() {
         let account_address = AccountAddress::from_str(
             "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
         )
         .unwrap();
 
-        let network = Network::Mainnet;
-        let account_details = get_entity_details(network, &[account_address.as_str()])
+        let gateway = RadixGateway::new(Network::Mainnet, RequestLimits::default());
+        let account_details = gateway
+            .entity_details(&[account_address.as_str()])
             .await
             .unwrap();
 
@@ -223,7 +643,8 @@ mod tests {
             "resource_rdx1t4h4396mukhpzdrr5sfvegjsxl8q7a34q2vkt4quxcxahna8fucuz4",
         )
         .unwrap();
-        let resource_details = get_entity_details(network, &[resource_address.as_str()])
+        let resource_details = gateway
+            .entity_details(&[resource_address.as_str()])
             .await
             .unwrap();
 
@@ -231,21 +652,18 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "hits the live mainnet gateway"]
     async fn test_get_fungible_balances() {
-        let network = Network::Mainnet;
+        let gateway = RadixGateway::new(Network::Mainnet, RequestLimits::default());
 
         let account_address = AccountAddress::from_str(
             "account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k",
         )
         .unwrap();
 
-        let response = match get_fungible_balances_for_entity(
-            network.into(),
-            account_address.as_str(),
-            None,
-            None,
-        )
-        .await
+        let response = match gateway
+            .fungibles_page(account_address.as_str(), None, None)
+            .await
         {
             Ok(response) => response,
             Err(err) => panic!("{err}"),
@@ -257,13 +675,9 @@ mod tests {
         let cursor = response.next_cursor.clone();
 
         if cursor.is_some() {
-            let response = match get_fungible_balances_for_entity(
-                network.into(),
-                account_address.as_str(),
-                cursor,
-                ledger_state,
-            )
-            .await
+            let response = match gateway
+                .fungibles_page(account_address.as_str(), cursor, ledger_state)
+                .await
             {
                 Ok(response) => response,
                 Err(err) => panic!("{err}"),
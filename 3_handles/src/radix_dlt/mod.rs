@@ -1,3 +1,8 @@
+#[cfg(test)]
+pub mod fake_gateway;
 pub mod gateway_requests;
+pub mod paginator;
 pub mod parse_responses;
+pub mod transaction_status;
+pub mod transfer;
 pub mod updates;
@@ -0,0 +1,152 @@
+use deps::*;
+
+use std::future::Future;
+
+use ports::network_gateway::Error;
+use radix_gateway_sdk::generated::model::{
+    StateEntityFungiblesPageResponse, StateEntityNonFungibleIdsPageResponse,
+    StateEntityNonFungiblesPageResponse, StreamTransactionsResponse,
+};
+
+/// A page of a collection read from the gateway
+pub trait Page {
+    /// The state version the page was read at
+    fn state_version(&self) -> i64;
+    /// Takes the cursor of the page that follows, `None` if this is the last page
+    fn take_next_cursor(&mut self) -> Option<String>;
+}
+
+impl Page for StateEntityFungiblesPageResponse {
+    fn state_version(&self) -> i64 {
+        self.ledger_state_mixin.ledger_state.state_version
+    }
+
+    fn take_next_cursor(&mut self) -> Option<String> {
+        self.next_cursor.take()
+    }
+}
+
+impl Page for StateEntityNonFungiblesPageResponse {
+    fn state_version(&self) -> i64 {
+        self.ledger_state_mixin.ledger_state.state_version
+    }
+
+    fn take_next_cursor(&mut self) -> Option<String> {
+        self.non_fungible_resources_collection.next_cursor.take()
+    }
+}
+
+impl Page for StateEntityNonFungibleIdsPageResponse {
+    fn state_version(&self) -> i64 {
+        self.ledger_state.state_version
+    }
+
+    fn take_next_cursor(&mut self) -> Option<String> {
+        self.non_fungible_ids_collection.next_cursor.take()
+    }
+}
+
+impl Page for StreamTransactionsResponse {
+    fn state_version(&self) -> i64 {
+        self.ledger_state.state_version
+    }
+
+    fn take_next_cursor(&mut self) -> Option<String> {
+        self.next_cursor.take()
+    }
+}
+
+/// Requests every page of a collection, `request_page` takes the cursor and the state version
+/// to read the page at.
+///
+/// All pages are read at the same ledger state so the collection does not change while paging.
+/// The state is `at_state_version` if given, otherwise the state the first page was read at.
+/// Returns the state version together with the pages in order, fails if any page fails
+pub async fn drain_pages<P, F, Fut>(
+    at_state_version: Option<i64>,
+    mut request_page: F,
+) -> Result<(i64, Vec<P>), Error>
+where
+    P: Page,
+    F: FnMut(Option<String>, Option<i64>) -> Fut,
+    Fut: Future<Output = Result<P, Error>>,
+{
+    let mut page = request_page(None, at_state_version).await?;
+    let state_version = at_state_version.unwrap_or(page.state_version());
+    let mut next_cursor = page.take_next_cursor();
+    let mut pages = vec![page];
+
+    while let Some(cursor) = next_cursor {
+        let mut page = request_page(Some(cursor), Some(state_version)).await?;
+        next_cursor = page.take_next_cursor();
+        pages.push(page);
+    }
+
+    Ok((state_version, pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestPage {
+        state_version: i64,
+        next_cursor: Option<String>,
+    }
+
+    impl Page for TestPage {
+        fn state_version(&self) -> i64 {
+            self.state_version
+        }
+
+        fn take_next_cursor(&mut self) -> Option<String> {
+            self.next_cursor.take()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_pages_at_pinned_state_version() {
+        let mut requests = Vec::new();
+
+        let (state_version, pages) = drain_pages(None, |cursor, at_state_version| {
+            requests.push((cursor.clone(), at_state_version));
+            let index = cursor.map_or(0, |cursor| cursor.parse::<i64>().unwrap());
+            async move {
+                Ok(TestPage {
+                    // The ledger moves on while paging
+                    state_version: 1000 + index,
+                    next_cursor: (index < 2).then(|| (index + 1).to_string()),
+                })
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(state_version, 1000);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            requests,
+            vec![
+                (None, None),
+                (Some("1".to_string()), Some(1000)),
+                (Some("2".to_string()), Some(1000)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drain_pages_fails_if_a_page_fails() {
+        let result = drain_pages(Some(500), |cursor, _| async move {
+            match cursor {
+                None => Ok(TestPage {
+                    state_version: 500,
+                    next_cursor: Some("1".to_string()),
+                }),
+                Some(cursor) => Err(Error::NotFound(format!("cursor {cursor}"))),
+            }
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+}
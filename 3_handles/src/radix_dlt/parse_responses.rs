@@ -1,29 +1,35 @@
 use deps::*;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 
 use debug_print::debug_println;
 use radix_gateway_sdk::generated::model::{
     StateEntityDetailsResponseItem, StateEntityFungiblesPageResponse,
-    StateEntityNonFungiblesPageResponse, StateNonFungibleDataResponse,
+    StateEntityNonFungiblesPageResponse, StateNonFungibleDataResponse, StreamTransactionsResponse,
+    TransactionPreviewResponse,
 };
 
-use types::address::{AccountAddress, ResourceAddress};
+use types::address::{AccountAddress, Address, ResourceAddress, TransactionAddress};
 use types::assets::{FungibleAsset, NFT, NFTs, NonFungibleAsset};
 use types::response_models::non_fungible_id_data::NFIdData;
 use types::response_models::{
-    FungibleCollectionItemGlobal, MetaDataStringArrayValue, MetaDataStringValue,
-    NonFungibleCollectionItemVaultAggregated, ResourceDetails,
+    BalanceChanges, FeeSummary, FungibleCollectionItemGlobal, MetaDataStringArrayValue,
+    MetaDataStringValue, NonFungibleCollectionItemVaultAggregated, ResourceDetails,
+    TransactionResponse, TransactionsResponse,
+};
+use types::{
+    BalanceChange, FeeBreakdown, Network, RadixDecimal, Resource, ResourceChange, TimeStamp,
+    Transaction, TransactionId, TransactionPreview, TransactionStatus, debug_info,
 };
-use types::{Resource, debug_info};
 
 /// Returns two tuples, first with the resource_address and resource and the second with the resource_address and url to the resources icon
 /// Returns None if the resource_address conversion failes.
 pub fn parse_resource_details_response(
     response: StateEntityDetailsResponseItem,
+    network: Network,
 ) -> Option<(ResourceAddress, (Resource, String))> {
-    let resource_address = ResourceAddress::from_str(&response.address).ok()?;
+    let resource_address = ResourceAddress::from_str_on(network, &response.address).ok()?;
 
     let (current_supply, divisibility) = response
         .details
@@ -94,76 +100,104 @@ pub fn parse_resource_details_response(
     Some((resource_address, (resource, icon_url)))
 }
 
+/// Returns the resources the account holds a balance of, together with the assets updated since
+/// `last_updated_at_state_version`. Emptied vaults are still listed by the gateway, they are
+/// left out of both
 pub fn parse_fungible_balances_response(
     response: StateEntityFungiblesPageResponse,
     last_updated_at_state_version: i64,
     account_address: &AccountAddress,
-) -> HashMap<ResourceAddress, FungibleAsset> {
-    response
-        .fungible_resources_collection
-        .items
-        .into_iter()
-        .filter_map(|item| {
-            let fungible = serde_json::from_value::<FungibleCollectionItemGlobal>(item.0)
-                .inspect_err(|err| {
-                    debug_println!("{}:{}", debug_info!("Failed to parse Json value"), err)
-                })
-                .ok()?;
+) -> (
+    HashSet<ResourceAddress>,
+    HashMap<ResourceAddress, FungibleAsset>,
+) {
+    let mut held = HashSet::new();
+    let mut updated = HashMap::new();
 
-            if fungible.last_updated_at_state_version < last_updated_at_state_version {
-                return None;
-            }
+    for item in response.fungible_resources_collection.items {
+        let Some(fungible) = serde_json::from_value::<FungibleCollectionItemGlobal>(item.0)
+            .inspect_err(|err| {
+                debug_println!("{}:{}", debug_info!("Failed to parse Json value"), err)
+            })
+            .ok()
+        else {
+            continue;
+        };
 
-            let resource_address = ResourceAddress::from_str(&fungible.resource_address).ok()?;
+        let Ok(resource_address) =
+            ResourceAddress::from_str_on(account_address.network(), &fungible.resource_address)
+        else {
+            continue;
+        };
 
-            let asset =
-                FungibleAsset::new(&account_address, fungible.amount, resource_address.clone());
+        if RadixDecimal::from_str(&fungible.amount).is_ok_and(|amount| amount == RadixDecimal::ZERO)
+        {
+            continue;
+        }
 
-            Some((resource_address, asset))
-        })
-        .collect()
+        held.insert(resource_address.clone());
+
+        if fungible.last_updated_at_state_version < last_updated_at_state_version {
+            continue;
+        }
+
+        let asset = FungibleAsset::new(&account_address, fungible.amount, resource_address.clone());
+        updated.insert(resource_address, asset);
+    }
+
+    (held, updated)
 }
 
-/// Returns a `HashMap` with a tuple value `(String, NonFungibleAsset)`, the `String` represents the vault address
-/// to later be able to get the nfids of the returned asset
+/// Returns the resources the account holds ids of, together with the assets updated since
+/// `last_updated_at_state_version`. The updated assets are in a tuple `(String, NonFungibleAsset)`,
+/// the `String` represents the vault address to later be able to get the nfids of the asset
 pub fn parse_non_fungible_balances_response_without_nfids(
     response: StateEntityNonFungiblesPageResponse,
     last_updated_at_state_version: i64,
     account_address: &AccountAddress,
-) -> HashMap<ResourceAddress, (String, NonFungibleAsset)> {
-    response
-        .non_fungible_resources_collection
-        .items
-        .into_iter()
-        .filter_map(|item| {
-            let mut collection_item =
-                serde_json::from_value::<NonFungibleCollectionItemVaultAggregated>(item.0)
-                    .inspect_err(|err| {
-                        debug_println!("{}:{}", debug_info!("Failed to parse Json value"), err)
-                    })
-                    .ok()?;
+) -> (
+    HashSet<ResourceAddress>,
+    HashMap<ResourceAddress, (String, NonFungibleAsset)>,
+) {
+    let mut held = HashSet::new();
+    let mut updated = HashMap::new();
 
-            let resource_address =
-                ResourceAddress::from_str(collection_item.resource_address.as_str()).ok()?;
+    for item in response.non_fungible_resources_collection.items {
+        let collection_item = serde_json::from_value::<NonFungibleCollectionItemVaultAggregated>(
+            item.0,
+        )
+        .inspect_err(|err| debug_println!("{}:{}", debug_info!("Failed to parse Json value"), err));
+        let Ok(mut collection_item) = collection_item else {
+            continue;
+        };
 
-            // This collection should always return one element
-            if collection_item.vaults.items.len() > 0 {
-                let vault = collection_item.vaults.items.remove(0);
-                if vault.last_updated_at_state_version < last_updated_at_state_version {
-                    return None;
-                }
+        let Ok(resource_address) = ResourceAddress::from_str_on(
+            account_address.network(),
+            collection_item.resource_address.as_str(),
+        ) else {
+            continue;
+        };
 
-                let vault_address = vault.vault_address;
+        // This collection should always return one element
+        if collection_item.vaults.items.is_empty() {
+            continue;
+        }
+        let vault = collection_item.vaults.items.remove(0);
+        if vault.total_count == 0 {
+            continue;
+        }
 
-                let asset =
-                    NonFungibleAsset::new(account_address, NFTs::new(), resource_address.clone());
+        held.insert(resource_address.clone());
 
-                return Some((resource_address, (vault_address, asset)));
-            }
+        if vault.last_updated_at_state_version < last_updated_at_state_version {
+            continue;
+        }
 
-            None
-        })
-        .collect()
+        let asset = NonFungibleAsset::new(account_address, NFTs::new(), resource_address.clone());
+        updated.insert(resource_address, (vault.vault_address, asset));
+    }
+
+    (held, updated)
 }
 
 pub fn parse_non_fungibles_data_response_for_asset(
@@ -202,8 +236,285 @@ pub fn parse_non_fungibles_data_response_for_asset(
     asset
 }
 
+/// The preview receipt is untyped json in the gateway models, so the whole response is parsed through our own models.
+/// Changes to entities that are not accounts, or amounts that can't be parsed, are skipped
+pub fn parse_transaction_preview_response(
+    response: TransactionPreviewResponse,
+    network: Network,
+    guaranteed_changes: Vec<ResourceChange>,
+) -> Option<TransactionPreview> {
+    let response = serde_json::to_value(response)
+        .and_then(serde_json::from_value::<types::response_models::TransactionPreviewResponse>)
+        .inspect_err(|err| {
+            debug_println!("{}:{}", debug_info!("Failed to parse preview response"), err)
+        })
+        .ok()?;
+
+    let estimated_changes = response
+        .resource_changes
+        .into_iter()
+        .flat_map(|instruction| instruction.resource_changes)
+        .filter_map(|change| {
+            Some(ResourceChange {
+                account: AccountAddress::from_str_on(
+                    network,
+                    &change.component_entity.entity_address,
+                )
+                .ok()?,
+                resource: ResourceAddress::from_str_on(network, &change.resource_address).ok()?,
+                amount: RadixDecimal::from_str(&change.amount).ok()?,
+            })
+        })
+        .collect();
+
+    let succeeded = response.receipt.status == "Succeeded";
+    let fee = match response.receipt.fee_summary {
+        Some(fee_summary) => parse_fee_summary(fee_summary)?,
+        // Rejected transactions are not charged, a fee of zero is never shown for a successful one
+        None if !succeeded => FeeBreakdown::default(),
+        None => return None,
+    };
+
+    Some(TransactionPreview {
+        succeeded,
+        error_message: response.receipt.error_message,
+        guaranteed_changes,
+        estimated_changes,
+        fee,
+    })
+}
+
+/// Returns `None` if any of the costs can't be parsed, so a failed parse is not shown as a lower fee
+fn parse_fee_summary(fee_summary: FeeSummary) -> Option<FeeBreakdown> {
+    let parse = |amount: &str| {
+        RadixDecimal::from_str(amount)
+            .inspect_err(|err| {
+                debug_println!("{}{}:{:?}", debug_info!("Failed to parse fee "), amount, err)
+            })
+            .ok()
+    };
+
+    Some(FeeBreakdown {
+        execution: parse(&fee_summary.xrd_total_execution_cost)?,
+        finalization: parse(&fee_summary.xrd_total_finalization_cost)?,
+        storage: parse(&fee_summary.xrd_total_storage_cost)?,
+        royalty: parse(&fee_summary.xrd_total_royalty_cost)?,
+        tipping: parse(&fee_summary.xrd_total_tipping_cost)?,
+    })
+}
+
+/// Returns the state version of the ledger at the time of the response, the cursor to the next page
+/// and the transactions with the balance changes of `account_address`
+pub fn parse_transactions_response(
+    response: StreamTransactionsResponse,
+    account_address: &AccountAddress,
+) -> Option<(i64, Option<String>, Vec<Transaction>)> {
+    let response = serde_json::to_value(response)
+        .and_then(serde_json::from_value::<TransactionsResponse>)
+        .inspect_err(|err| {
+            debug_println!("{}:{}", debug_info!("Failed to parse transactions response"), err)
+        })
+        .ok()?;
+
+    let transactions = response
+        .items
+        .into_iter()
+        .filter_map(|item| parse_transaction(item, account_address))
+        .collect();
+
+    Some((
+        response.ledger_state.state_version as i64,
+        response.next_cursor,
+        transactions,
+    ))
+}
+
+/// Transactions without an intent hash are not user transactions and are skipped
+fn parse_transaction(
+    response: TransactionResponse,
+    account_address: &AccountAddress,
+) -> Option<Transaction> {
+    let transaction_address =
+        TransactionAddress::from_str_on(account_address.network(), response.intent_hash.as_ref()?)
+            .ok()?;
+    let timestamp = TimeStamp::from_iso8601(&response.round_timestamp)?;
+
+    let status = match response.transaction_status.as_str() {
+        "CommittedSuccess" => TransactionStatus::Success,
+        _ => TransactionStatus::Failed,
+    };
+
+    let message = response
+        .message
+        .filter(|message| message.content.content_type == "String")
+        .map(|message| message.content.value);
+
+    let fee = response
+        .balance_changes
+        .as_ref()
+        .and_then(|balance_changes| parse_fee(balance_changes, account_address));
+
+    let transaction_id = TransactionId::new(account_address, &transaction_address);
+    let balance_changes = response
+        .balance_changes
+        .map(|balance_changes| {
+            parse_balance_changes(balance_changes, &transaction_id, account_address)
+        })
+        .unwrap_or_default();
+
+    Some(Transaction {
+        id: transaction_id,
+        transaction_address,
+        timestamp,
+        state_version: response.state_version,
+        balance_changes,
+        message,
+        status,
+        fee,
+    })
+}
+
+/// The fee paid by the account as a positive amount, `None` if the account did not pay any fee
+fn parse_fee(balance_changes: &BalanceChanges, account_address: &AccountAddress) -> Option<String> {
+    let fee = balance_changes
+        .fungible_fee_balance_changes
+        .iter()
+        .filter(|change| change.entity_address == account_address.as_str())
+        .filter_map(|change| RadixDecimal::from_str(&change.balance_change).ok())
+        .reduce(|total, amount| total + amount)?;
+
+    Some((-fee).to_string())
+}
+
+/// Only changes to `account_address` are kept. Fees are added to the fungible change of the same resource
+/// so the changes add up to the change of the account balance.
+/// Non fungible changes hold the net change in number of NFTs as amount, together with the deposited and the withdrawn ids
+fn parse_balance_changes(
+    balance_changes: BalanceChanges,
+    transaction_id: &TransactionId,
+    account_address: &AccountAddress,
+) -> Vec<BalanceChange> {
+    let mut fungible_changes: BTreeMap<ResourceAddress, RadixDecimal> = BTreeMap::new();
+
+    let fee_changes = balance_changes
+        .fungible_fee_balance_changes
+        .into_iter()
+        .map(|change| (change.entity_address, change.resource_address, change.balance_change));
+    let changes = balance_changes
+        .fungible_balance_changes
+        .into_iter()
+        .map(|change| (change.entity_address, change.resource_address, change.balance_change));
+
+    for (entity_address, resource_address, balance_change) in fee_changes.chain(changes) {
+        if entity_address != account_address.as_str() {
+            continue;
+        }
+        let (Ok(resource_address), Ok(amount)) = (
+            ResourceAddress::from_str_on(account_address.network(), &resource_address),
+            RadixDecimal::from_str(&balance_change),
+        ) else {
+            continue;
+        };
+
+        fungible_changes
+            .entry(resource_address)
+            .and_modify(|total| *total = *total + amount)
+            .or_insert(amount);
+    }
+
+    let fungible_changes = fungible_changes
+        .into_iter()
+        .map(|(resource_address, amount)| {
+            BalanceChange::new(
+                transaction_id.clone(),
+                account_address.clone(),
+                resource_address,
+                None,
+                None,
+                Some(amount.to_string()),
+            )
+        });
+
+    let non_fungible_changes = balance_changes
+        .non_fungible_balance_changes
+        .into_iter()
+        .filter(|change| change.entity_address == account_address.as_str())
+        .filter_map(|change| {
+            let resource_address =
+                ResourceAddress::from_str_on(account_address.network(), &change.resource_address)
+                    .ok()?;
+            let net_change = change.added.len() as i64 - change.removed.len() as i64;
+
+            let added = change.added.into_iter().map(NFT::new).collect::<NFTs>();
+            let removed = change.removed.into_iter().map(NFT::new).collect::<NFTs>();
+
+            Some(BalanceChange::new(
+                transaction_id.clone(),
+                account_address.clone(),
+                resource_address,
+                Some(added),
+                Some(removed),
+                Some(net_change.to_string()),
+            ))
+        });
+
+    fungible_changes.chain(non_fungible_changes).collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transaction() {
+        let account_address = AccountAddress::from_str(
+            "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax",
+        )
+        .unwrap();
+        let xrd = "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
+        let collection = "resource_rdx1nfxxxxxxxxxxsecpsgxxxxxxxxx004638826440xxxxxxxxxwj8qq5";
+
+        let response = serde_json::from_value::<TransactionResponse>(serde_json::json!({
+            "transaction_status": "CommittedSuccess",
+            "state_version": 100,
+            "intent_hash": "txid_rdx1v5ma7z8u6tn8jwhypwlt5eyjzuq9ch4c5k8ejftsn0xkjpqzfjysvl0q6y",
+            "round_timestamp": "2024-03-07T14:40:35.123Z",
+            "message": { "type": "Plaintext", "content": { "type": "String", "value": "hello" } },
+            "balance_changes": {
+                "fungible_fee_balance_changes": [
+                    { "entity_address": account_address.as_str(), "resource_address": xrd, "balance_change": "-0.5" }
+                ],
+                "fungible_balance_changes": [
+                    { "entity_address": account_address.as_str(), "resource_address": xrd, "balance_change": "-10" },
+                    { "entity_address": "account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k", "resource_address": xrd, "balance_change": "10" }
+                ],
+                "non_fungible_balance_changes": [
+                    { "entity_address": account_address.as_str(), "resource_address": collection, "added": ["#1#"], "removed": ["#2#", "#3#"] }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let transaction = parse_transaction(response, &account_address).unwrap();
+
+        assert_eq!(transaction.status, TransactionStatus::Success);
+        assert_eq!(transaction.state_version, 100);
+        assert_eq!(transaction.message.as_deref(), Some("hello"));
+        assert_eq!(transaction.timestamp, TimeStamp::new(2024, 3, 7, 14, 40, 35));
+        assert_eq!(transaction.balance_changes.len(), 2);
+        assert_eq!(transaction.balance_changes[0].amount.as_deref(), Some("-10.5"));
+        assert_eq!(transaction.fee.as_deref(), Some("0.5"));
+
+        let nft_change = &transaction.balance_changes[1];
+        let ids = |nfts: &Option<NFTs>| {
+            nfts.iter()
+                .flat_map(|nfts| nfts.iter().map(|nft| nft.id.as_str()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(nft_change.amount.as_deref(), Some("-1"));
+        assert_eq!(ids(&nft_change.nfts), ["#1#"]);
+        assert_eq!(ids(&nft_change.removed_nfts), ["#2#", "#3#"]);
+    }
 
     // #[tokio::test]
     // async fn test_update_assets_from_account_details_response() {
@@ -0,0 +1,99 @@
+use deps::*;
+
+use debug_print::debug_println;
+use futures::future::join_all;
+use ports::network_gateway::{self, NetworkGateway};
+use radix_gateway_sdk::generated::model::TransactionStatus as GatewayTransactionStatus;
+use store::AppDataDb;
+use types::{
+    AppError, Network, Notification, TransactionStatus,
+    address::{Address, TransactionAddress},
+};
+
+/// Checks the status of each pending transaction with the gateway.
+/// Transactions that have been committed or rejected are updated in the database and returned with their new status,
+/// transactions that are still pending or could not be reached are left out
+pub async fn poll_pending_transactions<G: NetworkGateway>(
+    gateway: G,
+    network: Network,
+    transactions: Vec<TransactionAddress>,
+) -> Result<Vec<(TransactionAddress, TransactionStatus)>, AppError> {
+    let db =
+        AppDataDb::get(network).ok_or(AppError::Fatal("Database not initialized".to_string()))?;
+
+    let tasks = transactions.into_iter().map(|transaction_address| {
+        let gateway = gateway.clone();
+        tokio::spawn(async move {
+            let response = gateway
+                .transaction_status(transaction_address.as_str())
+                .await?;
+            Ok::<_, network_gateway::Error>((transaction_address, response))
+        })
+    });
+
+    let mut resolved = Vec::new();
+
+    for result in join_all(tasks).await {
+        let (transaction_address, response) = match result {
+            Ok(Ok(response)) => response,
+            Ok(Err(err)) => {
+                debug_println!("Failed to get transaction status: {}", err);
+                continue;
+            }
+            Err(err) => {
+                debug_println!("Failed to join task: {}", err);
+                continue;
+            }
+        };
+
+        let status = transaction_status(&response.status);
+        if status == TransactionStatus::Pending {
+            continue;
+        }
+
+        let state_version = response.committed_state_version.unwrap_or(0) as u64;
+
+        db.update_transaction_status(transaction_address.clone(), status.clone(), state_version)
+            .await
+            .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+
+        resolved.push((transaction_address, status));
+    }
+
+    Ok(resolved)
+}
+
+/// A transaction the gateway does not know about yet is still considered pending,
+/// it might not have reached the mempool
+pub fn transaction_status(status: &GatewayTransactionStatus) -> TransactionStatus {
+    match status {
+        GatewayTransactionStatus::CommittedSuccess => TransactionStatus::Success,
+        GatewayTransactionStatus::CommittedFailure | GatewayTransactionStatus::Rejected => {
+            TransactionStatus::Failed
+        }
+        GatewayTransactionStatus::Pending | GatewayTransactionStatus::Unknown => {
+            TransactionStatus::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_status() {
+        assert_eq!(
+            transaction_status(&GatewayTransactionStatus::CommittedSuccess),
+            TransactionStatus::Success
+        );
+        assert_eq!(
+            transaction_status(&GatewayTransactionStatus::Rejected),
+            TransactionStatus::Failed
+        );
+        assert_eq!(
+            transaction_status(&GatewayTransactionStatus::Unknown),
+            TransactionStatus::Pending
+        );
+    }
+}
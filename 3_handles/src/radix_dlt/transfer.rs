@@ -0,0 +1,549 @@
+use deps::*;
+
+use std::str::FromStr;
+
+use ports::network_gateway::{self, NetworkGateway};
+use radix_transactions::{
+    builder::{ManifestBuilder, TransactionBuilder},
+    manifest::decompile,
+    model::{
+        MessageV1, NotarizedTransactionV1, PlaintextMessageV1, TransactionHeaderV1,
+        TransactionManifestV1,
+    },
+    prelude::{PreparationSettings, TransactionHashBech32Encoder},
+};
+use scrypto::{
+    address::AddressBech32Decoder,
+    crypto::Ed25519PublicKey,
+    prelude::{Epoch, NonFungibleLocalId},
+    types::{ComponentAddress, ResourceAddress as RadixResourceAddress},
+};
+use thiserror::Error;
+use types::{
+    Network, TransactionPreview, Transfer,
+    address::{AccountAddress, Address, ResourceAddress, TransactionAddress},
+    crypto::Ed25519KeyPair,
+};
+
+use super::parse_responses;
+
+/// The number of epochs a transaction is valid for after it was notarized, one epoch is roughly 5 minutes
+pub const TRANSACTION_VALID_EPOCHS: u64 = 10;
+
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("Nothing to transfer")]
+    EmptyTransfer,
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid non-fungible id: {0}")]
+    InvalidNonFungibleId(String),
+    #[error("Non-fungible {0} is sent to more than one recipient")]
+    DuplicateNonFungible(String),
+    #[error("Signing key does not belong to account {0}")]
+    KeyMismatch(String),
+    #[error("Missing signing key for account {0}")]
+    MissingKey(String),
+    #[error("Unable to prepare transaction: {0}")]
+    PrepareError(String),
+    #[error("Error connecting to gateway\n ∟{0}")]
+    GatewayError(#[from] network_gateway::Error),
+    #[error("Unable to parse preview response")]
+    PreviewParseError,
+}
+
+/// A notarized transaction ready to be submitted to the network
+pub struct NotarizedTransfer {
+    pub intent_hash: TransactionAddress,
+    pub notarized_transaction_hex: String,
+}
+
+/// Builds, signs and submits the transfer, returns the intent hash of the submitted transaction.
+/// The key pair must be derived from the path of the sending account, which notarizes the
+/// transaction. The signer key pairs must cover every other account in [`Transfer::signers`].
+pub async fn submit_transfer<G: NetworkGateway>(
+    gateway: G,
+    network: Network,
+    transfer: Transfer,
+    key_pair: Ed25519KeyPair,
+    signer_key_pairs: Vec<Ed25519KeyPair>,
+) -> Result<TransactionAddress, TransferError> {
+    if key_pair.bech32_address() != transfer.from_account.as_str() {
+        return Err(TransferError::KeyMismatch(transfer.from_account.truncate()));
+    }
+
+    for signer in transfer.signers() {
+        if signer != &transfer.from_account
+            && !signer_key_pairs
+                .iter()
+                .any(|signer_key_pair| signer_key_pair.bech32_address() == signer.as_str())
+        {
+            return Err(TransferError::MissingKey(signer.truncate()));
+        }
+    }
+
+    let manifest = build_transfer_manifest(network, &transfer)?;
+
+    let construction = gateway.transaction_construction().await?;
+    let current_epoch = construction.ledger_state.epoch as u64;
+
+    let notarized = notarize_transaction(
+        network,
+        manifest,
+        transfer.message.as_deref(),
+        &key_pair,
+        &signer_key_pairs,
+        current_epoch,
+    )?;
+    drop(key_pair);
+    drop(signer_key_pairs);
+
+    gateway
+        .submit_transaction(&notarized.notarized_transaction_hex)
+        .await?;
+
+    Ok(notarized.intent_hash)
+}
+
+/// Runs the transfer through the gateway preview, the guaranteed changes are taken from the transfer itself
+/// since every deposit in the manifest is of an exact amount.
+/// The public keys of every signer, including the notary, are needed for the fee to cover the signatures
+pub async fn preview_transfer<G: NetworkGateway>(
+    gateway: G,
+    network: Network,
+    transfer: &Transfer,
+    signer_public_keys: &[Ed25519PublicKey],
+) -> Result<TransactionPreview, TransferError> {
+    let manifest = build_transfer_manifest(network, transfer)?;
+    let manifest = decompile(&manifest, &network.definition())
+        .map_err(|err| TransferError::PrepareError(format!("{err:?}")))?;
+
+    let construction = gateway.transaction_construction().await?;
+    let current_epoch = construction.ledger_state.epoch as u64;
+
+    let message = transfer
+        .message
+        .as_ref()
+        .filter(|message| !message.trim().is_empty())
+        .map(|message| {
+            serde_json::json!({
+                "type": "Plaintext",
+                "mime_type": "text/plain",
+                "content": { "type": "String", "value": message },
+            })
+        });
+
+    let response = gateway
+        .preview_transaction(
+            &manifest,
+            current_epoch,
+            current_epoch + TRANSACTION_VALID_EPOCHS,
+            message,
+            signer_public_keys,
+        )
+        .await?;
+
+    parse_responses::parse_transaction_preview_response(
+        response,
+        network,
+        transfer.guaranteed_changes(),
+    )
+    .ok_or(TransferError::PreviewParseError)
+}
+
+/// Locks the fee from the fee payer, withdraws the total of each resource and the selected
+/// non-fungibles from each source account and deposits to each recipient with
+/// `try_deposit_or_abort`, so the transaction fails if a recipient does not accept the deposit
+pub fn build_transfer_manifest(
+    network: Network,
+    transfer: &Transfer,
+) -> Result<TransactionManifestV1, TransferError> {
+    if transfer.is_empty() {
+        return Err(TransferError::EmptyTransfer);
+    }
+    if let Some((_, nfid)) = transfer.duplicate_non_fungible() {
+        return Err(TransferError::DuplicateNonFungible(nfid.clone()));
+    }
+
+    let decoder = AddressBech32Decoder::new(&network.definition());
+    let fee_payer = to_component_address(&decoder, &transfer.fee.payer)?;
+
+    let mut builder = ManifestBuilder::new().lock_fee(fee_payer, transfer.fee.lock_amount());
+
+    for ((source, resource_address), amount) in transfer.withdrawals() {
+        let source = to_component_address(&decoder, source)?;
+        let resource_address = to_resource_address(&decoder, resource_address)?;
+        builder = builder.withdraw_from_account(source, resource_address, amount);
+    }
+
+    for ((source, resource_address), nfids) in transfer.non_fungible_withdrawals() {
+        let source = to_component_address(&decoder, source)?;
+        let resource_address = to_resource_address(&decoder, resource_address)?;
+        let nfids = to_non_fungible_local_ids(nfids)?;
+        builder = builder.withdraw_non_fungibles_from_account(source, resource_address, nfids);
+    }
+
+    for (recipient_index, recipient) in transfer.recipients.iter().enumerate() {
+        let recipient_address = to_component_address(&decoder, &recipient.address)?;
+
+        for (resource_index, resource) in recipient.resources.iter().enumerate() {
+            let resource_address = to_resource_address(&decoder, &resource.resource)?;
+            let bucket = format!("bucket_{recipient_index}_{resource_index}");
+
+            builder = builder
+                .take_from_worktop(resource_address, resource.amount, bucket.as_str())
+                .try_deposit_or_abort(recipient_address, None, bucket.as_str());
+        }
+
+        for (nft_index, non_fungibles) in recipient.non_fungibles.iter().enumerate() {
+            if non_fungibles.nfids.is_empty() {
+                continue;
+            }
+            let resource_address = to_resource_address(&decoder, &non_fungibles.resource)?;
+            let nfids = to_non_fungible_local_ids(&non_fungibles.nfids)?;
+            let bucket = format!("nft_bucket_{recipient_index}_{nft_index}");
+
+            builder = builder
+                .take_non_fungibles_from_worktop(resource_address, nfids, bucket.as_str())
+                .try_deposit_or_abort(recipient_address, None, bucket.as_str());
+        }
+    }
+
+    Ok(builder.build())
+}
+
+/// Notarizes the transaction with the key pair, which also counts as its signature.
+/// Each signer key pair signs the intent before it is notarized.
+pub fn notarize_transaction(
+    network: Network,
+    manifest: TransactionManifestV1,
+    message: Option<&str>,
+    key_pair: &Ed25519KeyPair,
+    signer_key_pairs: &[Ed25519KeyPair],
+    current_epoch: u64,
+) -> Result<NotarizedTransfer, TransferError> {
+    let network_definition = network.definition();
+    let private_key = key_pair.radixdlt_private_key();
+
+    let header = TransactionHeaderV1 {
+        network_id: network_definition.id,
+        start_epoch_inclusive: Epoch::of(current_epoch),
+        end_epoch_exclusive: Epoch::of(current_epoch + TRANSACTION_VALID_EPOCHS),
+        nonce: rand::random::<u32>(),
+        notary_public_key: key_pair.radixdlt_public_key().into(),
+        notary_is_signatory: true,
+        tip_percentage: 0,
+    };
+
+    let message = match message {
+        Some(message) if !message.trim().is_empty() => {
+            MessageV1::Plaintext(PlaintextMessageV1::text(message))
+        }
+        _ => MessageV1::None,
+    };
+
+    let mut builder = TransactionBuilder::new()
+        .header(header)
+        .manifest(manifest)
+        .message(message);
+
+    for signer_key_pair in signer_key_pairs {
+        builder = builder.sign(&signer_key_pair.radixdlt_private_key());
+    }
+
+    let transaction: NotarizedTransactionV1 = builder.notarize(&private_key).build();
+
+    let intent_hash = transaction
+        .prepare(&PreparationSettings::latest())
+        .map_err(|err| TransferError::PrepareError(format!("{err:?}")))?
+        .transaction_intent_hash();
+
+    let intent_hash = TransactionHashBech32Encoder::new(&network_definition)
+        .encode(&intent_hash)
+        .map_err(|err| TransferError::PrepareError(format!("{err:?}")))?;
+
+    let notarized_transaction_hex = transaction
+        .to_raw()
+        .map_err(|err| TransferError::PrepareError(format!("{err:?}")))?
+        .to_hex();
+
+    Ok(NotarizedTransfer {
+        intent_hash: TransactionAddress::from_str_on(network, &intent_hash)
+            .map_err(|_| TransferError::InvalidAddress(intent_hash))?,
+        notarized_transaction_hex,
+    })
+}
+
+fn to_component_address(
+    decoder: &AddressBech32Decoder,
+    address: &AccountAddress,
+) -> Result<ComponentAddress, TransferError> {
+    ComponentAddress::try_from_bech32(decoder, address.as_str())
+        .ok_or(TransferError::InvalidAddress(address.to_string()))
+}
+
+fn to_resource_address(
+    decoder: &AddressBech32Decoder,
+    address: &ResourceAddress,
+) -> Result<RadixResourceAddress, TransferError> {
+    RadixResourceAddress::try_from_bech32(decoder, address.as_str())
+        .ok_or(TransferError::InvalidAddress(address.to_string()))
+}
+
+fn to_non_fungible_local_ids<'a>(
+    nfids: impl IntoIterator<Item = &'a String>,
+) -> Result<Vec<NonFungibleLocalId>, TransferError> {
+    nfids
+        .into_iter()
+        .map(|nfid| {
+            NonFungibleLocalId::from_str(nfid)
+                .map_err(|_| TransferError::InvalidNonFungibleId(nfid.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bip39::{Language, Mnemonic};
+    use types::{
+        RadixDecimal, TransferNonFungibles, TransferRecipient, TransferResource,
+        crypto::{Bip32Entity, Bip32KeyKind},
+    };
+
+    use crate::radix_dlt::fake_gateway::FakeGateway;
+
+    use super::*;
+
+    const CONSTRUCTION_FIXTURE: &str = r#"{
+        "transaction_construction": {
+            "ledger_state": {
+                "network": "mainnet",
+                "state_version": 1000,
+                "proposer_round_timestamp": "2024-03-07T14:40:35.123Z",
+                "epoch": 100,
+                "round": 10
+            }
+        }
+    }"#;
+
+    fn key_pair(network: Network) -> Ed25519KeyPair {
+        key_pair_at_index(network, 0)
+    }
+
+    fn key_pair_at_index(network: Network, index: u32) -> Ed25519KeyPair {
+        let mnemonic = Mnemonic::from_phrase(
+            "toward point obtain quit degree route beauty magnet hidden cereal reform increase limb measure guide skirt nominee faint shoulder win deal april error axis",
+            Language::English,
+        )
+        .unwrap();
+
+        let (key_pair, _) = Ed25519KeyPair::new(
+            &mnemonic,
+            None,
+            index,
+            network,
+            Bip32Entity::Account,
+            Bip32KeyKind::TransactionSigning,
+        );
+        key_pair
+    }
+
+    fn transfer(network: Network) -> Transfer {
+        let from = AccountAddress::from_str(&key_pair(network).bech32_address()).unwrap();
+        let to = AccountAddress::from_str(
+            "account_rdx12xn8d9ykr8pmch33v0q66vhpvt98afalg0tfgctrqenkly96sgdx5n",
+        )
+        .unwrap();
+        let xrd = ResourceAddress::from_str(
+            "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd",
+        )
+        .unwrap();
+
+        let mut recipient = TransferRecipient::new(to);
+        recipient.resources.push(TransferResource::new(
+            from.clone(),
+            xrd,
+            RadixDecimal::from(1),
+        ));
+
+        let mut transfer = Transfer::new(from, Some("test".to_string()));
+        transfer.recipients.push(recipient);
+        transfer
+    }
+
+    #[test]
+    fn test_build_transfer_manifest() {
+        let transfer = transfer(Network::Mainnet);
+        let manifest = build_transfer_manifest(Network::Mainnet, &transfer).unwrap();
+
+        // lock fee, withdraw, take from worktop and deposit
+        assert_eq!(manifest.instructions.len(), 4);
+
+        let empty = Transfer::new(transfer.from_account.clone(), None);
+        assert!(matches!(
+            build_transfer_manifest(Network::Mainnet, &empty),
+            Err(TransferError::EmptyTransfer)
+        ));
+    }
+
+    #[test]
+    fn test_notarize_transaction() {
+        let network = Network::Mainnet;
+        let transfer = transfer(network);
+        let manifest = build_transfer_manifest(network, &transfer).unwrap();
+
+        let notarized = notarize_transaction(
+            network,
+            manifest,
+            Some("test"),
+            &key_pair(network),
+            &[],
+            100,
+        )
+        .unwrap();
+
+        assert!(notarized.intent_hash.as_str().starts_with("txid_rdx1"));
+        assert!(!notarized.notarized_transaction_hex.is_empty());
+    }
+
+    #[test]
+    fn test_separate_fee_payer() {
+        let network = Network::Mainnet;
+        let fee_payer_key_pair = key_pair_at_index(network, 1);
+
+        let mut transfer = transfer(network);
+        transfer.fee.payer =
+            AccountAddress::from_str(&fee_payer_key_pair.bech32_address()).unwrap();
+        transfer.fee.estimate = Some(RadixDecimal::from_str("0.5").unwrap());
+        assert_eq!(transfer.signers().len(), 2);
+
+        let manifest = build_transfer_manifest(network, &transfer).unwrap();
+        assert_eq!(manifest.instructions.len(), 4);
+
+        let manifest_string = decompile(&manifest, &network.definition()).unwrap();
+        assert!(manifest_string.contains(transfer.fee.payer.as_str()));
+        assert!(manifest_string.contains(transfer.from_account.as_str()));
+
+        let notarized = notarize_transaction(
+            network,
+            manifest,
+            None,
+            &key_pair(network),
+            &[fee_payer_key_pair],
+            100,
+        )
+        .unwrap();
+
+        assert!(notarized.intent_hash.as_str().starts_with("txid_rdx1"));
+    }
+
+    #[test]
+    fn test_multiple_source_accounts() {
+        let network = Network::Mainnet;
+        let source_key_pair = key_pair_at_index(network, 2);
+        let source = AccountAddress::from_str(&source_key_pair.bech32_address()).unwrap();
+
+        let mut transfer = transfer(network);
+        let mut resource = transfer.recipients[0].resources[0].clone();
+        resource.source = source.clone();
+        let mut recipient = TransferRecipient::new(transfer.recipients[0].address.clone());
+        recipient.resources.push(resource);
+        transfer.recipients.push(recipient);
+
+        assert!(transfer.signers().contains(&source));
+
+        let manifest = build_transfer_manifest(network, &transfer).unwrap();
+        // lock fee, one withdrawal per source, take from worktop and deposit per recipient
+        assert_eq!(manifest.instructions.len(), 7);
+
+        let manifest_string = decompile(&manifest, &network.definition()).unwrap();
+        assert!(manifest_string.contains(source.as_str()));
+
+        let notarized = notarize_transaction(
+            network,
+            manifest,
+            None,
+            &key_pair(network),
+            &[source_key_pair],
+            100,
+        )
+        .unwrap();
+
+        assert!(notarized.intent_hash.as_str().starts_with("txid_rdx1"));
+    }
+
+    #[test]
+    fn test_non_fungible_transfer() {
+        let network = Network::Mainnet;
+        let collection = ResourceAddress::from_str(
+            "resource_rdx1nfxxxxxxxxxxsecpsgxxxxxxxxx004638826440xxxxxxxxxwj8qq5",
+        )
+        .unwrap();
+
+        let mut transfer = transfer(network);
+        let source = transfer.from_account.clone();
+        transfer.recipients[0]
+            .non_fungibles
+            .push(TransferNonFungibles::new(
+                source,
+                collection,
+                vec!["#1#".to_string(), "<two>".to_string()],
+            ));
+
+        let manifest = build_transfer_manifest(network, &transfer).unwrap();
+        // lock fee, withdraw fungible and non-fungibles, take from worktop and deposit twice
+        assert_eq!(manifest.instructions.len(), 7);
+
+        let manifest_string = decompile(&manifest, &network.definition()).unwrap();
+        assert!(manifest_string.contains("WITHDRAW_NON_FUNGIBLES"));
+        assert!(manifest_string.contains("TAKE_NON_FUNGIBLES_FROM_WORKTOP"));
+
+        transfer.recipients[0].non_fungibles[0].nfids = vec!["not an id".to_string()];
+        assert!(matches!(
+            build_transfer_manifest(network, &transfer),
+            Err(TransferError::InvalidNonFungibleId(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_submit_requires_key_for_every_source() {
+        let network = Network::Mainnet;
+        let source =
+            AccountAddress::from_str(&key_pair_at_index(network, 2).bech32_address()).unwrap();
+
+        let mut transfer = transfer(network);
+        transfer.recipients[0].resources[0].source = source;
+
+        let gateway = FakeGateway::from_json(CONSTRUCTION_FIXTURE).unwrap();
+
+        let result = submit_transfer(
+            gateway.clone(),
+            network,
+            transfer,
+            key_pair(network),
+            Vec::new(),
+        )
+        .await;
+        assert!(matches!(result, Err(TransferError::MissingKey(_))));
+        assert!(gateway.submitted_transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_submit_transfer_through_gateway() {
+        let network = Network::Mainnet;
+        let gateway = FakeGateway::from_json(CONSTRUCTION_FIXTURE).unwrap();
+
+        let intent_hash = submit_transfer(
+            gateway.clone(),
+            network,
+            transfer(network),
+            key_pair(network),
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        assert!(intent_hash.as_str().starts_with("txid_rdx1"));
+        assert_eq!(gateway.submitted_transactions().len(), 1);
+    }
+}
@@ -1,69 +1,207 @@
 use deps::*;
 
 use super::*;
-use debug_print::debug_println;
-use futures::future::join_all;
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use futures::{StreamExt, future::join_all, stream::FuturesUnordered};
+use ports::network_gateway::{self, NetworkGateway};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
 use store::AppDataDb;
 use thiserror::Error;
-use tokio::task::JoinHandle;
+use tokio::{
+    sync::{Semaphore, mpsc::UnboundedSender},
+    task::JoinError,
+};
 use types::{
-    Account, AppError, Network, Resource, UnsafeRef,
+    Account, AppError, Network, Resource, Transaction, UnsafeRef,
     address::{AccountAddress, Address, ResourceAddress},
     assets::{FungibleAsset, NFT, NonFungibleAsset},
-    collections::{AccountUpdate, AccountsUpdate},
+    collections::{AccountAssets, AccountUpdate, AccountsUpdate, UpdateFailure, UpdateProgress},
 };
 
+/// Accounts updated at the same time, each account sends several requests at once
+pub const MAX_CONCURRENT_ACCOUNT_UPDATES: usize = 4;
+
 #[derive(Debug, Error)]
 pub enum UpdateError {
     #[error("Error connecting to gateway\n ∟{0}")]
-    GatewayError(#[from] radix_gateway_sdk::Error),
+    GatewayError(#[from] network_gateway::Error),
     #[error("Error parsing response")]
     ResponseParseError,
     #[error("Error parsing address")]
     AddressParseError,
     #[error("No assets found")]
     EmptyResponse,
+    #[error("Update task failed\n ∟{0}")]
+    TaskError(#[from] JoinError),
+}
+
+/// The assets of one kind held by an account, together with the resources not stored before
+pub struct AssetsUpdate<T> {
+    pub state_version: i64,
+    /// Every resource the account holds, also those not updated
+    pub held: HashSet<ResourceAddress>,
+    /// The assets updated since the last update
+    pub assets: HashMap<ResourceAddress, T>,
+    pub new_resources: HashMap<ResourceAddress, (Resource, String)>,
+    pub failures: Vec<UpdateFailure>,
+}
+
+impl<T> AssetsUpdate<T> {
+    fn empty() -> Self {
+        Self {
+            state_version: 0,
+            held: HashSet::new(),
+            assets: HashMap::new(),
+            new_resources: HashMap::new(),
+            failures: Vec::new(),
+        }
+    }
 }
 
-pub async fn update_all_accounts(network: Network) -> Result<AccountsUpdate, AppError> {
+/// Updates every account stored for the network, the progress is sent to `progress`
+/// each time an account has been updated
+pub async fn update_all_accounts<G: NetworkGateway>(
+    gateway: G,
+    network: Network,
+    progress: Option<UnboundedSender<UpdateProgress>>,
+) -> Result<AccountsUpdate, AppError> {
     let db =
         AppDataDb::get(network).ok_or(AppError::Fatal("Database not initialized".to_string()))?;
 
     let accounts = db.get_accounts().await.unwrap_or(Vec::new());
     let resource_map = db.get_all_resources().await.unwrap_or(HashMap::new());
     let resources = Arc::new(resource_map);
+    let stored_assets = Arc::new(stored_assets_per_account(&db).await);
+
+    Ok(update_accounts_with_progress(
+        gateway,
+        network,
+        resources,
+        stored_assets,
+        accounts,
+        progress,
+    )
+    .await)
+}
+
+async fn stored_assets_per_account(db: &AppDataDb) -> HashMap<AccountAddress, AccountAssets> {
+    let mut fungibles: HashMap<AccountAddress, BTreeSet<FungibleAsset>> = db
+        .get_all_fungible_assets_per_account()
+        .await
+        .unwrap_or_default();
+    let mut non_fungibles: HashMap<AccountAddress, BTreeSet<NonFungibleAsset>> = db
+        .get_all_non_fungible_assets_per_account()
+        .await
+        .unwrap_or_default();
+
+    fungibles
+        .keys()
+        .chain(non_fungibles.keys())
+        .cloned()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|account_address| {
+            let assets = AccountAssets {
+                fungibles: fungibles.remove(&account_address).unwrap_or_default(),
+                non_fungibles: non_fungibles.remove(&account_address).unwrap_or_default(),
+            };
+            (account_address, assets)
+        })
+        .collect()
+}
 
-    Ok(update_accounts(network, resources, accounts).await)
+/// Requests that fail are listed in the failures of the returned update.
+/// `stored_assets` are the assets held before the update, the assets no longer held
+/// are listed as removed in the update of the account
+pub async fn update_accounts<G: NetworkGateway>(
+    gateway: G,
+    network: Network,
+    resources: Arc<HashMap<ResourceAddress, Resource>>,
+    stored_assets: Arc<HashMap<AccountAddress, AccountAssets>>,
+    accounts: Vec<Account>,
+) -> AccountsUpdate {
+    update_accounts_with_progress(gateway, network, resources, stored_assets, accounts, None).await
 }
 
-pub async fn update_accounts(
+/// Same as [`update_accounts`], the progress is sent to `progress` each time an account
+/// has been updated. The account updates are in the order they finished
+pub async fn update_accounts_with_progress<G: NetworkGateway>(
+    gateway: G,
     network: Network,
     resources: Arc<HashMap<ResourceAddress, Resource>>,
+    stored_assets: Arc<HashMap<AccountAddress, AccountAssets>>,
     accounts: Vec<Account>,
+    progress: Option<UnboundedSender<UpdateProgress>>,
 ) -> AccountsUpdate {
     // `resources` is inside an Arc to make sure it is valid for the duration of this task
     // From this point we know that the resources will be valid until all tasks within this function are finished,
     // therefore we pass around a non reference counted unsafe reference to resources to sub tasks
     let resources = unsafe { UnsafeRef::new(&*resources) };
 
-    let tasks = accounts.into_iter().map(|account| {
-        tokio::spawn(async move { update_account(network, resources, account).await })
-    });
-
-    join_all(tasks)
-        .await
+    let total = accounts.len();
+    let account_permits = Arc::new(Semaphore::new(MAX_CONCURRENT_ACCOUNT_UPDATES));
+    let mut tasks = accounts
         .into_iter()
-        .filter_map(get_successful_task_value)
-        .fold(
-            AccountsUpdate::new(network),
-            add_account_update_and_resources_to_accounts_update,
-        )
+        .map(|account| {
+            let account_address = account.address.clone();
+            let gateway = gateway.clone();
+            let account_permits = account_permits.clone();
+            let stored_assets = stored_assets
+                .get(&account_address)
+                .cloned()
+                .unwrap_or_default();
+            let task = tokio::spawn(async move {
+                // The semaphore is never closed, the permit is held until the account is updated
+                let _permit = account_permits.acquire_owned().await;
+                update_account(gateway, resources, stored_assets, account).await
+            });
+            async move { (account_address, task.await) }
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut accounts_update = AccountsUpdate::new(network);
+    let mut completed = 0;
+
+    while let Some((account_address, join_result)) = tasks.next().await {
+        let failures_before = accounts_update.failures.len();
+        accounts_update = match join_result {
+            Ok(result) => add_account_update_to_accounts_update(accounts_update, result),
+            Err(err) => {
+                accounts_update.failures.push(UpdateFailure::new(
+                    Some(account_address.clone()),
+                    "account update",
+                    err,
+                ));
+                accounts_update
+            }
+        };
+
+        completed += 1;
+        if let Some(progress) = &progress {
+            // The receiver is gone if nobody is interested in the progress anymore
+            progress
+                .send(UpdateProgress {
+                    account: account_address,
+                    completed,
+                    total,
+                    failures: accounts_update.failures[failures_before..].to_vec(),
+                })
+                .ok();
+        }
+    }
+
+    accounts_update
 }
 
-fn add_account_update_and_resources_to_accounts_update(
+fn add_account_update_to_accounts_update(
     mut acc: AccountsUpdate,
-    (account_update, new_resources): (AccountUpdate, HashMap<ResourceAddress, (Resource, String)>),
+    (account_update, new_resources, failures): (
+        AccountUpdate,
+        HashMap<ResourceAddress, (Resource, String)>,
+        Vec<UpdateFailure>,
+    ),
 ) -> AccountsUpdate {
     new_resources
         .into_iter()
@@ -72,111 +210,279 @@ fn add_account_update_and_resources_to_accounts_update(
             acc.icon_urls.insert(resource_address, url);
         });
     acc.account_updates.push(account_update);
+    acc.failures.extend(failures);
     acc
 }
 
-fn get_successful_task_value<T, E: Display>(result: Result<T, E>) -> Option<T> {
-    match result {
+fn flatten_task_result<T>(
+    result: Result<Result<T, UpdateError>, JoinError>,
+) -> Result<T, UpdateError> {
+    result.map_err(UpdateError::from).and_then(|result| result)
+}
+
+/// Returns the value of a finished task, a failed task is added to `failures`
+fn task_value_or_failure<T>(
+    result: Result<Result<T, UpdateError>, JoinError>,
+    account_address: &AccountAddress,
+    request: &'static str,
+    failures: &mut Vec<UpdateFailure>,
+) -> Option<T> {
+    match flatten_task_result(result) {
         Ok(value) => Some(value),
-        Err(error) => {
-            debug_println!("Failed to join task: {}", error);
+        Err(err) => {
+            failures.push(UpdateFailure::new(
+                Some(account_address.clone()),
+                request,
+                err,
+            ));
             None
         }
     }
 }
 
-async fn update_account(
-    network: Network,
+/// The balances are only marked as updated if every balance request for the account succeeded,
+/// otherwise the next update starts from the same state version.
+/// Removed assets are also only listed if the balances are complete
+async fn update_account<G: NetworkGateway>(
+    gateway: G,
     resources: UnsafeRef<HashMap<ResourceAddress, Resource>>,
+    stored_assets: AccountAssets,
     mut account: Account,
-) -> (AccountUpdate, HashMap<ResourceAddress, (Resource, String)>) {
+) -> (
+    AccountUpdate,
+    HashMap<ResourceAddress, (Resource, String)>,
+    Vec<UpdateFailure>,
+) {
     let balances_last_updated_at_state_version = account.balances_last_updated.unwrap_or(0);
-    // TODO get transactions
     let transactions_last_updated = account.transactions_last_updated;
 
     // The account address should be used througout tasks and is never mutated or removed from the Account struct.
     // by the end of this function all tasks will be completed, so the UnsafeRef will never be used while the reference is not valid
     let account_address = unsafe { UnsafeRef::new(&account.address) };
 
+    let fungible_balances = update_fungible_balances_for_account(
+        gateway.clone(),
+        &account_address,
+        balances_last_updated_at_state_version,
+    )
+    .await;
+    // The non-fungibles are read at the ledger state the fungibles were read at,
+    // so both balances of the account are from the same state
+    let at_state_version = fungible_balances
+        .as_ref()
+        .ok()
+        .map(|(state_version, _, _)| *state_version);
+
+    let gateway_clone = gateway.clone();
     let fungible_assets_task = tokio::spawn(async move {
-        update_fungible_assets_and_resources_for_account(
-            network,
-            account_address,
-            resources,
-            balances_last_updated_at_state_version,
+        Ok::<_, UpdateError>(
+            update_fungible_resources_for_account(gateway_clone, resources, fungible_balances?)
+                .await,
         )
-        .await
     });
 
+    let gateway_clone = gateway.clone();
     let non_fungible_assets_task = tokio::spawn(async move {
         update_non_fungible_assets_and_resources_for_account(
-            network,
+            gateway_clone,
             account_address,
             resources,
             balances_last_updated_at_state_version,
+            at_state_version,
         )
         .await
     });
-    let (new_state_version_fungible_balances, fungible_assets, new_fungible_resources) =
-        fungible_assets_task
-            .await
-            .and_then(|result| Ok(result.unwrap_or((0, HashMap::new(), HashMap::new()))))
-            .unwrap_or_else(|err| {
-                debug_println!("Failed to update fungible assets: {}", err);
-                (0, HashMap::new(), HashMap::new())
-            });
 
-    let (new_state_version_non_fungible_balances, non_fungible_assets, new_non_fungible_resources) =
-        non_fungible_assets_task
-            .await
-            .and_then(|result| Ok(result.unwrap_or((0, HashMap::new(), HashMap::new()))))
-            .unwrap_or_else(|err| {
-                debug_println!("Failed to get Non fungible assets {}", err);
-                (0, HashMap::new(), HashMap::new())
-            });
+    let transactions_task = tokio::spawn(async move {
+        update_transactions_for_account(gateway, &account_address, transactions_last_updated).await
+    });
 
-    let new_state_version = balances_last_updated_at_state_version
-        .max(new_state_version_fungible_balances.min(new_state_version_non_fungible_balances));
+    let mut failures = Vec::new();
 
-    account.balances_last_updated = Some(new_state_version);
-    let mut new_resources = new_fungible_resources;
-    new_resources.extend(new_non_fungible_resources);
+    let fungibles = task_value_or_failure(
+        fungible_assets_task.await,
+        &account.address,
+        "fungible balances",
+        &mut failures,
+    )
+    .unwrap_or_else(AssetsUpdate::empty);
 
-    (
-        AccountUpdate {
-            account,
-            fungibles: fungible_assets,
-            non_fungibles: non_fungible_assets,
-        },
-        new_resources,
+    let non_fungibles = task_value_or_failure(
+        non_fungible_assets_task.await,
+        &account.address,
+        "non-fungible balances",
+        &mut failures,
     )
+    .unwrap_or_else(AssetsUpdate::empty);
+
+    failures.extend(fungibles.failures);
+    failures.extend(non_fungibles.failures);
+
+    // Failed resource details are not tied to an account and are requested again
+    // on the next update since they are still missing from the stored resources
+    let balances_complete = failures.iter().all(|failure| failure.account.is_none());
+    if balances_complete {
+        account.balances_last_updated = Some(
+            balances_last_updated_at_state_version
+                .max(fungibles.state_version.min(non_fungibles.state_version)),
+        );
+    }
+
+    let transactions = match task_value_or_failure(
+        transactions_task.await,
+        &account.address,
+        "transactions",
+        &mut failures,
+    ) {
+        Some((state_version, transactions)) => {
+            account.transactions_last_updated = Some(state_version);
+            transactions
+        }
+        None => Vec::new(),
+    };
+
+    let mut new_resources = fungibles.new_resources;
+    new_resources.extend(non_fungibles.new_resources);
+
+    let mut account_update = AccountUpdate {
+        account,
+        fungibles: fungibles.assets,
+        non_fungibles: non_fungibles.assets,
+        transactions,
+        removed_fungibles: BTreeSet::new(),
+        removed_non_fungibles: BTreeSet::new(),
+        removed_nfids: HashMap::new(),
+    };
+
+    if balances_complete {
+        add_removed_assets(
+            &mut account_update,
+            &stored_assets,
+            &fungibles.held,
+            &non_fungibles.held,
+        );
+    }
+
+    (account_update, new_resources, failures)
 }
 
-/// returns the updated `Resource` and the accompanying icon url
-pub async fn update_resources(
-    network: Network,
+/// Lists the assets in `stored_assets` the account no longer holds, and the ids that left
+/// the updated non-fungibles
+fn add_removed_assets(
+    account_update: &mut AccountUpdate,
+    stored_assets: &AccountAssets,
+    held_fungibles: &HashSet<ResourceAddress>,
+    held_non_fungibles: &HashSet<ResourceAddress>,
+) {
+    account_update.removed_fungibles = stored_assets
+        .fungibles
+        .iter()
+        .map(|asset| &asset.resource_address)
+        .filter(|resource_address| !held_fungibles.contains(resource_address))
+        .cloned()
+        .collect();
+
+    account_update.removed_non_fungibles = stored_assets
+        .non_fungibles
+        .iter()
+        .map(|asset| &asset.resource_address)
+        .filter(|resource_address| !held_non_fungibles.contains(resource_address))
+        .cloned()
+        .collect();
+
+    for stored_asset in &stored_assets.non_fungibles {
+        let Some(updated_asset) = account_update
+            .non_fungibles
+            .get(&stored_asset.resource_address)
+        else {
+            continue;
+        };
+
+        let held_nfids = updated_asset
+            .nfids
+            .iter()
+            .map(|nft| nft.id.as_str())
+            .collect::<HashSet<_>>();
+        let removed_nfids = stored_asset
+            .nfids
+            .iter()
+            .filter(|nft| !held_nfids.contains(nft.id.as_str()))
+            .map(|nft| nft.id.clone())
+            .collect::<BTreeSet<_>>();
+
+        if !removed_nfids.is_empty() {
+            account_update
+                .removed_nfids
+                .insert(stored_asset.resource_address.clone(), removed_nfids);
+        }
+    }
+}
+
+/// Pages through the transactions committed after `last_updated_at_state_version`,
+/// returns the state version the transactions are synced up to together with the transactions.
+/// If a page fails, nothing is returned so the sync is retried from the same state version
+pub async fn update_transactions_for_account<G: NetworkGateway>(
+    gateway: G,
+    account_address: &AccountAddress,
+    last_updated_at_state_version: Option<i64>,
+) -> Result<(i64, Vec<Transaction>), UpdateError> {
+    let from_state_version = last_updated_at_state_version.map(|state_version| state_version + 1);
+
+    let (state_version, pages) = paginator::drain_pages(None, |cursor, at_state_version| {
+        gateway.transactions_page(
+            account_address.as_str(),
+            cursor,
+            from_state_version,
+            at_state_version,
+        )
+    })
+    .await?;
+
+    let mut transactions = Vec::new();
+    for page in pages {
+        let (_, _, page_transactions) =
+            parse_responses::parse_transactions_response(page, account_address)
+                .ok_or(UpdateError::ResponseParseError)?;
+        transactions.extend(page_transactions);
+    }
+
+    Ok((state_version, transactions))
+}
+
+/// Returns the updated `Resource` and the accompanying icon url,
+/// together with the requests that failed
+pub async fn update_resources<G: NetworkGateway>(
+    gateway: G,
     resources: Vec<ResourceAddress>,
-) -> HashMap<ResourceAddress, (Resource, String)> {
+) -> (
+    HashMap<ResourceAddress, (Resource, String)>,
+    Vec<UpdateFailure>,
+) {
     let tasks = resources
         .chunks(gateway_requests::ENTITY_DETAILS_MAX_ADDRESSES)
         .map(|chunk| {
             let chunk = chunk.to_owned();
+            let gateway = gateway.clone();
 
             tokio::spawn(async move {
                 let addresses = chunk
                     .iter()
                     .map(|address| address.as_str())
                     .collect::<Vec<_>>();
+                // Chunks are never empty and every resource is on the network of the gateway
+                let network = chunk[0].network();
 
-                let response =
-                    gateway_requests::get_entity_details(network.into(), &addresses).await?;
+                let response = gateway.entity_details(&addresses).await?;
 
                 let new_resources = response
                     .items
                     .into_iter()
                     .filter_map(|response_item| {
                         let (resource_address, resource_and_icon_url) =
-                            parse_responses::parse_resource_details_response(response_item)?;
+                            parse_responses::parse_resource_details_response(
+                                response_item,
+                                network,
+                            )?;
                         Some((resource_address, resource_and_icon_url))
                     })
                     .collect::<HashMap<ResourceAddress, (Resource, String)>>();
@@ -185,479 +491,284 @@ pub async fn update_resources(
             })
         });
 
-    join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|join_result| join_result.ok()?.ok())
-        .reduce(|mut acc, new_resources| {
-            acc.extend(new_resources);
-            acc
-        })
-        .unwrap_or(HashMap::new())
+    join_all(tasks).await.into_iter().fold(
+        (HashMap::new(), Vec::new()),
+        |(mut resources, mut failures), join_result| {
+            match flatten_task_result(join_result) {
+                Ok(new_resources) => resources.extend(new_resources),
+                Err(err) => failures.push(UpdateFailure::new(None, "resource details", err)),
+            }
+            (resources, failures)
+        },
+    )
 }
 
-pub async fn update_fungible_assets_and_resources_for_account(
-    network: Network,
-    account_address: UnsafeRef<AccountAddress>,
+/// Returns the resource addresses that are not in `stored_resources`
+fn new_resource_addresses<T>(
+    assets: &HashMap<ResourceAddress, T>,
+    stored_resources: &HashMap<ResourceAddress, Resource>,
+) -> Vec<ResourceAddress> {
+    assets
+        .keys()
+        .filter(|resource_address| !stored_resources.contains_key(resource_address))
+        .cloned()
+        .collect()
+}
+
+/// Fetches the resources of the fungible balances that are not stored yet
+pub async fn update_fungible_resources_for_account<G: NetworkGateway>(
+    gateway: G,
     stored_resources: UnsafeRef<HashMap<ResourceAddress, Resource>>,
+    (state_version, held, assets): (
+        i64,
+        HashSet<ResourceAddress>,
+        HashMap<ResourceAddress, FungibleAsset>,
+    ),
+) -> AssetsUpdate<FungibleAsset> {
+    let (new_resources, failures) =
+        update_resources(gateway, new_resource_addresses(&assets, &stored_resources)).await;
+
+    AssetsUpdate {
+        state_version,
+        held,
+        assets,
+        new_resources,
+        failures,
+    }
+}
+
+/// Returns the state version, every resource the account holds and the assets updated since
+/// `last_updated_at_state_version`. Fails if any page fails, since the balances would otherwise
+/// be incomplete
+pub async fn update_fungible_balances_for_account<G: NetworkGateway>(
+    gateway: G,
+    account_address: &AccountAddress,
     last_updated_at_state_version: i64,
 ) -> Result<
     (
         i64,
+        HashSet<ResourceAddress>,
         HashMap<ResourceAddress, FungibleAsset>,
-        HashMap<ResourceAddress, (Resource, String)>,
     ),
     UpdateError,
 > {
-    let (state_version, assets) = update_fungible_balances_for_account(
-        network,
-        &account_address,
-        last_updated_at_state_version,
-    )
+    let (state_version, pages) = paginator::drain_pages(None, |cursor, at_state_version| {
+        gateway.fungibles_page(account_address.as_str(), cursor, at_state_version)
+    })
     .await?;
 
-    let new_resource_addresses = assets
-        .iter()
-        .filter_map(|(resource_address, _)| {
-            if !stored_resources.contains_key(resource_address) {
-                Some(resource_address.clone())
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<ResourceAddress>>();
-
-    let new_resources = update_resources(network.into(), new_resource_addresses).await;
+    let mut held = HashSet::new();
+    let mut assets = HashMap::new();
+    for page in pages {
+        let (page_held, page_assets) = parse_responses::parse_fungible_balances_response(
+            page,
+            last_updated_at_state_version,
+            account_address,
+        );
+        held.extend(page_held);
+        assets.extend(page_assets);
+    }
 
-    Ok::<_, UpdateError>((state_version, assets, new_resources))
+    Ok((state_version, held, assets))
 }
 
-pub async fn update_fungible_balances_for_account(
-    network: Network,
-    account_address: &AccountAddress,
+/// The balances are read at `at_state_version` if given, otherwise at the current ledger state
+pub async fn update_non_fungible_assets_and_resources_for_account<G: NetworkGateway>(
+    gateway: G,
+    account_address: UnsafeRef<AccountAddress>,
+    stored_resources: UnsafeRef<HashMap<ResourceAddress, Resource>>,
     last_updated_at_state_version: i64,
-) -> Result<(i64, HashMap<ResourceAddress, FungibleAsset>), UpdateError> {
-    let mut response = gateway_requests::get_fungible_balances_for_entity(
-        network,
-        account_address.as_str(),
-        None,
-        None,
+    at_state_version: Option<i64>,
+) -> Result<AssetsUpdate<NonFungibleAsset>, UpdateError> {
+    let (state_version, held, assets) = update_non_fungible_assets_for_account(
+        gateway.clone(),
+        &account_address,
+        last_updated_at_state_version,
+        at_state_version,
     )
     .await?;
 
-    let mut result = (
-        response.ledger_state_mixin.ledger_state.state_version,
-        HashMap::new(),
-    );
+    let (assets_with_ids, mut failures) = update_non_fungible_ids_for_assets(
+        gateway.clone(),
+        &account_address,
+        assets,
+        state_version,
+    )
+    .await;
 
-    'main: loop {
-        let next_cursor = response.next_cursor.take();
-        if next_cursor.is_none() {
-            let parsed = parse_responses::parse_fungible_balances_response(
-                response,
-                last_updated_at_state_version,
-                &account_address,
-            );
-            result.1.extend(parsed);
-            break;
-        } else {
-            let address = account_address.clone();
-            let cursor = next_cursor.clone();
-            let at_state_version = Some(result.0);
-            let next_response = tokio::spawn(async move {
-                gateway_requests::get_fungible_balances_for_entity(
-                    network,
-                    address.as_str(),
-                    cursor,
-                    at_state_version,
-                )
-                .await
-            });
+    let (assets_with_nfdata, data_failures) =
+        update_non_fungible_data_for_ids(gateway.clone(), &account_address, assets_with_ids).await;
+    failures.extend(data_failures);
 
-            let parsed = parse_responses::parse_fungible_balances_response(
-                response,
-                last_updated_at_state_version,
-                &account_address,
-            );
-            result.1.extend(parsed);
-
-            match next_response.await {
-                Ok(response_result) => match response_result {
-                    Ok(new_response) => response = new_response,
-                    Err(_) => {
-                        for _ in 0..3 {
-                            let retry_result = gateway_requests::get_fungible_balances_for_entity(
-                                network,
-                                account_address.as_str(),
-                                next_cursor.clone(),
-                                Some(result.0),
-                            )
-                            .await;
-                            match retry_result {
-                                Ok(new_response) => {
-                                    response = new_response;
-                                    continue 'main;
-                                }
-                                Err(_) => continue,
-                            }
-                        }
-                        debug_println!(
-                            "Failed to get fungible balances for {}",
-                            account_address.as_str()
-                        );
-                        break;
-                    }
-                },
-                Err(_) => {
-                    debug_println!(
-                        "Join error when getting fungible balances for {}",
-                        account_address.as_str()
-                    );
-                    break;
-                }
-            }
-        }
-    }
+    let (new_resources, resource_failures) = update_resources(
+        gateway,
+        new_resource_addresses(&assets_with_nfdata, &stored_resources),
+    )
+    .await;
+    failures.extend(resource_failures);
 
-    Ok(result)
+    Ok(AssetsUpdate {
+        state_version,
+        held,
+        assets: assets_with_nfdata,
+        new_resources,
+        failures,
+    })
 }
 
-pub async fn update_non_fungible_assets_and_resources_for_account(
-    network: Network,
-    account_address: UnsafeRef<AccountAddress>,
-    stored_resources: UnsafeRef<HashMap<ResourceAddress, Resource>>,
+/// Returns the state version, every resource the account holds and the assets updated since
+/// `last_updated_at_state_version` without their ids, read at `at_state_version` if given.
+/// Fails if any page fails, since the balances would otherwise be incomplete
+pub async fn update_non_fungible_assets_for_account<G: NetworkGateway>(
+    gateway: G,
+    account_address: &AccountAddress,
     last_updated_at_state_version: i64,
+    at_state_version: Option<i64>,
 ) -> Result<
     (
         i64,
-        HashMap<ResourceAddress, NonFungibleAsset>,
-        HashMap<ResourceAddress, (Resource, String)>,
+        HashSet<ResourceAddress>,
+        HashMap<ResourceAddress, (String, NonFungibleAsset)>,
     ),
     UpdateError,
 > {
-    let (state_version, assets) = update_non_fungible_assets_for_account(
-        network,
-        &account_address,
-        last_updated_at_state_version,
-    )
-    .await?;
-
-    let assets_with_ids =
-        update_non_fungible_ids_for_assets(network, &account_address, assets).await?;
-
-    let assets_with_nfdata = update_non_fungible_data_for_ids(network, assets_with_ids).await;
-
-    let new_resource_addresses = assets_with_nfdata
-        .iter()
-        .filter_map(|(resource_address, _)| {
-            if !stored_resources.contains_key(resource_address) {
-                Some(resource_address.clone())
-            } else {
-                None
-            }
+    let (state_version, pages) =
+        paginator::drain_pages(at_state_version, |cursor, at_state_version| {
+            gateway.non_fungibles_page(account_address.as_str(), cursor, at_state_version)
         })
-        .collect::<Vec<ResourceAddress>>();
-
-    let new_resources = update_resources(network, new_resource_addresses).await;
-
-    Ok((state_version, assets_with_nfdata, new_resources))
-}
-
-pub async fn update_non_fungible_assets_for_account(
-    network: Network,
-    account_address: &AccountAddress,
-    last_updated_at_state_version: i64,
-) -> Result<(i64, HashMap<ResourceAddress, (String, NonFungibleAsset)>), UpdateError> {
-    let mut response = gateway_requests::get_non_fungible_balances_for_entity(
-        network,
-        account_address.as_str(),
-        None,
-        None,
-    )
-    .await?;
-
-    let mut result = (
-        response.ledger_state_mixin.ledger_state.state_version,
-        HashMap::new(),
-    );
-
-    'main: loop {
-        let next_cursor = response
-            .non_fungible_resources_collection
-            .next_cursor
-            .take();
-
-        if next_cursor.is_none() {
-            let parsed = parse_responses::parse_non_fungible_balances_response_without_nfids(
-                response,
-                last_updated_at_state_version,
-                &account_address,
-            );
-            result.1.extend(parsed);
-            break;
-        } else {
-            let address = account_address.clone();
-            let cursor = next_cursor.clone();
-            let at_state_version = Some(result.0);
-            let next_response = tokio::spawn(async move {
-                gateway_requests::get_non_fungible_balances_for_entity(
-                    network,
-                    address.as_str(),
-                    cursor,
-                    at_state_version,
-                )
-                .await
-            });
-
-            let parsed = parse_responses::parse_non_fungible_balances_response_without_nfids(
-                response,
+        .await?;
+
+    let mut held = HashSet::new();
+    let mut assets = HashMap::new();
+    for page in pages {
+        let (page_held, page_assets) =
+            parse_responses::parse_non_fungible_balances_response_without_nfids(
+                page,
                 last_updated_at_state_version,
-                &account_address,
+                account_address,
             );
-            result.1.extend(parsed);
-
-            match next_response.await {
-                Ok(response_result) => match response_result {
-                    Ok(new_response) => response = new_response,
-                    Err(_) => {
-                        for _ in 0..3 {
-                            let retry_result =
-                                gateway_requests::get_non_fungible_balances_for_entity(
-                                    network,
-                                    account_address.as_str(),
-                                    next_cursor.clone(),
-                                    Some(result.0),
-                                )
-                                .await;
-                            match retry_result {
-                                Ok(new_response) => {
-                                    response = new_response;
-                                    continue 'main;
-                                }
-                                Err(_) => continue,
-                            }
-                        }
-                        debug_println!(
-                            "Failed to get non-fungible balances for account: {}",
-                            account_address.as_str()
-                        );
-                        break;
-                    }
-                },
-                Err(_) => {
-                    debug_println!(
-                        "Join Error when getting non_fungible balances for account: {}",
-                        account_address.as_str()
-                    );
-                    break;
-                }
-            }
-        }
+        held.extend(page_held);
+        assets.extend(page_assets);
     }
-    Ok(result)
+
+    Ok((state_version, held, assets))
 }
 
-pub async fn update_non_fungible_ids_for_assets(
-    network: Network,
+/// Assets whose ids could not be fetched are left out and listed in the returned failures.
+/// The ids are read at `at_state_version`, the state the assets were read at
+pub async fn update_non_fungible_ids_for_assets<G: NetworkGateway>(
+    gateway: G,
     account_address: &AccountAddress,
     assets: HashMap<ResourceAddress, (String, NonFungibleAsset)>,
-) -> Result<HashMap<ResourceAddress, NonFungibleAsset>, UpdateError> {
+    at_state_version: i64,
+) -> (
+    HashMap<ResourceAddress, NonFungibleAsset>,
+    Vec<UpdateFailure>,
+) {
     let tasks = assets
         .into_iter()
         .map(|(resource_address, (vault_address, asset))| {
             let account_address = account_address.clone();
+            let gateway = gateway.clone();
             tokio::spawn(async move {
                 update_non_fungible_ids_for_asset(
-                    network,
+                    gateway,
                     account_address,
                     resource_address,
                     vault_address,
                     asset,
+                    at_state_version,
                 )
                 .await
             })
         });
 
-    join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|join_result| {
-            #[cfg(debug_assertions)]
-            if let Err(err) = &join_result {
-                println!("Join Error when getting nfids {}", err)
-            }
-            join_result.ok()
-        })
-        .fold(Err(UpdateError::EmptyResponse), |mut acc, result| {
-            match result {
-                Ok((resource_address, asset)) => match acc {
-                    Ok(ref mut map) => {
-                        map.insert(resource_address, asset);
-                    }
-                    Err(_) => {
-                        let mut map = HashMap::new();
-                        map.insert(resource_address, asset);
-                        acc = Ok(map);
-                    }
-                },
-                Err(err) => {
-                    debug_println!("Error when updating nfids {}", err);
-                    acc = acc.map_err(|_| err)
+    join_all(tasks).await.into_iter().fold(
+        (HashMap::new(), Vec::new()),
+        |(mut assets, mut failures), join_result| {
+            match flatten_task_result(join_result) {
+                Ok((resource_address, asset)) => {
+                    assets.insert(resource_address, asset);
                 }
+                Err(err) => failures.push(UpdateFailure::new(
+                    Some(account_address.clone()),
+                    "non-fungible ids",
+                    err,
+                )),
             }
-            acc
-        })
+            (assets, failures)
+        },
+    )
 }
-async fn update_non_fungible_ids_for_asset(
-    network: Network,
+
+async fn update_non_fungible_ids_for_asset<G: NetworkGateway>(
+    gateway: G,
     account_address: AccountAddress,
     resource_address: ResourceAddress,
     vault_address: String,
     mut asset: NonFungibleAsset,
+    at_state_version: i64,
 ) -> Result<(ResourceAddress, NonFungibleAsset), UpdateError> {
-    let mut response = gateway_requests::get_non_fungible_ids_from_vault(
-        network,
-        account_address.as_str(),
-        resource_address.as_str(),
-        vault_address.as_str(),
-        None,
-        None,
-    )
+    let (_, pages) = paginator::drain_pages(Some(at_state_version), |cursor, at_state_version| {
+        gateway.non_fungible_ids_page(
+            account_address.as_str(),
+            resource_address.as_str(),
+            vault_address.as_str(),
+            cursor,
+            at_state_version,
+        )
+    })
     .await?;
 
-    let ledger_state_version = response.ledger_state.state_version;
-
-    'main: loop {
-        let next_cursor = response.non_fungible_ids_collection.next_cursor.take();
-
-        if next_cursor.is_none() {
-            asset.nfids.extend(
-                response
-                    .non_fungible_ids_collection
-                    .items
-                    .into_iter()
-                    .map(|id| NFT::new(id)),
-            );
-            break;
-        } else {
-            let account_address_string = response.address;
-            let resource_address_string = response.resource_address;
-            let vault_address_clone = vault_address.clone();
-            let cursor = next_cursor.clone();
-            let next_response = tokio::spawn(async move {
-                gateway_requests::get_non_fungible_ids_from_vault(
-                    network,
-                    account_address_string.as_str(),
-                    resource_address_string.as_str(),
-                    vault_address_clone.as_str(),
-                    cursor,
-                    Some(ledger_state_version),
-                )
-                .await
-            });
-
-            asset.nfids.extend(
-                response
-                    .non_fungible_ids_collection
-                    .items
-                    .into_iter()
-                    .map(|id| NFT::new(id)),
-            );
-
-            match next_response.await {
-                Ok(response_result) => match response_result {
-                    Ok(new_response) => response = new_response,
-                    Err(_) => {
-                        for _ in 0..3 {
-                            let retry_result = gateway_requests::get_non_fungible_ids_from_vault(
-                                network,
-                                account_address.as_str(),
-                                resource_address.as_str(),
-                                vault_address.as_str(),
-                                next_cursor.clone(),
-                                Some(ledger_state_version),
-                            )
-                            .await;
-                            match retry_result {
-                                Ok(new_response) => {
-                                    response = new_response;
-                                    continue 'main;
-                                }
-                                Err(_) => continue,
-                            }
-                        }
-                        debug_println!(
-                            "Failed to get nfids for account {}, resource {}",
-                            account_address.as_str(),
-                            resource_address.as_str()
-                        );
-                        break;
-                    }
-                },
-                Err(_) => {
-                    debug_println!(
-                        "Join Error when getting nfids for account {}, resource {}",
-                        account_address.as_str(),
-                        resource_address.as_str()
-                    );
-                    break;
-                }
-            }
-        }
-    }
+    asset.nfids.extend(
+        pages
+            .into_iter()
+            .flat_map(|page| page.non_fungible_ids_collection.items)
+            .map(|id| NFT::new(id)),
+    );
 
     Ok((resource_address, asset))
 }
 
-async fn update_non_fungible_data_for_ids(
-    network: Network,
+/// Assets whose data could not be fetched are left out and listed in the returned failures.
+/// The gateway splits the ids of each asset into requests of the maximum size
+async fn update_non_fungible_data_for_ids<G: NetworkGateway>(
+    gateway: G,
+    account_address: &AccountAddress,
     assets: HashMap<ResourceAddress, NonFungibleAsset>,
-) -> HashMap<ResourceAddress, NonFungibleAsset> {
-    let mut tasks: Vec<JoinHandle<Result<(ResourceAddress, NonFungibleAsset), UpdateError>>> =
-        Vec::new();
-
-    assets
-        .into_iter()
-        .for_each(|(resource_address, mut asset)| {
+) -> (
+    HashMap<ResourceAddress, NonFungibleAsset>,
+    Vec<UpdateFailure>,
+) {
+    let tasks = assets.into_iter().map(|(resource_address, asset)| {
+        let gateway = gateway.clone();
+        tokio::spawn(async move {
             let ids = asset.nft_id_as_string();
-            for chunk in ids.chunks(100) {
-                let chunk = chunk.to_vec();
-                let resource_address = resource_address.clone();
-                let asset = asset.clone();
-                tasks.push(tokio::spawn(async move {
-                    let response = gateway_requests::get_non_fungible_data(
-                        network,
-                        resource_address.as_str(),
-                        chunk.as_slice(),
-                    )
-                    .await?;
-                    let asset_with_nfdata =
-                        parse_responses::parse_non_fungibles_data_response_for_asset(
-                            asset, response,
-                        );
-                    Ok((resource_address, asset_with_nfdata))
-                }))
-            }
-        });
+            let response = gateway
+                .non_fungible_data(resource_address.as_str(), ids.as_slice())
+                .await?;
+            let asset_with_nfdata =
+                parse_responses::parse_non_fungibles_data_response_for_asset(asset, response);
+            Ok::<_, UpdateError>((resource_address, asset_with_nfdata))
+        })
+    });
 
-    join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|join_result| {
-            #[cfg(debug_assertions)]
-            match &join_result {
-                Ok(result) => {
-                    if let Err(err) = &result {
-                        println!("Failed to update non fungible data {}", err);
-                    }
-                }
-                Err(err) => {
-                    println!("Join error when updating non fungible data {}", err);
+    join_all(tasks).await.into_iter().fold(
+        (HashMap::new(), Vec::new()),
+        |(mut assets, mut failures), join_result| {
+            match flatten_task_result(join_result) {
+                Ok((resource_address, asset)) => {
+                    assets.insert(resource_address, asset);
                 }
+                Err(err) => failures.push(UpdateFailure::new(
+                    Some(account_address.clone()),
+                    "non-fungible data",
+                    err,
+                )),
             }
-            join_result.ok()?.ok()
-        })
-        .collect()
+            (assets, failures)
+        },
+    )
 }
 
 #[cfg(test)]
@@ -665,9 +776,214 @@ mod tests {
     use std::str::FromStr;
 
     use super::*;
-    use types::Ed25519PublicKey;
+    use crate::radix_dlt::{
+        fake_gateway::{BalanceRequest, FakeGateway},
+        gateway_requests::RadixGateway,
+    };
+    use types::{Ed25519PublicKey, RequestLimits, assets::NFTs};
+
+    const ACCOUNT_SYNC_FIXTURE: &str = include_str!("./../../fixtures/account_sync.json");
+
+    fn account(address: &str) -> Account {
+        Account::new(
+            1,
+            "test".to_string(),
+            Network::Mainnet,
+            [0; 6],
+            AccountAddress::from_str(address).unwrap(),
+            Ed25519PublicKey([0; Ed25519PublicKey::LENGTH]),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_update_accounts_from_fixture() {
+        let gateway = FakeGateway::from_json(ACCOUNT_SYNC_FIXTURE).unwrap();
+        let account = account("account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k");
+
+        let mut accounts_update = update_accounts(
+            gateway,
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![account],
+        )
+        .await;
+
+        assert_eq!(accounts_update.account_updates.len(), 1);
+        let account_update = accounts_update.account_updates.remove(0);
+
+        assert_eq!(account_update.fungibles.len(), 2);
+        let xrd = ResourceAddress::from_str(
+            "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd",
+        )
+        .unwrap();
+        assert_eq!(account_update.fungibles[&xrd].amount, "150.5");
+
+        assert_eq!(account_update.non_fungibles.len(), 1);
+        let nft = account_update.non_fungibles.values().next().unwrap();
+        assert_eq!(nft.nfids.nr_of_nfts(), 2);
+
+        assert_eq!(account_update.transactions.len(), 1);
+        assert_eq!(account_update.account.transactions_last_updated, Some(1000));
+        assert_eq!(account_update.account.balances_last_updated, Some(1000));
+
+        assert_eq!(accounts_update.new_resources.len(), 3);
+        assert_eq!(accounts_update.icon_urls.len(), 3);
+        assert!(accounts_update.failures.is_empty());
+    }
 
     #[tokio::test]
+    async fn test_balances_are_read_at_the_same_ledger_state() {
+        let gateway = FakeGateway::from_json(ACCOUNT_SYNC_FIXTURE).unwrap();
+        let account = account("account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k");
+
+        update_accounts(
+            gateway.clone(),
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![account],
+        )
+        .await;
+
+        assert_eq!(
+            gateway.balance_requests(),
+            vec![
+                BalanceRequest::Fungibles(None),
+                BalanceRequest::Fungibles(Some(1000)),
+                BalanceRequest::NonFungibles(Some(1000)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_progress_is_reported_per_account() {
+        let gateway = FakeGateway::from_json(ACCOUNT_SYNC_FIXTURE).unwrap();
+        let synced = account("account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k");
+        let unknown = account("account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax");
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let accounts_update = update_accounts_with_progress(
+            gateway,
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![synced.clone(), unknown.clone()],
+            Some(sender),
+        )
+        .await;
+
+        let mut progress = Vec::new();
+        while let Some(update_progress) = receiver.recv().await {
+            progress.push(update_progress);
+        }
+
+        assert_eq!(progress.len(), 2);
+        assert_eq!(progress[1].completed, 2);
+        assert!(
+            progress
+                .iter()
+                .all(|update_progress| update_progress.total == 2)
+        );
+        for update_progress in &progress {
+            assert_eq!(
+                update_progress.failures.is_empty(),
+                update_progress.account == synced.address
+            );
+        }
+        assert!(!accounts_update.every_account_failed());
+    }
+
+    #[tokio::test]
+    async fn test_removed_assets_are_listed() {
+        let gateway = FakeGateway::from_json(ACCOUNT_SYNC_FIXTURE).unwrap();
+        let account = account("account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k");
+        let resource = |address: &str| ResourceAddress::from_str(address).unwrap();
+
+        let xrd = resource("resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd");
+        let sent_fungible =
+            resource("resource_rdx1t5rswpc8qurswpc8qurswpc8qurswpc8qurswpc8qurswpc820luy5");
+        let nft = resource("resource_rdx1nfxxxxxxxxxxsecpsgxxxxxxxxx004638826440xxxxxxxxxwj8qq5");
+        let burned_nft =
+            resource("resource_rdx1ngysjzgfpyysjzgfpyysjzgfpyysjzgfpyysjzgfpyysjzgfdefak2");
+
+        let nfts = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<NFTs>();
+        let stored_assets = AccountAssets {
+            fungibles: [xrd, sent_fungible.clone()]
+                .into_iter()
+                .map(|resource| FungibleAsset::new(&account.address, "1".to_string(), resource))
+                .collect(),
+            non_fungibles: [
+                NonFungibleAsset::new(&account.address, nfts(&["#1#", "#3#"]), nft.clone()),
+                NonFungibleAsset::new(&account.address, nfts(&["#1#"]), burned_nft.clone()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let mut accounts_update = update_accounts(
+            gateway,
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::from([(account.address.clone(), stored_assets)])),
+            vec![account],
+        )
+        .await;
+
+        let account_update = accounts_update.account_updates.remove(0);
+        assert_eq!(
+            account_update.removed_fungibles,
+            BTreeSet::from([sent_fungible])
+        );
+        assert_eq!(
+            account_update.removed_non_fungibles,
+            BTreeSet::from([burned_nft])
+        );
+        assert_eq!(
+            account_update.removed_nfids,
+            HashMap::from([(nft, BTreeSet::from(["#3#".to_string()]))])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_requests_are_reported() {
+        let gateway = FakeGateway::from_json("{}").unwrap();
+        let account = account("account_rdx16y60m8p2lxl72rdqcxh6wj270ckku7e3hrr6fra05f9p34zlqwgd0k");
+        let mut account_with_state_version = account.clone();
+        account_with_state_version.balances_last_updated = Some(500);
+
+        let mut accounts_update = update_accounts(
+            gateway,
+            Network::Mainnet,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![account_with_state_version],
+        )
+        .await;
+
+        let requests = accounts_update
+            .failures
+            .iter()
+            .map(|failure| failure.request)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            ["fungible balances", "non-fungible balances", "transactions"]
+        );
+        assert!(
+            accounts_update
+                .failures
+                .iter()
+                .all(|failure| failure.account.as_ref() == Some(&account.address))
+        );
+
+        let account_update = accounts_update.account_updates.remove(0);
+        assert_eq!(account_update.account.balances_last_updated, Some(500));
+        assert_eq!(account_update.account.transactions_last_updated, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live mainnet gateway"]
     async fn test_update_accounts() {
         let network = Network::Mainnet;
 
@@ -683,8 +999,14 @@ mod tests {
             account_address,
             Ed25519PublicKey([0; Ed25519PublicKey::LENGTH]),
         );
-        let mut updated_accounts_entities =
-            update_accounts(network, Arc::new(HashMap::new()), vec![account]).await;
+        let mut updated_accounts_entities = update_accounts(
+            RadixGateway::new(network, RequestLimits::default()),
+            network,
+            Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
+            vec![account],
+        )
+        .await;
 
         let account_entities = updated_accounts_entities.account_updates.remove(0);
         let new_fungibles = account_entities.fungibles;
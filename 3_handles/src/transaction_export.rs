@@ -0,0 +1,471 @@
+use deps::*;
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Display,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use scrypto::math::CheckedAdd;
+use store::{AppDataDb, DbError};
+use thiserror::Error;
+use types::{
+    AppPath, Network, RadixDecimal, Resource, TimeStamp, Transaction,
+    address::{AccountAddress, Address, ResourceAddress},
+};
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("{0}")]
+    DbError(#[from] DbError),
+    #[error("Unable to write export: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::JsonLines => write!(f, "JSON Lines"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    In,
+    Out,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::In => write!(f, "in"),
+            Self::Out => write!(f, "out"),
+        }
+    }
+}
+
+/// One record per balance change. The fee is listed once per transaction on the XRD record, and is not
+/// part of its amount, so the amount and fee columns can be summed without counting the fee twice
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRecord {
+    pub date: String,
+    pub state_version: u64,
+    pub transaction_address: String,
+    pub account: String,
+    pub resource_address: String,
+    pub symbol: String,
+    pub amount: String,
+    pub direction: Direction,
+    pub fee: String,
+    pub message: String,
+    pub status: String,
+}
+
+impl ExportRecord {
+    const CSV_HEADER: &'static str = "date,state_version,transaction_address,account,resource_address,symbol,amount,direction,fee,message,status";
+
+    fn write_csv<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            self.date,
+            self.state_version,
+            self.transaction_address,
+            self.account,
+            self.resource_address,
+            csv_field(&self.symbol),
+            self.amount,
+            self.direction,
+            self.fee,
+            csv_field(&self.message),
+            self.status
+        )
+    }
+
+    fn write_json_line<W: Write>(&self, writer: &mut W) -> Result<(), std::io::Error> {
+        let record = serde_json::json!({
+            "date": self.date,
+            "state_version": self.state_version,
+            "transaction_address": self.transaction_address,
+            "account": self.account,
+            "resource_address": self.resource_address,
+            "symbol": self.symbol,
+            "amount": self.amount,
+            "direction": self.direction.to_string(),
+            "fee": self.fee,
+            "message": self.message,
+            "status": self.status,
+        });
+        writeln!(writer, "{record}")
+    }
+}
+
+/// Writes the transactions between `from` and `to` to a new file in the exports directory and returns the path.
+/// Without an account, the transactions of every account in the wallet are exported
+pub async fn export_transactions(
+    network: Network,
+    account_address: Option<AccountAddress>,
+    from: TimeStamp,
+    to: TimeStamp,
+    format: ExportFormat,
+) -> Result<PathBuf, ExportError> {
+    let db = AppDataDb::get(network).ok_or(DbError::DatabaseNotLoaded)?;
+
+    let exports_directory = AppPath::get().exports_directory();
+    std::fs::create_dir_all(&exports_directory)?;
+
+    let account = account_address
+        .as_ref()
+        .map(|account_address| account_address.checksum_as_str())
+        .unwrap_or("wallet");
+    let file_name = format!(
+        "transactions_{}_{}_{}.{}",
+        account,
+        &from.to_iso8601()[..10],
+        &to.to_iso8601()[..10],
+        format.extension()
+    );
+    let path = exports_directory.join(file_name);
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    write_transactions(db, account_address, from, to, format, &mut writer).await?;
+    writer.flush()?;
+
+    Ok(path)
+}
+
+/// Writes the transactions between `from` and `to` and returns the number of records written
+pub async fn write_transactions<W: Write>(
+    db: &AppDataDb,
+    account_address: Option<AccountAddress>,
+    from: TimeStamp,
+    to: TimeStamp,
+    format: ExportFormat,
+    writer: &mut W,
+) -> Result<usize, ExportError> {
+    let transactions = db
+        .get_transactions_in_range(account_address, from, to)
+        .await?;
+    let resources: HashMap<ResourceAddress, Resource> = db.get_all_resources().await?;
+
+    let records = export_records(transactions, &resources);
+
+    match format {
+        ExportFormat::Csv => {
+            writeln!(writer, "{}", ExportRecord::CSV_HEADER)?;
+            for record in &records {
+                record.write_csv(writer)?;
+            }
+        }
+        ExportFormat::JsonLines => {
+            for record in &records {
+                record.write_json_line(writer)?;
+            }
+        }
+    }
+
+    Ok(records.len())
+}
+
+pub fn export_records(
+    transactions: Vec<Transaction>,
+    resources: &HashMap<ResourceAddress, Resource>,
+) -> Vec<ExportRecord> {
+    transactions
+        .into_iter()
+        .flat_map(|transaction| {
+            let date = transaction.timestamp.to_iso8601();
+            let transaction_address = transaction.transaction_address.to_string();
+            let message = transaction.message.unwrap_or_default();
+            let status = transaction.status.to_string();
+
+            // The fee is paid in XRD and is part of the XRD balance change
+            let xrd = transaction.transaction_address.network().xrd_address();
+            let fee_index = transaction
+                .balance_changes
+                .iter()
+                .position(|balance_change| balance_change.resource.as_str() == xrd)
+                .unwrap_or(0);
+            let mut fee = transaction.fee;
+
+            transaction
+                .balance_changes
+                .into_iter()
+                .enumerate()
+                .map(move |(index, balance_change)| {
+                    let fee = match index == fee_index {
+                        true => fee.take(),
+                        false => None,
+                    };
+                    let amount = balance_change.amount.unwrap_or_default();
+                    let amount = match &fee {
+                        Some(fee) if balance_change.resource.as_str() == xrd => {
+                            amount_without_fee(&amount, fee).unwrap_or(amount)
+                        }
+                        _ => amount,
+                    };
+                    let (direction, amount) = match amount.strip_prefix('-') {
+                        Some(amount) => (Direction::Out, amount.to_string()),
+                        // Only the fee was paid
+                        None if fee.is_some() && is_zero(&amount) => (Direction::Out, amount),
+                        None => (Direction::In, amount),
+                    };
+                    let symbol = resources
+                        .get(&balance_change.resource)
+                        .map(|resource| resource.symbol.clone())
+                        .unwrap_or_default();
+
+                    ExportRecord {
+                        date: date.clone(),
+                        state_version: transaction.state_version,
+                        transaction_address: transaction_address.clone(),
+                        account: balance_change.account.to_string(),
+                        resource_address: balance_change.resource.to_string(),
+                        symbol,
+                        amount,
+                        direction,
+                        fee: fee.unwrap_or_default(),
+                        message: message.clone(),
+                        status: status.clone(),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The fee is stored as a positive amount and the balance change has the fee subtracted
+fn amount_without_fee(amount: &str, fee: &str) -> Option<String> {
+    let amount = RadixDecimal::from_str(amount).ok()?;
+    let fee = RadixDecimal::from_str(fee).ok()?;
+    Some(amount.checked_add(fee)?.to_string())
+}
+
+fn is_zero(amount: &str) -> bool {
+    RadixDecimal::from_str(amount).is_ok_and(|amount| amount.is_zero())
+}
+
+/// Quotes the field if it contains a separator, quote or line break.
+/// Fields starting with a character that spreadsheets read as the start of a formula are prefixed
+/// with an apostrophe, so a message or symbol can't run as a formula when the export is opened
+fn csv_field(value: &str) -> Cow<'_, str> {
+    let value = match value.starts_with(['=', '+', '-', '@']) {
+        true => Cow::Owned(format!("'{value}")),
+        false => Cow::Borrowed(value),
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use store::DataBase;
+    use types::{
+        BalanceChange, TransactionId, TransactionStatus,
+        address::TransactionAddress,
+        crypto::{KeySaltPair, Password},
+    };
+
+    use super::*;
+
+    const ACCOUNT: &str = "account_rdx12ymqrlezhreuknut5x5ucq30he638pqu9wum7nuxl65z9pjdt2a5ax";
+    const OTHER_ACCOUNT: &str =
+        "account_rdx12ymqrlezhreuknut5x5ueq30he638p8u9wum7nuxl65z9pjdeyvc8h";
+    const XRD: &str = "resource_rdx1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxradxrd";
+
+    fn transaction(
+        account: &str,
+        transaction_address: &str,
+        timestamp: TimeStamp,
+        state_version: u64,
+        amount: &str,
+        fee: Option<&str>,
+        message: Option<&str>,
+    ) -> Transaction {
+        let account = AccountAddress::from_str(account).unwrap();
+        let transaction_address = TransactionAddress::from_str(transaction_address).unwrap();
+        let id = TransactionId::new(&account, &transaction_address);
+
+        let mut transaction = Transaction::new(
+            timestamp,
+            state_version,
+            vec![BalanceChange::new(
+                id,
+                account.clone(),
+                ResourceAddress::from_str(XRD).unwrap(),
+                None,
+                None,
+                Some(amount.to_string()),
+            )],
+            &account,
+            transaction_address,
+            message.map(str::to_string),
+            TransactionStatus::Success,
+        );
+        transaction.fee = fee.map(str::to_string);
+        transaction
+    }
+
+    async fn fixture_db(path: &std::path::Path) -> AppDataDb {
+        let key = KeySaltPair::<DataBase>::new(Password::from("export test password").as_str())
+            .unwrap()
+            .into_key();
+        let db = AppDataDb::load_from_path(path, key).await.unwrap();
+
+        db.upsert_resources(vec![Resource {
+            address: ResourceAddress::from_str(XRD).unwrap(),
+            name: "Radix".to_string(),
+            symbol: "XRD".to_string(),
+            description: String::new(),
+            current_supply: String::new(),
+            divisibility: Some(18),
+            tags: Vec::<String>::new().into(),
+        }])
+        .await
+        .unwrap();
+
+        db.insert_transactions(vec![
+            transaction(
+                ACCOUNT,
+                "txid_rdx1v5ma7z8u6tn8jwhypwlt5eyjzuq9ch4c5k8ejftsn0xkjpqzfjysvl0q6y",
+                TimeStamp::new(2024, 3, 7, 14, 40, 35),
+                100,
+                "-10.5",
+                Some("0.5"),
+                Some("rent, march"),
+            ),
+            transaction(
+                ACCOUNT,
+                "txid_rdx1x4n6z9ex7xvnlwjw2ypc3cuqu0u5eu8gkhrttz8d7pnfqq7gk4qs7zzydq",
+                TimeStamp::new(2024, 6, 1, 9, 0, 0),
+                200,
+                "25",
+                None,
+                None,
+            ),
+            transaction(
+                OTHER_ACCOUNT,
+                "txid_rdx1ktmdf3dmzwzc4dvy6xvtqk9gusuy5uyrlpfnm0pjq7hmrqtqkx2qjqc2hx",
+                TimeStamp::new(2024, 6, 2, 9, 0, 0),
+                300,
+                "1",
+                None,
+                None,
+            ),
+            transaction(
+                ACCOUNT,
+                "txid_rdx1pjr6ug6e8fhjm4vmcqxglzkqfcz6yn4lc8j4fl0s2ns7rq9skspqlhrjvp",
+                TimeStamp::new(2025, 1, 1, 0, 0, 0),
+                400,
+                "3",
+                None,
+                None,
+            ),
+        ])
+        .await
+        .unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn test_export_transactions() {
+        let path = std::env::temp_dir().join(format!("mercurium_export_{}.db", std::process::id()));
+        let db = fixture_db(&path).await;
+
+        let from = TimeStamp::new(2024, 1, 1, 0, 0, 0);
+        let to = TimeStamp::new(2024, 12, 31, 23, 59, 59);
+        let account = AccountAddress::from_str(ACCOUNT).unwrap();
+
+        let mut csv = Vec::new();
+        let written = write_transactions(
+            &db,
+            Some(account.clone()),
+            from.clone(),
+            to.clone(),
+            ExportFormat::Csv,
+            &mut csv,
+        )
+        .await
+        .unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(written, 2);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], ExportRecord::CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!(
+                "2024-03-07T14:40:35Z,100,txid_rdx1v5ma7z8u6tn8jwhypwlt5eyjzuq9ch4c5k8ejftsn0xkjpqzfjysvl0q6y,{ACCOUNT},{XRD},XRD,10,out,0.5,\"rent, march\",Success"
+            )
+        );
+        assert!(lines[2].starts_with("2024-06-01T09:00:00Z,200,"));
+        assert!(lines[2].ends_with(",XRD,25,in,,,Success"));
+
+        let mut json_lines = Vec::new();
+        let written = write_transactions(
+            &db,
+            None,
+            from,
+            to,
+            ExportFormat::JsonLines,
+            &mut json_lines,
+        )
+        .await
+        .unwrap();
+        let records = String::from_utf8(json_lines)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(written, 3);
+        assert_eq!(records[0]["direction"], "out");
+        assert_eq!(records[0]["amount"], "10");
+        assert_eq!(records[0]["fee"], "0.5");
+        assert_eq!(records[0]["message"], "rent, march");
+        assert_eq!(records[2]["account"], OTHER_ACCOUNT);
+        assert_eq!(records[2]["state_version"], 300);
+
+        drop(db);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=1+1"), "'=1+1");
+        assert_eq!(csv_field("-2"), "'-2");
+        assert_eq!(csv_field("@SUM(A1, A2)"), "\"'@SUM(A1, A2)\"");
+    }
+}
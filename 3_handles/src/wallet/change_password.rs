@@ -0,0 +1,453 @@
+use deps::*;
+
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use debug_print::debug_println;
+use serde::{Deserialize, Serialize};
+use store::{AppDataDb, DataBase, DbError, IconsDb};
+use thiserror::Error;
+use types::{
+    AppError, AppPath, Network,
+    crypto::{
+        CryptoError, EncryptedMnemonic, EncryptedMnemonicError, HashedPassword, Key, KeySaltPair,
+        KeyType, Password, Salt,
+    },
+};
+
+use crate::credentials::{
+    get_db_encryption_salt, get_encrypted_mnemonic, store_db_encryption_salt,
+    store_encrypted_mnemonic,
+};
+
+const JOURNAL_FILE_NAME: &str = "password_change.json";
+const DB_EXTENSION: &str = "db";
+const BACKUP_EXTENSION: &str = "bak";
+
+#[derive(Debug, Error)]
+pub enum ChangePasswordError {
+    #[error("Incorrect password")]
+    IncorrectPassword,
+    #[error(
+        "The new password must be at least {} characters long",
+        Password::MIN_LEN
+    )]
+    PasswordTooShort,
+    #[error("{0}")]
+    Credentials(AppError),
+    #[error("Database error: {0}")]
+    Database(#[from] DbError),
+    #[error(transparent)]
+    Mnemonic(#[from] EncryptedMnemonicError),
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+    #[error("Failed to back up the databases: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to read or write the password change journal: {0}")]
+    Journal(#[from] serde_json::Error),
+    #[error("Task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    #[error("Failed to undo the password change, restart the wallet to restore it: {0}")]
+    RollbackFailed(String),
+}
+
+/// Written to the config directory before the first database is re-encrypted and removed
+/// once the new credentials are stored. A journal left behind means the change did not
+/// complete, it holds what is needed to put the old password back
+#[derive(Serialize, Deserialize)]
+struct Journal {
+    salt: Salt,
+    encrypted_mnemonic: EncryptedMnemonic,
+    /// Every database that is re-encrypted, each one has a backup next to it
+    databases: Vec<PathBuf>,
+}
+
+struct Credentials {
+    key: Key<DataBase>,
+    salt: Salt,
+    password_hash: HashedPassword,
+    encrypted_mnemonic: EncryptedMnemonic,
+}
+
+impl Credentials {
+    /// The stored credentials, slow as the key is derived from the password
+    fn current(password: &Password) -> Result<Self, ChangePasswordError> {
+        let salt = get_db_encryption_salt().map_err(ChangePasswordError::Credentials)?;
+        let encrypted_mnemonic =
+            get_encrypted_mnemonic().map_err(ChangePasswordError::Credentials)?;
+
+        Ok(Self {
+            key: Key::new(password.as_str(), &salt),
+            password_hash: password.derive_db_encryption_key_hash_from_salt(&salt),
+            salt,
+            encrypted_mnemonic,
+        })
+    }
+
+    /// New credentials for the new password with the mnemonic encrypted by the new password,
+    /// slow as two keys are derived from the password
+    fn derive_new(
+        &self,
+        old_password: &Password,
+        new_password: &Password,
+    ) -> Result<Self, ChangePasswordError> {
+        let (mnemonic, seed_password) = self.encrypted_mnemonic.decrypt_mnemonic(old_password)?;
+        let (key, salt) = KeySaltPair::<DataBase>::new(new_password.as_str())?.into_inner();
+        let encrypted_mnemonic = EncryptedMnemonic::new_with_key_and_salt(
+            &mnemonic,
+            seed_password.as_str(),
+            KeySaltPair::new(new_password.as_str())?,
+        )?;
+
+        Ok(Self {
+            key,
+            password_hash: new_password.derive_db_encryption_key_hash_from_salt(&salt),
+            salt,
+            encrypted_mnemonic,
+        })
+    }
+
+    fn store(&self) -> Result<(), ChangePasswordError> {
+        store_encrypted_mnemonic(&self.encrypted_mnemonic)
+            .map_err(ChangePasswordError::Credentials)?;
+        store_db_encryption_salt(self.salt.clone()).map_err(ChangePasswordError::Credentials)
+    }
+}
+
+/// An open database that is re-encrypted, the app data databases also hold the password hash
+enum OpenDatabase {
+    AppData(PathBuf, AppDataDb),
+    Icons(PathBuf, DataBase),
+}
+
+impl OpenDatabase {
+    fn path(&self) -> &Path {
+        match self {
+            Self::AppData(path, _) | Self::Icons(path, _) => path,
+        }
+    }
+
+    fn database(&self) -> &DataBase {
+        match self {
+            Self::AppData(_, db) => db,
+            Self::Icons(_, db) => db,
+        }
+    }
+
+    async fn rekey(&self, credentials: &Credentials) -> Result<(), DbError> {
+        match self {
+            Self::AppData(_, db) => {
+                db.upsert_password_hash(credentials.password_hash.clone())
+                    .await?;
+                db.rekey(credentials.key.clone()).await
+            }
+            Self::Icons(_, db) => db.rekey(credentials.key.clone()).await,
+        }
+    }
+}
+
+/// Changes the wallet password, the mnemonic is encrypted with the new password and the
+/// databases of every network are re-encrypted with a key derived from it.
+///
+/// The databases are backed up and a journal with the old credentials is written before
+/// anything is changed. A failure is rolled back right away, a crash is rolled back by
+/// [recover_interrupted_password_change] on the next start. Returns the new database key
+pub async fn change_password(
+    network: Network,
+    old_password: Password,
+    new_password: Password,
+) -> Result<Key<DataBase>, ChangePasswordError> {
+    if new_password.len() < Password::MIN_LEN {
+        return Err(ChangePasswordError::PasswordTooShort);
+    }
+    re_encrypt_wallet(network, old_password, new_password).await
+}
+
+/// Re-encrypts the wallet with keys derived with the current key derivation parameters if
+/// the stored salts use older ones, returns the new database key. The password stays the same,
+/// it runs after a successful login so wallets created with PBKDF2 move to Argon2id
+pub async fn upgrade_key_derivation(
+    network: Network,
+    password: Password,
+) -> Result<Option<Key<DataBase>>, ChangePasswordError> {
+    let salt = get_db_encryption_salt().map_err(ChangePasswordError::Credentials)?;
+    let encrypted_mnemonic = get_encrypted_mnemonic().map_err(ChangePasswordError::Credentials)?;
+    if salt.kdf() == &DataBase::KDF && encrypted_mnemonic.kdf() == &EncryptedMnemonic::KDF {
+        return Ok(None);
+    }
+
+    debug_println!("Upgrading the key derivation parameters");
+    re_encrypt_wallet(network, password.clone(), password)
+        .await
+        .map(Some)
+}
+
+async fn re_encrypt_wallet(
+    network: Network,
+    old_password: Password,
+    new_password: Password,
+) -> Result<Key<DataBase>, ChangePasswordError> {
+    let old_password_clone = old_password.clone();
+    let old =
+        tokio::task::spawn_blocking(move || Credentials::current(&old_password_clone)).await??;
+
+    let db = AppDataDb::get_or_init(network, old.key.clone()).await?;
+    if db.get_db_password_hash().await? != old.password_hash {
+        return Err(ChangePasswordError::IncorrectPassword);
+    }
+
+    let (old, new) = tokio::task::spawn_blocking(move || {
+        let new = old.derive_new(&old_password, &new_password)?;
+        Ok::<_, ChangePasswordError>((old, new))
+    })
+    .await??;
+
+    let databases = open_databases(&old.key).await?;
+    let paths = databases
+        .iter()
+        .map(|database| database.path().to_path_buf())
+        .collect::<Vec<_>>();
+
+    let journal = Journal {
+        salt: old.salt.clone(),
+        encrypted_mnemonic: old.encrypted_mnemonic.clone(),
+        databases: paths.clone(),
+    };
+    let backed_up = match back_up(&databases).await {
+        Ok(()) => write_journal(&journal),
+        Err(err) => Err(err),
+    };
+    if let Err(err) = backed_up {
+        remove_backups(&paths);
+        return Err(err);
+    }
+
+    debug_println!("Re-encrypting {} databases", databases.len());
+
+    match re_encrypt(&databases, &new).await {
+        Ok(()) => {
+            // The change is complete once the journal is removed
+            remove_journal()?;
+            remove_backups(&paths);
+            Ok(new.key.clone())
+        }
+        Err(err) => {
+            re_encrypt(&databases, &old).await.map_err(|rollback_err| {
+                ChangePasswordError::RollbackFailed(rollback_err.to_string())
+            })?;
+            remove_journal()?;
+            remove_backups(&paths);
+            Err(err)
+        }
+    }
+}
+
+/// Rolls back a password change that did not complete, the databases are restored from
+/// their backups and the old credentials from the journal. Backups left behind by a change
+/// without a journal are removed. Must run before any database is opened
+pub fn recover_interrupted_password_change() -> Result<(), ChangePasswordError> {
+    let app_path = AppPath::get();
+
+    if let Some(journal) = read_journal()? {
+        debug_println!("Rolling back an interrupted password change");
+        restore_databases(&journal.databases)?;
+        store_encrypted_mnemonic(&journal.encrypted_mnemonic)
+            .map_err(ChangePasswordError::Credentials)?;
+        store_db_encryption_salt(journal.salt.clone()).map_err(ChangePasswordError::Credentials)?;
+        remove_journal()?;
+    }
+
+    for directory in [app_path.db_directory_ref(), app_path.icons_directory_ref()] {
+        for backup in files_with_extension(directory, BACKUP_EXTENSION)? {
+            fs::remove_file(backup)?;
+        }
+    }
+    Ok(())
+}
+
+/// Re-encrypts every database with the key of the credentials and stores the credentials.
+/// A database that already has the key is re-encrypted with the same key
+async fn re_encrypt(
+    databases: &[OpenDatabase],
+    credentials: &Credentials,
+) -> Result<(), ChangePasswordError> {
+    for database in databases {
+        database.rekey(credentials).await?;
+    }
+    credentials.store()
+}
+
+/// The app data databases and icon caches of every network, the ones that are loaded are
+/// used as is, the others are opened with `key`
+async fn open_databases(key: &Key<DataBase>) -> Result<Vec<OpenDatabase>, ChangePasswordError> {
+    let app_path = AppPath::get();
+    let mut databases = Vec::new();
+
+    let loaded_app_data = AppDataDb::loaded();
+    for path in files_with_extension(app_path.db_directory_ref(), DB_EXTENSION)? {
+        let loaded = loaded_app_data
+            .iter()
+            .find(|(network, _)| app_path.db_path_ref(*network) == path.as_path());
+        let db = match loaded {
+            Some((_, db)) => AppDataDb::clone(db),
+            None => AppDataDb::load_from_path(&path, key.clone()).await?,
+        };
+        databases.push(OpenDatabase::AppData(path, db));
+    }
+
+    let loaded_icons = IconsDb::loaded();
+    for path in files_with_extension(app_path.icons_directory_ref(), DB_EXTENSION)? {
+        let loaded = loaded_icons
+            .iter()
+            .find(|(network, _)| app_path.icon_cache_ref(*network) == path.as_path());
+        let db = match loaded {
+            Some((_, db)) => DataBase::clone(db),
+            None => DataBase::clone(&IconsDb::load_from_path(&path, key.clone()).await?),
+        };
+        databases.push(OpenDatabase::Icons(path, db));
+    }
+
+    Ok(databases)
+}
+
+/// The files in `directory` with the extension, empty if the directory does not exist
+fn files_with_extension(directory: &Path, extension: &str) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Backs up each database through its own connection, a copy of the file could miss writes
+/// the connection has not yet flushed
+async fn back_up(databases: &[OpenDatabase]) -> Result<(), ChangePasswordError> {
+    for database in databases {
+        let backup = backup_path(database.path());
+        // A backup left behind by an earlier change would make the copy fail
+        match fs::remove_file(&backup) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        }
+        database.database().back_up_into(&backup).await?;
+        fs::File::open(&backup)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Copies the backups over the databases, a database without a backup was not changed
+fn restore_databases(databases: &[PathBuf]) -> io::Result<()> {
+    for database in databases {
+        let backup = backup_path(database);
+        if !backup.exists() {
+            continue;
+        }
+        // The rollback journal of SQLite belongs to the re-encrypted file
+        match fs::remove_file(suffixed(database, "-journal")) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        fs::copy(&backup, database)?;
+        fs::File::open(database)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn remove_backups(databases: &[PathBuf]) {
+    for database in databases {
+        fs::remove_file(backup_path(database)).ok();
+    }
+}
+
+fn backup_path(database: &Path) -> PathBuf {
+    suffixed(database, &format!(".{BACKUP_EXTENSION}"))
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn journal_path() -> PathBuf {
+    AppPath::get()
+        .config_directory_ref()
+        .join(JOURNAL_FILE_NAME)
+}
+
+/// Written to a temporary file first so a crash never leaves a partial journal
+fn write_journal(journal: &Journal) -> Result<(), ChangePasswordError> {
+    let path = journal_path();
+    let temporary = path.with_extension("tmp");
+
+    let mut file = fs::File::create(&temporary)?;
+    serde_json::to_writer(&mut file, journal)?;
+    file.sync_all()?;
+    fs::rename(temporary, path)?;
+    Ok(())
+}
+
+fn read_journal() -> Result<Option<Journal>, ChangePasswordError> {
+    match fs::read(journal_path()) {
+        Ok(content) => Ok(Some(serde_json::from_slice(&content)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn remove_journal() -> io::Result<()> {
+    fs::remove_file(journal_path())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_restore_databases_from_backups() {
+        let directory = Path::new("./mock_password_change");
+        fs::create_dir_all(directory).unwrap();
+        let changed = directory.join("mainnet.db");
+        let unchanged = directory.join("stokenet.db");
+
+        fs::write(&changed, "old").unwrap();
+        fs::write(&unchanged, "old").unwrap();
+        let databases = vec![changed.clone(), unchanged.clone()];
+        fs::write(backup_path(&changed), "old").unwrap();
+
+        fs::write(&changed, "new").unwrap();
+        fs::write(suffixed(&changed, "-journal"), "journal").unwrap();
+        restore_databases(&databases).unwrap();
+
+        let backups = files_with_extension(directory, BACKUP_EXTENSION).unwrap();
+        let restored = fs::read_to_string(&changed).unwrap();
+        let journal_exists = suffixed(&changed, "-journal").exists();
+        remove_backups(&databases);
+        let backups_after_removal = files_with_extension(directory, BACKUP_EXTENSION).unwrap();
+        fs::remove_dir_all(directory).ok();
+
+        assert_eq!(backups, vec![directory.join("mainnet.db.bak")]);
+        assert_eq!(restored, "old");
+        assert!(!journal_exists);
+        assert!(backups_after_removal.is_empty());
+    }
+
+    #[test]
+    fn test_files_with_extension_of_missing_directory() {
+        let files = files_with_extension(Path::new("./does_not_exist"), DB_EXTENSION);
+        assert!(files.unwrap().is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+mod change_password;
 mod create_account;
 mod create_wallet;
 mod login;
@@ -5,4 +6,8 @@ mod login;
 // pub use create_account::create_account_from_mnemonic;
 // pub use create_account::create_multiple_accounts_from_mnemonic;
 // pub use create_wallet::create_new_wallet_with_accounts;
+pub use change_password::{
+    ChangePasswordError, change_password, recover_interrupted_password_change,
+    upgrade_key_derivation,
+};
 pub use login::perform_login_check;
@@ -5,6 +5,8 @@ pub mod error;
 
 
 pub use wallet::Wallet;
+pub use wallet::backup::{BackupAccount, BackupError, WalletBackup};
+pub use wallet::wallet_setup::radix_wallet_profile::{ProfileImport, ProfileImportError};
 pub use wallet::wallet_setup::setup::Setup;
 pub use wallet::wallet_setup::setup_error::SetupError;
 pub use wallet::unlocked::Unlocked;
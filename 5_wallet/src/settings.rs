@@ -1,35 +1,119 @@
-use std::{fs::File, io::BufReader};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    time::Duration,
+};
 
 use deps::{
     serde::{Deserialize, Serialize},
     serde_json,
 };
-use types::{AppPath, Network};
+use handles::{credentials, http, image::download};
+use types::{
+    AppError, AppPath, CredentialBackend, Currency, CustomNetwork, Fee, GatewayEndpoint, Network,
+    NetworkError, Notification, PriceSourceConfig, Proxy, RequestLimits,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub network: Network,
     pub max_login_attempts: usize,
+    /// The safety margin added to the previewed fee of a transfer
+    #[serde(default = "Settings::default_fee_margin_percent")]
+    pub fee_margin_percent: u8,
+    #[serde(default)]
+    pub custom_networks: Vec<CustomNetwork>,
+    /// Gateways used instead of the default gateway of a network
+    #[serde(default)]
+    pub gateway_endpoints: Vec<GatewayEndpoint>,
+    #[serde(default)]
+    pub request_limits: RequestLimits,
+    /// How often the accounts are refreshed in the background, `0` turns the refresh off
+    #[serde(default = "Settings::default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    /// Every outbound request is sent through the proxy, icon downloads included
+    #[serde(default)]
+    pub proxy: Option<Proxy>,
+    /// Icon downloads can be turned off, they tell the hosts of the icons which resources are held
+    #[serde(default = "Settings::default_download_icons")]
+    pub download_icons: bool,
+    /// The currency the value of the assets is shown in
+    #[serde(default)]
+    pub currency: Currency,
+    /// The value of the assets is not shown without a price source
+    #[serde(default)]
+    pub price_source: Option<PriceSourceConfig>,
+    /// Where the salt and the encrypted recovery phrase are kept
+    #[serde(default)]
+    pub credential_backend: CredentialBackend,
 }
 
 impl Settings {
     const DEFAULT_MAX_LOGIN_ATTEMPTS: usize = 100;
+    const DEFAULT_REFRESH_INTERVAL_SECS: u64 = 60;
 
     pub fn new() -> Self {
         Self {
             network: Network::default(),
             max_login_attempts: Self::DEFAULT_MAX_LOGIN_ATTEMPTS,
+            fee_margin_percent: Fee::DEFAULT_MARGIN_PERCENT,
+            custom_networks: Vec::new(),
+            gateway_endpoints: Vec::new(),
+            request_limits: RequestLimits::default(),
+            refresh_interval_secs: Self::DEFAULT_REFRESH_INTERVAL_SECS,
+            proxy: None,
+            download_icons: true,
+            currency: Currency::default(),
+            price_source: None,
+            credential_backend: CredentialBackend::default(),
         }
     }
 
+    fn default_fee_margin_percent() -> u8 {
+        Fee::DEFAULT_MARGIN_PERCENT
+    }
+
+    fn default_refresh_interval_secs() -> u64 {
+        Self::DEFAULT_REFRESH_INTERVAL_SECS
+    }
+
+    fn default_download_icons() -> bool {
+        true
+    }
+
+    /// Also registers the custom networks, gateways and the proxy, so they are known before
+    /// anything is read from the network databases or sent over the network.
+    /// The credential backend is not applied, see [Settings::use_credential_backend]
     pub fn load_from_disk_or_default() -> Self {
-        match File::open(AppPath::get().settings_path_ref()) {
+        let settings = match File::open(AppPath::get().settings_path_ref()) {
             Ok(file) => {
                 let content = BufReader::new(file);
                 serde_json::from_reader::<_, Self>(content).unwrap_or(Self::new())
             }
             Err(_) => Self::new(),
-        }
+        };
+        settings.register_network_settings();
+        settings
+    }
+
+    /// Reads and writes the credentials with the credential backend of the settings and moves
+    /// them to it if it has changed, called once at startup. If they can not be moved they stay
+    /// where they are, the error tells which backend is used instead
+    pub fn use_credential_backend(&self) -> Result<(), AppError> {
+        credentials::use_credential_backend(self.credential_backend)
+            .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+    }
+
+    pub fn save_to_disk(&self) -> Result<(), std::io::Error> {
+        let file = File::create(AppPath::get().settings_path_ref())?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    pub(crate) fn register_network_settings(&self) {
+        Network::register(&self.custom_networks, &self.gateway_endpoints);
+        http::set_proxy(self.proxy.clone());
+        download::set_icon_downloads_enabled(self.download_icons);
     }
 
     pub fn network(&self) -> Network {
@@ -47,4 +131,122 @@ impl Settings {
     pub fn set_max_login_attempts(&mut self, max_login_attempts: usize) {
         self.max_login_attempts = max_login_attempts;
     }
+
+    pub fn fee_margin_percent(&self) -> u8 {
+        self.fee_margin_percent
+    }
+
+    pub fn set_fee_margin_percent(&mut self, fee_margin_percent: u8) {
+        self.fee_margin_percent = fee_margin_percent;
+    }
+
+    pub fn custom_networks(&self) -> &[CustomNetwork] {
+        &self.custom_networks
+    }
+
+    pub fn add_custom_network(
+        &mut self,
+        custom_network: CustomNetwork,
+    ) -> Result<(), NetworkError> {
+        custom_network.validate_against(&self.custom_networks)?;
+        self.custom_networks.push(custom_network);
+        self.register_network_settings();
+        Ok(())
+    }
+
+    /// The database of the network is left on disk
+    pub fn remove_custom_network(&mut self, id: u8) {
+        self.custom_networks
+            .retain(|custom_network| custom_network.id != id);
+        self.gateway_endpoints
+            .retain(|endpoint| endpoint.network != Network::Custom(id));
+        self.register_network_settings();
+    }
+
+    /// Sets the gateway of a network, `None` goes back to the default gateway of the network
+    pub fn set_gateway_url(&mut self, network: Network, url: Option<String>) {
+        self.gateway_endpoints
+            .retain(|endpoint| endpoint.network != network);
+        if let Some(url) = url {
+            self.gateway_endpoints
+                .push(GatewayEndpoint { network, url });
+        }
+        self.register_network_settings();
+    }
+
+    pub fn request_limits(&self) -> RequestLimits {
+        self.request_limits
+    }
+
+    pub fn set_request_limits(&mut self, request_limits: RequestLimits) {
+        self.request_limits = request_limits;
+    }
+
+    /// `None` if the background refresh is turned off
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        (self.refresh_interval_secs > 0).then(|| Duration::from_secs(self.refresh_interval_secs))
+    }
+
+    /// `None` turns the background refresh off
+    pub fn set_refresh_interval(&mut self, refresh_interval: Option<Duration>) {
+        self.refresh_interval_secs = refresh_interval.map_or(0, |interval| interval.as_secs());
+    }
+
+    pub fn proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    /// `None` sends requests directly
+    pub fn set_proxy(&mut self, proxy: Option<Proxy>) {
+        self.proxy = proxy;
+        self.register_network_settings();
+    }
+
+    pub fn download_icons(&self) -> bool {
+        self.download_icons
+    }
+
+    pub fn set_download_icons(&mut self, download_icons: bool) {
+        self.download_icons = download_icons;
+        self.register_network_settings();
+    }
+
+    pub fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    pub fn set_currency(&mut self, currency: Currency) {
+        self.currency = currency;
+    }
+
+    pub fn price_source(&self) -> Option<&PriceSourceConfig> {
+        self.price_source.as_ref()
+    }
+
+    /// `None` stops showing the value of the assets
+    pub fn set_price_source(&mut self, price_source: Option<PriceSourceConfig>) {
+        self.price_source = price_source;
+    }
+
+    pub fn credential_backend(&self) -> CredentialBackend {
+        self.credential_backend
+    }
+
+    /// Moves the credentials to `backend` and saves the settings. If they can not be moved
+    /// they stay in the current backend and moving them is tried again on the next start
+    pub fn set_credential_backend(&mut self, backend: CredentialBackend) -> Result<(), AppError> {
+        if !backend.is_supported() {
+            return Err(AppError::NonFatal(Notification::Info(format!(
+                "{backend} is not available on this platform"
+            ))));
+        }
+        self.credential_backend = backend;
+        self.save_to_disk().map_err(|err| {
+            AppError::NonFatal(Notification::Warn(format!(
+                "Unable to save settings: {err}"
+            )))
+        })?;
+
+        self.use_credential_backend()
+    }
 }
@@ -1,15 +1,14 @@
+pub(crate) mod backup;
 pub(crate) mod locked;
 pub(crate) mod resource_data;
 pub(crate) mod unlocked;
 pub(crate) mod wallet_data;
 pub(crate) mod wallet_setup;
 
-use std::str::FromStr;
-
 use deps::bip39::Mnemonic;
 use types::{
     Account, Network, UnwrapUnreachable,
-    address::AccountAddress,
+    address::{AccountAddress, Address},
     crypto::{Bip32Entity, Bip32KeyKind, Ed25519KeyPair},
     debug_info,
 };
@@ -83,7 +82,7 @@ pub(crate) fn create_account_from_mnemonic(
 
     let radixdlt_pub_key = keypair.radixdlt_public_key();
     let account_address = keypair.bech32_address();
-    let account_address = AccountAddress::from_str(account_address.as_str())
+    let account_address = AccountAddress::from_str_on(network, account_address.as_str())
         .unwrap_unreachable(debug_info!("Invalid account address"));
 
     let account = Account::new(
@@ -0,0 +1,551 @@
+use deps::{tokio::task::JoinHandle, *};
+
+use std::{
+    collections::BTreeSet,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use handles::credentials;
+use serde::{Deserialize, Serialize};
+use store::{AppDataDb, DataBase, DbError, IconsDb};
+use thiserror::Error;
+use types::{
+    Account, AccountSettings, AddressBookEntry, AppError, AppPath, AppPathError, CredentialBackend,
+    Network, Notification, TimeStamp,
+    address::{AccountAddress, Address},
+    crypto::{CryptoError, EncryptedBackup, EncryptedMnemonic, Key, KeySaltPair, Password},
+};
+use zeroize::Zeroize;
+
+use crate::{Settings, wallet::create_account_from_mnemonic};
+
+use super::{Wallet, unlocked::Unlocked, wallet_data::WalletData, wallet_setup::setup::Setup};
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("Incorrect password")]
+    IncorrectPassword,
+    #[error(
+        "The backup password needs to be at least {} characters long",
+        Password::MIN_LEN
+    )]
+    PasswordTooShort,
+    #[error("Unable to open the backup\n ∟{0}")]
+    Crypto(#[from] CryptoError),
+    #[error("Unable to read or write the backup file\n ∟{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid backup file\n ∟{0}")]
+    InvalidBackup(#[from] serde_json::Error),
+    #[error("Account {0} does not belong to the recovery phrase of the backup")]
+    AccountMismatch(String),
+    #[error("A wallet already exists on {0}")]
+    WalletExists(String),
+    #[error("The credential store already holds the credentials of a wallet")]
+    CredentialsExist,
+    #[error("Credentials error\n ∟{0}")]
+    Credentials(AppError),
+    #[error("Database error\n ∟{0}")]
+    Database(#[from] DbError),
+    #[error("Application directory error\n ∟{0}")]
+    AppPath(#[from] AppPathError),
+}
+
+impl From<BackupError> for AppError {
+    fn from(err: BackupError) -> Self {
+        match err {
+            BackupError::IncorrectPassword
+            | BackupError::PasswordTooShort
+            | BackupError::Crypto(_)
+            | BackupError::AccountMismatch(_)
+            | BackupError::WalletExists(_)
+            | BackupError::CredentialsExist => {
+                AppError::NonFatal(Notification::Info(err.to_string()))
+            }
+            _ => AppError::NonFatal(Notification::Warn(err.to_string())),
+        }
+    }
+}
+
+/// An account as it is written to a backup, the keys are derived again from the recovery phrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupAccount {
+    pub network: Network,
+    pub id: usize,
+    pub name: String,
+    pub derivation_path: [u32; 6],
+    pub address: AccountAddress,
+    pub hidden: bool,
+    /// Holds the third party deposit rules
+    pub settings: AccountSettings,
+}
+
+impl From<Account> for BackupAccount {
+    fn from(account: Account) -> Self {
+        Self {
+            network: account.network,
+            id: account.id,
+            name: account.name.clone(),
+            derivation_path: account.derivation_path(),
+            address: account.address,
+            hidden: account.hidden,
+            settings: account.settings,
+        }
+    }
+}
+
+/// An address book entry as it is written to a backup, with the network of its database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupAddressBookEntry {
+    pub network: Network,
+    #[serde(flatten)]
+    pub entry: AddressBookEntry,
+}
+
+/// Everything needed to rebuild the wallet without the ledger,
+/// written to a file as an [EncryptedBackup]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletBackup {
+    /// Still encrypted with the wallet password at the time of the backup
+    pub encrypted_mnemonic: EncryptedMnemonic,
+    /// The accounts of every network
+    pub accounts: Vec<BackupAccount>,
+    pub settings: Settings,
+    /// The address books of every network
+    #[serde(default)]
+    pub address_book: Vec<BackupAddressBookEntry>,
+}
+
+impl WalletBackup {
+    /// Reads the accounts and address books of every network the wallet has a database for
+    pub(crate) async fn collect(
+        settings: Settings,
+        key: Key<DataBase>,
+    ) -> Result<Self, BackupError> {
+        let encrypted_mnemonic =
+            credentials::get_encrypted_mnemonic().map_err(BackupError::Credentials)?;
+
+        let mut accounts = Vec::new();
+        let mut address_book = Vec::new();
+        for network in Self::networks(&settings) {
+            if !AppDataDb::exists(network) {
+                continue;
+            }
+            let db = AppDataDb::get_or_init(network, key.clone()).await?;
+            let network_accounts: Vec<Account> = db.get_accounts().await?;
+            accounts.extend(network_accounts.into_iter().map(BackupAccount::from));
+            address_book.extend(
+                db.get_address_book()
+                    .await?
+                    .into_iter()
+                    .map(|entry| BackupAddressBookEntry { network, entry }),
+            );
+        }
+        accounts.sort_by_key(|account| (account.network, account.id));
+
+        Ok(Self {
+            encrypted_mnemonic,
+            accounts,
+            settings,
+            address_book,
+        })
+    }
+
+    /// Slow, the key of the backup is derived from `backup_password`. The file is only readable
+    /// by the owner and is written to a temporary file first, so a failed write never leaves
+    /// a partial backup at `path`
+    pub fn write_to_file(
+        &self,
+        path: &Path,
+        backup_password: &Password,
+    ) -> Result<(), BackupError> {
+        if backup_password.len() < Password::MIN_LEN {
+            return Err(BackupError::PasswordTooShort);
+        }
+        let mut document = serde_json::to_vec(self)?;
+        let encrypted = EncryptedBackup::seal(&document, backup_password);
+        document.zeroize();
+
+        let temporary = path.with_extension("tmp");
+        let written = write_private_file(&temporary, &serde_json::to_vec_pretty(&encrypted?)?)
+            .and_then(|_| std::fs::rename(&temporary, path));
+        if written.is_err() {
+            std::fs::remove_file(&temporary).ok();
+        }
+        Ok(written?)
+    }
+
+    /// Slow, the key of the backup is derived from `backup_password`
+    pub fn read_from_file(path: &Path, backup_password: &Password) -> Result<Self, BackupError> {
+        let encrypted: EncryptedBackup = serde_json::from_slice(&std::fs::read(path)?)?;
+        let mut document = encrypted.open(backup_password)?;
+        let backup = serde_json::from_slice(&document);
+        document.zeroize();
+
+        Ok(backup?)
+    }
+
+    /// Rebuilds the databases of every network in the backup and stores the credentials,
+    /// `password` is the wallet password at the time of the backup and stays the password of
+    /// the restored wallet. Refused if a wallet already exists, nothing is left behind if the
+    /// restore fails. Also returns the error of a failed sync or credential move, the wallet
+    /// is restored regardless
+    pub async fn restore(
+        self,
+        password: Password,
+        resync: bool,
+    ) -> Result<(Wallet<Unlocked>, Option<AppError>), BackupError> {
+        let mut settings = self.settings;
+        if !settings.credential_backend.is_supported() {
+            settings.credential_backend = CredentialBackend::default();
+        }
+
+        let networks = self
+            .accounts
+            .iter()
+            .map(|account| account.network)
+            .chain(self.address_book.iter().map(|entry| entry.network))
+            .chain(std::iter::once(settings.network))
+            .collect::<BTreeSet<_>>();
+        let existing = networks
+            .iter()
+            .copied()
+            .chain(Self::networks(&settings))
+            .find(|network| AppDataDb::exists(*network));
+        if let Some(network) = existing {
+            return Err(BackupError::WalletExists(network.name()));
+        }
+        let holds_credentials = credentials::credential_store()
+            .holds_credentials()
+            .map_err(|err| {
+                BackupError::Credentials(AppError::NonFatal(Notification::Warn(err.to_string())))
+            })?;
+        if holds_credentials {
+            return Err(BackupError::CredentialsExist);
+        }
+
+        // Only once nothing of an existing wallet can be overwritten
+        settings.register_network_settings();
+
+        let (mnemonic, seed_password) = self
+            .encrypted_mnemonic
+            .decrypt_mnemonic(&password)
+            .map_err(|_| BackupError::IncorrectPassword)?;
+
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|backup_account| {
+                let mut account = create_account_from_mnemonic(
+                    &mnemonic,
+                    Some(seed_password.as_str()),
+                    backup_account.id,
+                    backup_account.derivation_path[5],
+                    backup_account.name.clone(),
+                    backup_account.network,
+                );
+                if account.address != backup_account.address
+                    || account.derivation_path() != backup_account.derivation_path
+                {
+                    return Err(BackupError::AccountMismatch(
+                        backup_account.address.truncate_long(),
+                    ));
+                }
+                account.hidden = backup_account.hidden;
+                account.settings = backup_account.settings.clone();
+                Ok(account)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        AppPath::get().create_directories_if_not_exists()?;
+
+        let (key, salt) = KeySaltPair::<DataBase>::new(password.as_str())?.into_inner();
+        let password_hash = password.derive_db_encryption_key_hash_from_salt(&salt);
+
+        let written = async {
+            credentials::store_encrypted_mnemonic(&self.encrypted_mnemonic)
+                .map_err(BackupError::Credentials)?;
+            credentials::store_db_encryption_salt(salt).map_err(BackupError::Credentials)?;
+
+            for network in &networks {
+                let path = AppPath::get().db_path(*network);
+                let db = AppDataDb::load_from_path(&path, key.clone()).await?;
+                db.upsert_password_hash(password_hash.clone()).await?;
+                db.upsert_accounts(
+                    accounts
+                        .iter()
+                        .filter(|account| account.network == *network)
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+                db.upsert_address_book_entries(
+                    self.address_book
+                        .iter()
+                        .filter(|entry| entry.network == *network)
+                        .map(|entry| entry.entry.clone())
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+            }
+            settings.save_to_disk()?;
+            Ok::<_, BackupError>(())
+        }
+        .await;
+
+        if let Err(err) = written {
+            Self::remove_restored(&networks);
+            return Err(err);
+        }
+
+        // Registers the restored settings and moves the credentials if they are kept
+        // in another backend
+        let settings = Settings::load_from_disk_or_default();
+        let mut warning = settings.use_credential_backend().err();
+        let network = settings.network;
+        let mut wallet = Wallet {
+            state: Unlocked::new(key.clone()),
+            wallet_data: WalletData::new(settings),
+        };
+
+        let app_data_db = AppDataDb::get_or_init(network, key.clone()).await?;
+        let icons_db = IconsDb::get_or_init(network, key).await?;
+        let currency = wallet.wallet_data.settings.currency.clone();
+        Arc::make_mut(&mut wallet.wallet_data.resource_data)
+            .load_resource_data_from_disk(app_data_db, icons_db, currency)
+            .await?;
+
+        if resync {
+            let synced = match wallet.update_accounts().await {
+                Ok(accounts_update) => match wallet.apply_accounts_update(accounts_update).await {
+                    Ok(saved) => saved,
+                    Err(err) => Err(AppError::NonFatal(Notification::Warn(format!(
+                        "Failed to save the synced accounts: {err}"
+                    )))),
+                },
+                Err(err) => Err(AppError::NonFatal(Notification::Warn(format!(
+                    "Failed to sync the restored accounts: {err}"
+                )))),
+            };
+            warning = warning.or(synced.err());
+        }
+
+        Ok((wallet, warning))
+    }
+
+    /// The networks the wallet can have accounts on
+    fn networks(settings: &Settings) -> Vec<Network> {
+        [Network::Mainnet, Network::Stokenet]
+            .into_iter()
+            .chain(
+                settings
+                    .custom_networks()
+                    .iter()
+                    .map(|custom_network| Network::Custom(custom_network.id)),
+            )
+            .collect()
+    }
+
+    /// Removes what a failed restore has written, the databases did not exist before it
+    fn remove_restored(networks: &BTreeSet<Network>) {
+        for network in networks {
+            std::fs::remove_file(AppPath::get().db_path(*network)).ok();
+        }
+        std::fs::remove_file(AppPath::get().settings_path_ref()).ok();
+        credentials::delete_salt().ok();
+        credentials::delete_encrypted_mnemonic().ok();
+    }
+}
+
+impl Wallet<Unlocked> {
+    /// Writes a backup of the wallet to the exports directory, encrypted with `backup_password`,
+    /// and returns the path of the file. `password` is checked against the wallet password
+    pub fn export_backup(
+        &self,
+        password: Password,
+        backup_password: Password,
+    ) -> JoinHandle<Result<PathBuf, AppError>> {
+        let settings = self.wallet_data.settings.clone();
+        let key = self.state.key.clone();
+
+        tokio::spawn(async move {
+            let salt = credentials::get_db_encryption_salt()?;
+            let db = AppDataDb::get_or_init(settings.network, key.clone())
+                .await
+                .map_err(BackupError::from)?;
+            let password_hash = db.get_db_password_hash().await.map_err(BackupError::from)?;
+            if password.derive_db_encryption_key_hash_from_salt(&salt) != password_hash {
+                return Err(BackupError::IncorrectPassword.into());
+            }
+
+            let backup = WalletBackup::collect(settings, key).await?;
+
+            let exports_directory = AppPath::get().exports_directory();
+            std::fs::create_dir_all(&exports_directory).map_err(BackupError::from)?;
+            let path = backup_file_path(&exports_directory);
+
+            tokio::task::spawn_blocking(move || {
+                backup.write_to_file(&path, &backup_password).map(|_| path)
+            })
+            .await
+            .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?
+            .map_err(AppError::from)
+        })
+    }
+}
+
+impl Wallet<Setup> {
+    /// Restores the wallet from the backup file at `path`, see [WalletBackup::restore]
+    pub fn restore_from_backup(
+        &self,
+        path: PathBuf,
+        backup_password: Password,
+        password: Password,
+        resync: bool,
+    ) -> JoinHandle<Result<(Wallet<Unlocked>, Option<AppError>), AppError>> {
+        tokio::spawn(async move {
+            let backup = tokio::task::spawn_blocking(move || {
+                WalletBackup::read_from_file(&path, &backup_password)
+            })
+            .await
+            .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))??;
+
+            Ok(backup.restore(password, resync).await?)
+        })
+    }
+}
+
+/// `wallet_backup_<date>T<time>.json` in `directory`, a counter is added to the name if a
+/// backup was already exported in the same second
+fn backup_file_path(directory: &Path) -> PathBuf {
+    let timestamp = TimeStamp::now().to_iso8601()[..19].replace(':', "-");
+    let mut path = directory.join(format!("wallet_backup_{timestamp}.json"));
+    let mut counter = 1;
+    while path.exists() {
+        counter += 1;
+        path = directory.join(format!("wallet_backup_{timestamp}_{counter}.json"));
+    }
+    path
+}
+
+/// Creates the file readable and writable only by the owner, on platforms with unix permissions
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    // A leftover file would keep its permissions
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use types::crypto::bip39::{Language, Mnemonic, MnemonicType};
+
+    #[test]
+    fn test_backup_file_round_trip() {
+        let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let password = Password::from("wallet password");
+        let mut account = create_account_from_mnemonic(
+            &mnemonic,
+            None,
+            0,
+            0,
+            "Savings".to_string(),
+            Network::Mainnet,
+        );
+        account.hidden = true;
+        let entry = AddressBookEntry {
+            address: account.address.clone(),
+            name: "Savings".to_string(),
+        };
+
+        let backup = WalletBackup {
+            encrypted_mnemonic: EncryptedMnemonic::new(&mnemonic, "", &password).unwrap(),
+            accounts: vec![BackupAccount::from(account.clone())],
+            settings: Settings::new(),
+            address_book: vec![BackupAddressBookEntry {
+                network: Network::Mainnet,
+                entry: entry.clone(),
+            }],
+        };
+        let path = std::env::temp_dir().join(format!("wallet_backup_{}.json", std::process::id()));
+        let backup_password = Password::from("backup password");
+
+        backup.write_to_file(&path, &backup_password).unwrap();
+        let wrong_password = WalletBackup::read_from_file(&path, &Password::from("wrong password"));
+        let restored = WalletBackup::read_from_file(&path, &backup_password).unwrap();
+        #[cfg(unix)]
+        let permissions = std::fs::metadata(&path).unwrap().permissions();
+        std::fs::remove_file(&path).ok();
+
+        #[cfg(unix)]
+        assert_eq!(
+            std::os::unix::fs::PermissionsExt::mode(&permissions) & 0o777,
+            0o600
+        );
+        assert!(matches!(
+            wrong_password,
+            Err(BackupError::Crypto(CryptoError::Decryption))
+        ));
+        assert_eq!(restored.accounts.len(), 1);
+        assert_eq!(restored.accounts[0].address, account.address);
+        assert_eq!(
+            restored.accounts[0].derivation_path,
+            account.derivation_path()
+        );
+        assert!(restored.accounts[0].hidden);
+        assert_eq!(restored.address_book.len(), 1);
+        assert_eq!(restored.address_book[0].network, Network::Mainnet);
+        assert_eq!(restored.address_book[0].entry, entry);
+        let (restored_mnemonic, _) = restored
+            .encrypted_mnemonic
+            .decrypt_mnemonic(&password)
+            .unwrap();
+        assert_eq!(restored_mnemonic.phrase(), mnemonic.phrase());
+    }
+
+    #[test]
+    fn test_backup_file_path_does_not_overwrite_a_backup() {
+        let directory = std::env::temp_dir().join(format!("wallet_backups_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let first = backup_file_path(&directory);
+        std::fs::write(&first, "").unwrap();
+        let second = backup_file_path(&directory);
+        std::fs::remove_dir_all(&directory).ok();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_short_backup_password_is_rejected() {
+        let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let backup = WalletBackup {
+            encrypted_mnemonic: EncryptedMnemonic::new(
+                &mnemonic,
+                "",
+                &Password::from("wallet password"),
+            )
+            .unwrap(),
+            accounts: Vec::new(),
+            settings: Settings::new(),
+            address_book: Vec::new(),
+        };
+
+        assert!(matches!(
+            backup.write_to_file(Path::new("unused"), &Password::from("short")),
+            Err(BackupError::PasswordTooShort)
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use handles::wallet::ChangePasswordError;
 use store::{AppDataDb, DataBase, IconsDb};
 use thiserror::Error;
 use types::crypto::{Key, Password};
@@ -51,18 +52,32 @@ impl Wallet<Locked> {
         };
 
         let key = Key::<DataBase>::new(password.as_str(), &salt);
+        let network = self.wallet_data.settings.network;
 
-        let mut wallet = match handles::wallet::perform_login_check(
-            self.wallet_data.settings.network,
-            &password,
-        )
-        .await
+        if handles::wallet::perform_login_check(network, &password)
+            .await
+            .is_err()
         {
-            Ok(_) => Wallet {
-                state: Unlocked::new(key),
-                wallet_data: self.wallet_data,
-            },
-            Err(_) => return LoginResponse::Failed(self, LoginError::IncorrectPassword),
+            return LoginResponse::Failed(self, LoginError::IncorrectPassword);
+        }
+
+        // A failed upgrade is rolled back and tried again on the next login
+        let key = match handles::wallet::upgrade_key_derivation(network, password).await {
+            Ok(Some(upgraded_key)) => upgraded_key,
+            Ok(None) => key,
+            Err(ChangePasswordError::RollbackFailed(err)) => {
+                eprintln!("Failed to roll back the key derivation upgrade: {err}");
+                return LoginResponse::Failed(self, LoginError::Unrecoverable);
+            }
+            Err(err) => {
+                eprintln!("Failed to upgrade the key derivation: {err}");
+                key
+            }
+        };
+
+        let mut wallet = Wallet {
+            state: Unlocked::new(key),
+            wallet_data: self.wallet_data,
         };
 
         if self.state.is_initial_login {
@@ -96,10 +111,11 @@ impl Wallet<Locked> {
                 );
             };
 
+            let currency = wallet.wallet_data.settings.currency.clone();
             let resources = Arc::make_mut(&mut wallet.wallet_data.resource_data);
 
             resources
-                .load_resource_data_from_disk(app_data_db, icons_db)
+                .load_resource_data_from_disk(app_data_db, icons_db, currency)
                 .await
                 .inspect_err(|err| eprintln!("Failed to load resource data: {err}"))
                 .ok();
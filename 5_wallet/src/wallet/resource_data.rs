@@ -1,10 +1,10 @@
 use deps::*;
-use store::{AppDataDb, DbError, IconsDb};
+use store::{AccountsUpdateRows, AppDataDb, DbError, IconsDb};
 
 use std::collections::{BTreeSet, HashMap};
 
 use bytes::Bytes;
-use types::{address::{AccountAddress, ResourceAddress}, assets::{FungibleAsset, NonFungibleAsset}, debug_info, Account, Resource, UnwrapUnreachable};
+use types::{address::{AccountAddress, ResourceAddress}, assets::{FungibleAsset, NonFungibleAsset}, collections::{AccountAssets, AccountsUpdate}, debug_info, Account, Currency, PriceTable, Resource, Transaction, UnwrapUnreachable};
 
 #[derive(Debug, Clone)]
 pub struct ResourceData {
@@ -13,6 +13,8 @@ pub struct ResourceData {
     pub non_fungibles: HashMap<AccountAddress, BTreeSet<NonFungibleAsset>>,
     pub resources: HashMap<ResourceAddress, Resource>,
     pub resource_icons: HashMap<ResourceAddress, Bytes>,
+    pub pending_transactions: Vec<Transaction>,
+    pub prices: PriceTable,
 }
 
 
@@ -24,15 +26,20 @@ impl ResourceData {
             non_fungibles: HashMap::new(),
             resources: HashMap::new(),
             resource_icons: HashMap::new(),
+            pending_transactions: Vec::new(),
+            prices: PriceTable::default(),
         }
     }
 
-    pub async fn load_resource_data_from_disk(&mut self, app_data_db: &AppDataDb, icons_db: &IconsDb) -> Result<(), DbError> {
+    /// The prices are the ones saved for `currency`
+    pub async fn load_resource_data_from_disk(&mut self, app_data_db: &AppDataDb, icons_db: &IconsDb, currency: Currency) -> Result<(), DbError> {
         self.accounts = app_data_db.get_accounts().await?;
         self.fungibles = app_data_db.get_all_fungible_assets_per_account().await?;
         self.non_fungibles = app_data_db.get_all_non_fungible_assets_per_account().await?;
         self.resources = app_data_db.get_all_resources().await?;
         self.resource_icons = handles::store::get::resource_icons(&icons_db).await;
+        self.pending_transactions = app_data_db.get_pending_transactions().await?;
+        self.prices = app_data_db.get_price_table(currency).await?;
 
         Ok(())
     }
@@ -89,4 +96,68 @@ impl ResourceData {
         db.upsert_account(account).await
     }
 
+    /// The stored rows of `accounts` and their assets
+    pub fn account_rows(&self, accounts: &[AccountAddress]) -> AccountsUpdateRows {
+        let mut rows = AccountsUpdateRows::default();
+        for address in accounts {
+            if let Some(account) = self.accounts.get(address) {
+                rows.accounts.push(account.clone());
+            }
+            if let Some(fungibles) = self.fungibles.get(address) {
+                rows.fungibles.extend(fungibles.iter().map(|fungible| (address.clone(), fungible.clone())));
+            }
+            if let Some(non_fungibles) = self.non_fungibles.get(address) {
+                rows.non_fungibles.extend(non_fungibles.iter().map(|non_fungible| (address.clone(), non_fungible.clone())));
+            }
+        }
+        rows
+    }
+
+    /// The assets of every account, an account update is compared against them to find removed assets
+    pub fn assets_per_account(&self) -> HashMap<AccountAddress, AccountAssets> {
+        self.accounts.keys().map(|address| {
+            let assets = AccountAssets {
+                fungibles: self.fungibles.get(address).cloned().unwrap_or_default(),
+                non_fungibles: self.non_fungibles.get(address).cloned().unwrap_or_default(),
+            };
+            (address.clone(), assets)
+        })
+        .collect()
+    }
+
+    /// Merges the updated balances into the existing assets and removes the assets and ids the accounts no longer hold,
+    /// assets not included in the update are left as is.
+    /// Transactions are not kept in memory and have to be taken out of the update and saved separately
+    pub fn apply_accounts_update(&mut self, accounts_update: AccountsUpdate) {
+        for account_update in accounts_update.account_updates {
+            let address = account_update.account.address.clone();
+
+            let fungibles = self.fungibles.entry(address.clone()).or_default();
+            fungibles.retain(|fungible| !account_update.removed_fungibles.contains(&fungible.resource_address));
+            for fungible in account_update.fungibles.into_values() {
+                fungibles.replace(fungible);
+            }
+
+            let non_fungibles = self.non_fungibles.entry(address.clone()).or_default();
+            non_fungibles.retain(|non_fungible| !account_update.removed_non_fungibles.contains(&non_fungible.resource_address));
+            for non_fungible in account_update.non_fungibles.into_values() {
+                non_fungibles.replace(non_fungible);
+            }
+            for (resource_address, removed_nfids) in &account_update.removed_nfids {
+                let Some(mut non_fungible) = non_fungibles.iter().find(|non_fungible| &non_fungible.resource_address == resource_address).cloned() else {
+                    continue;
+                };
+                non_fungible.nfids.retain(|nft| !removed_nfids.contains(&nft.id));
+                non_fungibles.replace(non_fungible);
+            }
+
+            if let Some(account) = self.accounts.get_mut(&address) {
+                account.balances_last_updated = account_update.account.balances_last_updated;
+                account.transactions_last_updated = account_update.account.transactions_last_updated;
+            }
+        }
+
+        self.resources.extend(accounts_update.new_resources);
+    }
+
 }
\ No newline at end of file
@@ -1,14 +1,20 @@
 use deps::{tokio::task::JoinHandle, *};
-use handles::credentials::get_db_encryption_salt;
+use handles::{credentials::get_db_encryption_salt, wallet::ChangePasswordError};
 use store::DataBase;
 
-use std::collections::{BTreeSet, HashMap};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 use bytes::Bytes;
+use scrypto::crypto::Ed25519PublicKey;
 use types::{
-    Account, AppError, Resource,
-    address::{AccountAddress, ResourceAddress},
+    Account, AppError, BalanceSnapshot, Notification, PriceTable, Resource, Transaction,
+    TransactionStatus, Transfer,
+    address::{AccountAddress, ResourceAddress, TransactionAddress},
     assets::{FungibleAsset, NonFungibleAsset},
+    collections::AccountsUpdate,
     crypto::{Key, Password},
 };
 
@@ -61,6 +67,14 @@ impl Wallet<Unlocked> {
         &self.wallet_data.resource_data.resource_icons
     }
 
+    pub fn pending_transactions(&self) -> &[Transaction] {
+        &self.wallet_data.resource_data.pending_transactions
+    }
+
+    pub fn prices(&self) -> &PriceTable {
+        &self.wallet_data.resource_data.prices
+    }
+
     // pub fn accounts_mut(&mut self) -> &mut HashMap<AccountAddress, Account> {
     //     &mut self.wallet_data.resource_data.accounts
     // }
@@ -76,4 +90,101 @@ impl Wallet<Unlocked> {
             .wallet_data
             .create_new_account(account_name, password, key))
     }
+
+    /// Re-encrypts the mnemonic and the databases of every network with the new password,
+    /// returns the new database key to pass to [Wallet::apply_password_change]
+    pub fn change_password(
+        &self,
+        old_password: Password,
+        new_password: Password,
+    ) -> JoinHandle<Result<Key<DataBase>, AppError>> {
+        let network = self.wallet_data.settings.network;
+        tokio::spawn(async move {
+            handles::wallet::change_password(network, old_password, new_password)
+                .await
+                .map_err(|err| match err {
+                    ChangePasswordError::IncorrectPassword
+                    | ChangePasswordError::PasswordTooShort => {
+                        AppError::NonFatal(Notification::Info(err.to_string()))
+                    }
+                    ChangePasswordError::RollbackFailed(_) => AppError::Fatal(err.to_string()),
+                    _ => AppError::NonFatal(Notification::Warn(err.to_string())),
+                })
+        })
+    }
+
+    /// The databases are encrypted with the new key once the password is changed
+    pub fn apply_password_change(&mut self, key: Key<DataBase>) {
+        self.state.key = key;
+    }
+
+    /// The public keys of the accounts signing the transfer, used to include the signatures in the preview fee.
+    /// Signers that are not accounts of this wallet are skipped, submitting the transfer fails for them
+    pub fn signer_public_keys(&self, transfer: &Transfer) -> Vec<Ed25519PublicKey> {
+        transfer
+            .signers()
+            .into_iter()
+            .filter_map(|signer| self.wallet_data.resource_data.accounts.get(signer))
+            .map(|account| account.public_key)
+            .collect()
+    }
+
+    pub fn submit_transfer(
+        &self,
+        transfer: Transfer,
+        password: Password,
+    ) -> Result<JoinHandle<Result<Transaction, AppError>>, AppError> {
+        self.wallet_data.submit_transfer(transfer, password)
+    }
+
+    pub fn add_pending_transaction(&mut self, transaction: Transaction) {
+        Arc::make_mut(&mut self.wallet_data.resource_data)
+            .pending_transactions
+            .push(transaction);
+    }
+
+    pub fn poll_pending_transactions(
+        &self,
+    ) -> JoinHandle<Result<Vec<(TransactionAddress, TransactionStatus)>, AppError>> {
+        self.wallet_data.poll_pending_transactions()
+    }
+
+    pub fn remove_pending_transactions(
+        &mut self,
+        resolved: &[(TransactionAddress, TransactionStatus)],
+    ) {
+        self.wallet_data.remove_pending_transactions(resolved)
+    }
+
+    pub fn update_accounts(&self) -> JoinHandle<AccountsUpdate> {
+        self.wallet_data.update_accounts()
+    }
+
+    /// Applies the update to the wallet and saves the updated data to disk
+    pub fn apply_accounts_update(
+        &mut self,
+        accounts_update: AccountsUpdate,
+    ) -> JoinHandle<Result<(), AppError>> {
+        let key = self.state.key.clone();
+        self.wallet_data.apply_accounts_update(accounts_update, key)
+    }
+
+    /// The balance history of the account, ordered by state version
+    pub fn balance_snapshots(
+        &self,
+        account_address: AccountAddress,
+    ) -> JoinHandle<Result<Vec<BalanceSnapshot>, AppError>> {
+        let key = self.state.key.clone();
+        self.wallet_data.balance_snapshots(account_address, key)
+    }
+
+    /// `None` if no price source is set
+    pub fn update_prices(&self) -> Option<JoinHandle<Result<PriceTable, AppError>>> {
+        let key = self.state.key.clone();
+        self.wallet_data.update_prices(key)
+    }
+
+    pub fn apply_price_table(&mut self, price_table: PriceTable) {
+        self.wallet_data.apply_price_table(price_table)
+    }
 }
@@ -1,11 +1,21 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use deps::tokio::{self, task::JoinHandle};
-use store::{AppDataDb, DataBase, DbError, IconsDb};
+use handles::{prices::ConfiguredPriceSource, radix_dlt::gateway_requests::RadixGateway};
+use store::{AccountsUpdateRows, AppDataDb, DataBase, DbError, IconsDb};
 use types::{
-    Account, AppError, Network,
-    address::ResourceAddress,
-    crypto::{EncryptedMnemonicError, Key, Password},
+    Account, AppError, BalanceSnapshot, Network, Notification, PriceTable, Transaction,
+    TransactionStatus, Transfer,
+    address::{AccountAddress, Address, ResourceAddress, TransactionAddress},
+    collections::AccountsUpdate,
+    crypto::{
+        Bip32Entity, Bip32KeyKind, Ed25519KeyPair, EncryptedMnemonicError, Key, Password,
+        bip39::Mnemonic,
+    },
 };
 
 use crate::settings::Settings;
@@ -42,6 +52,34 @@ impl WalletData {
         self.resource_data.save_resource_data_to_disk(db).await
     }
 
+    pub async fn save_transactions_to_disk(
+        &self,
+        transactions: Vec<Transaction>,
+        key: Key<DataBase>,
+    ) -> Result<(), DbError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        let db = AppDataDb::get_or_init(self.settings.network, key).await?;
+        db.insert_transactions(transactions).await
+    }
+
+    pub(crate) fn balance_snapshots(
+        &self,
+        account_address: AccountAddress,
+        key: Key<DataBase>,
+    ) -> JoinHandle<Result<Vec<BalanceSnapshot>, AppError>> {
+        let network = self.settings.network;
+        tokio::spawn(async move {
+            let db = AppDataDb::get_or_init(network, key)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+            db.get_balance_snapshots_for_account(account_address)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+        })
+    }
+
     pub(crate) fn create_new_account(
         &mut self,
         account_name: String,
@@ -65,15 +103,7 @@ impl WalletData {
         let network = self.settings.network;
 
         tokio::spawn(async move {
-            let encrypted_mnemonic = handles::credentials::get_encrypted_mnemonic()?;
-            let (mnemonic, seed_password) = encrypted_mnemonic
-                .decrypt_mnemonic(&password)
-                .map_err(|err| match err {
-                    EncryptedMnemonicError::FailedToDecryptData => {
-                        AppError::NonFatal(types::Notification::Info("Wrong password".to_string()))
-                    }
-                    _ => AppError::Fatal(err.to_string()),
-                })?;
+            let (mnemonic, seed_password) = decrypt_mnemonic(&password)?;
 
             let account = create_account_from_mnemonic(
                 &mnemonic,
@@ -92,4 +122,324 @@ impl WalletData {
             Ok(account)
         })
     }
+
+    /// Derives the signing keys of every account that signs the transfer and submits it,
+    /// the submitted transaction is stored as pending and returned by the task
+    pub(crate) fn submit_transfer(
+        &self,
+        transfer: Transfer,
+        password: Password,
+    ) -> Result<JoinHandle<Result<Transaction, AppError>>, AppError> {
+        let account = self
+            .resource_data
+            .accounts
+            .get(&transfer.from_account)
+            .cloned()
+            .ok_or(AppError::NonFatal(Notification::Warn(
+                "Unknown sending account".to_string(),
+            )))?;
+        let signers = transfer
+            .signers()
+            .into_iter()
+            .filter(|signer| *signer != &transfer.from_account)
+            .map(|signer| {
+                self.resource_data
+                    .accounts
+                    .get(signer)
+                    .cloned()
+                    .ok_or(AppError::NonFatal(Notification::Warn(format!(
+                        "Account {} does not belong to this wallet",
+                        signer.truncate()
+                    ))))
+            })
+            .collect::<Result<Vec<Account>, AppError>>()?;
+        let network = self.settings.network;
+        let limits = self.settings.request_limits;
+
+        Ok(tokio::spawn(async move {
+            let (mnemonic, seed_password) = decrypt_mnemonic(&password)?;
+
+            let derive_key_pair = |account: &Account| {
+                let (key_pair, derivation_path) = Ed25519KeyPair::new(
+                    &mnemonic,
+                    Some(seed_password.as_str()),
+                    account.derivation_index(),
+                    network,
+                    Bip32Entity::Account,
+                    Bip32KeyKind::TransactionSigning,
+                );
+
+                if derivation_path != account.derivation_path() {
+                    return Err(AppError::NonFatal(Notification::Danger(
+                        "Derivation path does not match the account".to_string(),
+                    )));
+                }
+                Ok(key_pair)
+            };
+
+            let key_pair = derive_key_pair(&account)?;
+            let signer_key_pairs = signers
+                .iter()
+                .map(derive_key_pair)
+                .collect::<Result<Vec<Ed25519KeyPair>, AppError>>()?;
+
+            let from_account = transfer.from_account.clone();
+            let message = transfer.message.clone();
+
+            let intent_hash = handles::radix_dlt::transfer::submit_transfer(
+                RadixGateway::new(network, limits),
+                network,
+                transfer,
+                key_pair,
+                signer_key_pairs,
+            )
+            .await
+            .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+
+            let transaction = Transaction::pending(&from_account, intent_hash, message);
+
+            let db =
+                AppDataDb::get(network).ok_or(AppError::Fatal("Database not found".to_string()))?;
+            db.upsert_transactions(vec![transaction.clone()])
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Info(err.to_string())))?;
+
+            Ok(transaction)
+        }))
+    }
+
+    /// Returns the transactions that are no longer pending together with their new status
+    pub(crate) fn poll_pending_transactions(
+        &self,
+    ) -> JoinHandle<Result<Vec<(TransactionAddress, TransactionStatus)>, AppError>> {
+        let network = self.settings.network;
+        let limits = self.settings.request_limits;
+        let transactions = self
+            .resource_data
+            .pending_transactions
+            .iter()
+            .map(|transaction| transaction.transaction_address.clone())
+            .collect();
+
+        tokio::spawn(async move {
+            handles::radix_dlt::transaction_status::poll_pending_transactions(
+                RadixGateway::new(network, limits),
+                network,
+                transactions,
+            )
+            .await
+        })
+    }
+
+    pub(crate) fn remove_pending_transactions(
+        &mut self,
+        resolved: &[(TransactionAddress, TransactionStatus)],
+    ) {
+        Arc::make_mut(&mut self.resource_data)
+            .pending_transactions
+            .retain(|transaction| {
+                !resolved
+                    .iter()
+                    .any(|(address, _)| address == &transaction.transaction_address)
+            });
+    }
+
+    /// Fetches the balances of all accounts that have changed since the last update
+    pub(crate) fn update_accounts(&self) -> JoinHandle<AccountsUpdate> {
+        let network = self.settings.network;
+        let limits = self.settings.request_limits;
+        let resources = Arc::new(self.resource_data.resources.clone());
+        let stored_assets = Arc::new(self.resource_data.assets_per_account());
+        let accounts = self.resource_data.accounts.values().cloned().collect();
+
+        tokio::spawn(async move {
+            let gateway = RadixGateway::new(network, limits);
+            handles::radix_dlt::updates::update_accounts(
+                gateway,
+                network,
+                resources,
+                stored_assets,
+                accounts,
+            )
+            .await
+        })
+    }
+
+    /// Fetches the prices of the fungibles held by the accounts and saves them,
+    /// `None` if no price source is set
+    pub(crate) fn update_prices(
+        &self,
+        key: Key<DataBase>,
+    ) -> Option<JoinHandle<Result<PriceTable, AppError>>> {
+        let source = ConfiguredPriceSource::from(self.settings.price_source.as_ref()?);
+        let currency = self.settings.currency.clone();
+        let network = self.settings.network;
+        let resource_addresses = self
+            .resource_data
+            .fungibles
+            .values()
+            .flatten()
+            .map(|fungible| fungible.resource_address.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        Some(tokio::spawn(async move {
+            let price_table = handles::prices::update_prices(source, currency, &resource_addresses)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+
+            let db = AppDataDb::get_or_init(network, key)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+            db.upsert_prices(price_table.clone())
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+
+            Ok(price_table)
+        }))
+    }
+
+    /// Prices missing from the update are kept, the same way they are kept on disk
+    pub(crate) fn apply_price_table(&mut self, price_table: PriceTable) {
+        let prices = &mut Arc::make_mut(&mut self.resource_data).prices;
+        if prices.currency == price_table.currency {
+            prices.prices.extend(price_table.prices);
+            prices.updated_at = price_table.updated_at;
+        } else {
+            *prices = price_table;
+        }
+    }
+
+    /// The transactions, the removed assets, the updated accounts and their balance snapshots
+    /// are saved in one database transaction, so the transaction cursor of an account never
+    /// moves past transactions that are not stored
+    pub(crate) fn apply_accounts_update(
+        &mut self,
+        mut accounts_update: AccountsUpdate,
+        key: Key<DataBase>,
+    ) -> JoinHandle<Result<(), AppError>> {
+        let transactions = take_transactions(&mut accounts_update);
+        let removed_assets = RemovedAssets::from(&accounts_update);
+        let snapshots = self.balance_snapshots_of_update(&accounts_update);
+        let resources = accounts_update.new_resources.values().cloned().collect();
+        let updated_accounts = accounts_update
+            .account_updates
+            .iter()
+            .map(|account_update| account_update.account.address.clone())
+            .collect::<Vec<_>>();
+        Arc::make_mut(&mut self.resource_data).apply_accounts_update(accounts_update);
+
+        let rows = AccountsUpdateRows {
+            transactions,
+            removed_fungibles: removed_assets.fungibles,
+            removed_non_fungibles: removed_assets.non_fungibles,
+            resources,
+            snapshots,
+            ..self.resource_data.account_rows(&updated_accounts)
+        };
+
+        let network = self.settings.network;
+        tokio::spawn(async move {
+            let db = AppDataDb::get_or_init(network, key)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))?;
+            db.save_accounts_update(rows)
+                .await
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+        })
+    }
+
+    /// Snapshots of every balance an account holds after the update, for each account whose
+    /// balances moved past its stored state version
+    fn balance_snapshots_of_update(
+        &self,
+        accounts_update: &AccountsUpdate,
+    ) -> Vec<BalanceSnapshot> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+
+        accounts_update
+            .account_updates
+            .iter()
+            .flat_map(|account_update| {
+                let address = &account_update.account.address;
+                let previous_state_version = self
+                    .resource_data
+                    .accounts
+                    .get(address)
+                    .and_then(|account| account.balances_last_updated);
+                let unchanged = self
+                    .resource_data
+                    .fungibles
+                    .get(address)
+                    .into_iter()
+                    .flatten()
+                    .filter(|fungible| {
+                        !account_update
+                            .fungibles
+                            .contains_key(&fungible.resource_address)
+                            && !account_update
+                                .removed_fungibles
+                                .contains(&fungible.resource_address)
+                    });
+                BalanceSnapshot::from_account_update(
+                    account_update,
+                    unchanged,
+                    previous_state_version,
+                    timestamp,
+                )
+            })
+            .collect()
+    }
+}
+
+/// The assets removed from the accounts in an update, as `(account, resource)` pairs
+#[derive(Debug, Default)]
+pub struct RemovedAssets {
+    pub fungibles: Vec<(AccountAddress, ResourceAddress)>,
+    pub non_fungibles: Vec<(AccountAddress, ResourceAddress)>,
+}
+
+impl From<&AccountsUpdate> for RemovedAssets {
+    fn from(accounts_update: &AccountsUpdate) -> Self {
+        let mut removed_assets = Self::default();
+        for account_update in &accounts_update.account_updates {
+            let address = &account_update.account.address;
+            removed_assets.fungibles.extend(
+                account_update
+                    .removed_fungibles
+                    .iter()
+                    .map(|resource_address| (address.clone(), resource_address.clone())),
+            );
+            removed_assets.non_fungibles.extend(
+                account_update
+                    .removed_non_fungibles
+                    .iter()
+                    .map(|resource_address| (address.clone(), resource_address.clone())),
+            );
+        }
+        removed_assets
+    }
+}
+
+pub(crate) fn take_transactions(accounts_update: &mut AccountsUpdate) -> Vec<Transaction> {
+    accounts_update
+        .account_updates
+        .iter_mut()
+        .flat_map(|account_update| std::mem::take(&mut account_update.transactions))
+        .collect()
+}
+
+fn decrypt_mnemonic(password: &Password) -> Result<(Mnemonic, Password), AppError> {
+    let encrypted_mnemonic = handles::credentials::get_encrypted_mnemonic()?;
+    encrypted_mnemonic
+        .decrypt_mnemonic(password)
+        .map_err(|err| match err {
+            EncryptedMnemonicError::FailedToDecryptData => {
+                AppError::NonFatal(Notification::Info("Wrong password".to_string()))
+            }
+            _ => AppError::Fatal(err.to_string()),
+        })
 }
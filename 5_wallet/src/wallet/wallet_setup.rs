@@ -1,5 +1,6 @@
 pub mod task_manager;
 pub mod task_runner;
+pub mod radix_wallet_profile;
 pub mod setup;
 pub mod setup_error;
 
@@ -19,7 +20,7 @@ impl Wallet<Setup> {
     pub fn set_seed_phrase_and_password(&mut self, seed_phrase: Phrase, seed_password: Option<Password>) -> Result<(), SetupError> {
         let mnemonic = Mnemonic::from_phrase(seed_phrase.as_str(), Language::English)?;
 
-        self.state.set_mnemonic_and_password(mnemonic, seed_password);
+        self.state.set_mnemonic_and_password(mnemonic, seed_password, self.settings().request_limits());
         Ok(())
     }
 
@@ -39,6 +40,10 @@ impl Wallet<Setup> {
         self.state.accounts = accounts;
     }
 
+    pub fn set_other_network_accounts(&mut self, accounts: Vec<Account>) {
+        self.state.other_network_accounts = accounts;
+    }
+
     pub fn task_manager(&self) -> Arc<TaskManager> {
         self.state.setup_tasks.clone()
     }
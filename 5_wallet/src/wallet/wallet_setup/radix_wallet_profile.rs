@@ -0,0 +1,577 @@
+use deps::{tokio::task::JoinHandle, *};
+
+use std::path::{Path, PathBuf};
+
+use ring::{
+    aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    hkdf::{self, HKDF_SHA256},
+};
+use serde::Deserialize;
+use thiserror::Error;
+use types::{
+    Account, AppError, Network, Notification,
+    address::{AccountAddress, Address},
+    crypto::{Bip32Entity, Bip32KeyKind, Ed25519KeyPair, Password, bip39::Mnemonic},
+};
+use zeroize::Zeroize;
+
+use super::setup::Setup;
+use crate::wallet::Wallet;
+
+#[derive(Debug, Error)]
+pub enum ProfileImportError {
+    #[error("Unable to read the profile file\n ∟{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a Radix wallet profile\n ∟{0}")]
+    InvalidProfile(#[from] serde_json::Error),
+    #[error("Unsupported profile encryption, version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Invalid encrypted profile")]
+    InvalidEncryptedProfile,
+    #[error("Wrong profile password")]
+    IncorrectPassword,
+    #[error("The recovery phrase does not belong to any account of the profile on {0}")]
+    NoMatchingAccounts(String),
+    #[error("No recovery phrase provided")]
+    NoMnemonicProvided,
+}
+
+impl From<ProfileImportError> for AppError {
+    fn from(err: ProfileImportError) -> Self {
+        AppError::NonFatal(Notification::Info(err.to_string()))
+    }
+}
+
+/// The accounts of a Radix wallet profile that the recovery phrase controls
+#[derive(Debug, Clone)]
+pub struct ProfileImport {
+    /// Accounts of the network being set up
+    pub accounts: Vec<Account>,
+    /// Accounts of the other networks known to the wallet, restored in the database of their network
+    pub other_network_accounts: Vec<Account>,
+    /// Accounts that belong to another recovery phrase or to a Ledger device
+    pub unmatched: usize,
+    /// Olympia accounts, which are controlled by a secp256k1 key and can not be imported
+    pub olympia: usize,
+    /// Accounts on networks that are not known to the wallet
+    pub unknown_networks: usize,
+}
+
+/// A profile backup as exported by the Radix wallet, the profile is encrypted with AES-256-GCM
+/// using a key derived from the password with HKDF-SHA256, without salt or info
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedProfileSnapshot {
+    /// Hex encoded nonce, ciphertext and tag
+    encrypted_snapshot: String,
+    key_derivation_scheme: SchemeVersion,
+    encryption_scheme: SchemeVersion,
+}
+
+#[derive(Deserialize)]
+struct SchemeVersion {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProfileFile {
+    Encrypted(EncryptedProfileSnapshot),
+    Plain(Profile),
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    networks: Vec<ProfileNetwork>,
+}
+
+#[derive(Deserialize)]
+struct ProfileNetwork {
+    #[serde(rename = "networkID")]
+    network_id: u8,
+    #[serde(default)]
+    accounts: Vec<ProfileAccount>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileAccount {
+    address: String,
+    display_name: String,
+    security_state: SecurityState,
+    #[serde(default)]
+    flags: Vec<String>,
+}
+
+impl ProfileAccount {
+    fn signing_key(&self) -> Option<&HierarchicalDeterministicPublicKey> {
+        self.security_state
+            .unsecured_entity_control
+            .as_ref()
+            .map(|control| control.transaction_signing.key())
+    }
+
+    /// Accounts imported from Olympia are controlled by a secp256k1 key
+    fn is_olympia(&self) -> bool {
+        self.signing_key()
+            .is_some_and(|key| key.public_key.curve == "secp256k1")
+    }
+
+    /// Accounts hidden or deleted in the Radix wallet are imported as hidden
+    fn is_hidden(&self) -> bool {
+        self.flags.iter().any(|flag| {
+            matches!(
+                flag.as_str(),
+                "deletedByUser" | "hiddenByUser" | "tombstonedByUser"
+            )
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecurityState {
+    /// Only set for accounts controlled by a single key
+    unsecured_entity_control: Option<UnsecuredEntityControl>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnsecuredEntityControl {
+    transaction_signing: FactorInstance,
+}
+
+/// Older profiles wrap the key in a badge
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FactorInstance {
+    Badge { badge: Badge },
+    Key(HierarchicalDeterministicPublicKey),
+}
+
+impl FactorInstance {
+    fn key(&self) -> &HierarchicalDeterministicPublicKey {
+        match self {
+            Self::Badge { badge } => &badge.virtual_source.hierarchical_deterministic_public_key,
+            Self::Key(key) => key,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Badge {
+    virtual_source: VirtualSource,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VirtualSource {
+    hierarchical_deterministic_public_key: HierarchicalDeterministicPublicKey,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HierarchicalDeterministicPublicKey {
+    public_key: ProfilePublicKey,
+    derivation_path: ProfileDerivationPath,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfilePublicKey {
+    curve: String,
+    /// Hex encoded
+    compressed_data: String,
+}
+
+#[derive(Deserialize)]
+struct ProfileDerivationPath {
+    /// Like `m/44H/1022H/1H/525H/1460H/0H`
+    path: String,
+}
+
+impl ProfileDerivationPath {
+    /// The indexes without the hardened marker, `None` if the path is not a CAP-26 path
+    fn indexes(&self) -> Option<[u32; 6]> {
+        let mut components = self.path.strip_prefix("m/")?.split('/');
+        let mut indexes = [0u32; 6];
+        for index in indexes.iter_mut() {
+            *index = components
+                .next()?
+                .trim_end_matches(['H', 'S', '\''])
+                .parse()
+                .ok()?;
+        }
+        components.next().is_none().then_some(indexes)
+    }
+}
+
+/// Reads a profile backup exported by the Radix wallet, `password` is only used if the
+/// profile is encrypted
+fn read_profile(path: &Path, password: &Password) -> Result<Profile, ProfileImportError> {
+    match serde_json::from_slice(&std::fs::read(path)?)? {
+        ProfileFile::Plain(profile) => Ok(profile),
+        ProfileFile::Encrypted(snapshot) => {
+            let mut decrypted = decrypt_snapshot(&snapshot, password)?;
+            let profile = serde_json::from_slice(&decrypted);
+            decrypted.zeroize();
+            Ok(profile?)
+        }
+    }
+}
+
+fn decrypt_snapshot(
+    snapshot: &EncryptedProfileSnapshot,
+    password: &Password,
+) -> Result<Vec<u8>, ProfileImportError> {
+    for version in [
+        snapshot.key_derivation_scheme.version,
+        snapshot.encryption_scheme.version,
+    ] {
+        if version != 1 {
+            return Err(ProfileImportError::UnsupportedVersion(version));
+        }
+    }
+
+    let sealed = decode_hex(&snapshot.encrypted_snapshot)
+        .filter(|sealed| sealed.len() > NONCE_LEN)
+        .ok_or(ProfileImportError::InvalidEncryptedProfile)?;
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| ProfileImportError::InvalidEncryptedProfile)?;
+
+    let mut key = [0u8; 32];
+    hkdf::Salt::new(HKDF_SHA256, &[])
+        .extract(password.as_str().as_bytes())
+        .expand(&[], HKDF_SHA256)
+        .and_then(|okm| okm.fill(&mut key))
+        .map_err(|_| ProfileImportError::InvalidEncryptedProfile)?;
+    let unbound_key = UnboundKey::new(&AES_256_GCM, &key);
+    key.zeroize();
+    let key =
+        LessSafeKey::new(unbound_key.map_err(|_| ProfileImportError::InvalidEncryptedProfile)?);
+
+    let mut plaintext = ciphertext.to_vec();
+    let opened = key
+        .open_in_place(nonce, Aad::empty(), &mut plaintext)
+        .map(|opened| opened.len());
+
+    match opened {
+        Ok(len) => {
+            plaintext.truncate(len);
+            Ok(plaintext)
+        }
+        Err(_) => {
+            plaintext.zeroize();
+            Err(ProfileImportError::IncorrectPassword)
+        }
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Keeps the accounts whose public key and address are reproduced by the recovery phrase at
+/// the derivation path of the profile, the accounts of `network` are kept apart from the accounts
+/// of the other networks
+fn accounts_from_profile(
+    profile: Profile,
+    mnemonic: &Mnemonic,
+    seed_password: Option<&str>,
+    network: Network,
+) -> ProfileImport {
+    let mut import = ProfileImport {
+        accounts: Vec::new(),
+        other_network_accounts: Vec::new(),
+        unmatched: 0,
+        olympia: 0,
+        unknown_networks: 0,
+    };
+
+    for profile_network in profile.networks {
+        let Ok(account_network) = Network::from_id(profile_network.network_id as u32) else {
+            import.unknown_networks += profile_network.accounts.len();
+            continue;
+        };
+
+        for profile_account in profile_network.accounts {
+            if profile_account.is_olympia() {
+                import.olympia += 1;
+                continue;
+            }
+            let Some(mut account) =
+                account_from_profile(&profile_account, mnemonic, seed_password, account_network)
+            else {
+                import.unmatched += 1;
+                continue;
+            };
+
+            let accounts = if account_network == network {
+                &mut import.accounts
+            } else {
+                &mut import.other_network_accounts
+            };
+            account.id = accounts
+                .iter()
+                .filter(|stored| stored.network == account_network)
+                .count();
+            accounts.push(account);
+        }
+    }
+
+    import
+}
+
+fn account_from_profile(
+    profile_account: &ProfileAccount,
+    mnemonic: &Mnemonic,
+    seed_password: Option<&str>,
+    network: Network,
+) -> Option<Account> {
+    let key = profile_account.signing_key()?;
+    if key.public_key.curve != "curve25519" {
+        return None;
+    }
+    let profile_path = key.derivation_path.indexes()?;
+    let profile_public_key = decode_hex(&key.public_key.compressed_data)?;
+
+    let (keypair, path) = Ed25519KeyPair::new(
+        mnemonic,
+        seed_password,
+        profile_path[5],
+        network,
+        Bip32Entity::Account,
+        Bip32KeyKind::TransactionSigning,
+    );
+    let public_key = keypair.radixdlt_public_key();
+    let address = keypair.bech32_address();
+
+    if path != profile_path
+        || public_key.0.as_slice() != profile_public_key.as_slice()
+        || address != profile_account.address
+    {
+        return None;
+    }
+
+    let mut account = Account::new(
+        0,
+        profile_account.display_name.clone(),
+        network,
+        path,
+        AccountAddress::from_str_on(network, &address).ok()?,
+        public_key,
+    );
+    account.hidden = profile_account.is_hidden();
+    Some(account)
+}
+
+impl Wallet<Setup> {
+    /// Reads the accounts of a profile backup from the Radix wallet that the entered recovery
+    /// phrase controls, they replace the accounts to restore
+    pub fn import_radix_wallet_profile(
+        &self,
+        path: PathBuf,
+        profile_password: Password,
+    ) -> JoinHandle<Result<ProfileImport, AppError>> {
+        let network = self.state.network;
+        let mnemonic_with_password = self
+            .state
+            .mnemonic_with_password
+            .as_ref()
+            .map(|(mnemonic, seed_password, _)| (mnemonic.clone(), seed_password.clone()));
+
+        tokio::task::spawn_blocking(move || {
+            let (mnemonic, seed_password) =
+                mnemonic_with_password.ok_or(ProfileImportError::NoMnemonicProvided)?;
+            let profile = read_profile(&path, &profile_password)?;
+
+            let import = accounts_from_profile(
+                profile,
+                &mnemonic,
+                seed_password.as_ref().map(|password| password.as_str()),
+                network,
+            );
+            if import.accounts.is_empty() {
+                return Err(ProfileImportError::NoMatchingAccounts(network.name()).into());
+            }
+            Ok(import)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ring::rand::{SecureRandom, SystemRandom};
+    use types::crypto::bip39::{Language, MnemonicType};
+
+    fn account_json(mnemonic: &Mnemonic, index: u32, network: Network, curve: &str) -> String {
+        let (keypair, _) = Ed25519KeyPair::new(
+            mnemonic,
+            None,
+            index,
+            network,
+            Bip32Entity::Account,
+            Bip32KeyKind::TransactionSigning,
+        );
+        let public_key = keypair
+            .radixdlt_public_key()
+            .0
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        format!(
+            r#"{{
+                "networkID": {},
+                "address": "{}",
+                "displayName": "Savings",
+                "flags": ["deletedByUser"],
+                "securityState": {{
+                    "discriminator": "unsecured",
+                    "unsecuredEntityControl": {{"transactionSigning": {{"badge": {{
+                        "discriminator": "virtualSource",
+                        "virtualSource": {{
+                            "discriminator": "hierarchicalDeterministicPublicKey",
+                            "hierarchicalDeterministicPublicKey": {{
+                                "publicKey": {{"curve": "{}", "compressedData": "{}"}},
+                                "derivationPath": {{
+                                    "scheme": "cap26",
+                                    "path": "m/44H/1022H/{}H/525H/1460H/{}H"
+                                }}
+                            }}
+                        }}
+                    }}}}}}
+                }}
+            }}"#,
+            network.id(),
+            keypair.bech32_address(),
+            curve,
+            public_key,
+            network.id(),
+            index
+        )
+    }
+
+    fn profile_json(mnemonic: &Mnemonic, index: u32) -> String {
+        format!(
+            r#"{{"header": {{}}, "networks": [{{"networkID": 1, "accounts": [{}]}}, {{"networkID": 2, "accounts": []}}]}}"#,
+            account_json(mnemonic, index, Network::Mainnet, "curve25519")
+        )
+    }
+
+    fn encrypt_snapshot(profile: &str, password: &str) -> String {
+        let mut key = [0u8; 32];
+        hkdf::Salt::new(HKDF_SHA256, &[])
+            .extract(password.as_bytes())
+            .expand(&[], HKDF_SHA256)
+            .unwrap()
+            .fill(&mut key)
+            .unwrap();
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).unwrap();
+
+        let mut sealed = profile.as_bytes().to_vec();
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).unwrap())
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .unwrap();
+        let combined = [nonce.as_slice(), &sealed].concat();
+
+        format!(
+            r#"{{
+                "encryptedSnapshot": "{}",
+                "version": 1,
+                "keyDerivationScheme": {{"version": 1, "description": "HKDFSHA256"}},
+                "encryptionScheme": {{"version": 1, "description": "AESGCM-256"}}
+            }}"#,
+            combined
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>()
+        )
+    }
+
+    #[test]
+    fn test_profile_accounts_are_verified_against_the_mnemonic() {
+        let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let other_mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let profile: Profile = serde_json::from_str(&profile_json(&mnemonic, 3)).unwrap();
+
+        let import = accounts_from_profile(profile, &mnemonic, None, Network::Mainnet);
+        assert_eq!(import.accounts.len(), 1);
+        assert_eq!(import.unmatched, 0);
+        let account = &import.accounts[0];
+        assert_eq!(account.name, "Savings");
+        assert_eq!(account.derivation_index(), 3);
+        assert!(account.hidden);
+
+        let profile: Profile = serde_json::from_str(&profile_json(&mnemonic, 3)).unwrap();
+        let import = accounts_from_profile(profile, &other_mnemonic, None, Network::Mainnet);
+        assert!(import.accounts.is_empty());
+        assert_eq!(import.unmatched, 1);
+    }
+
+    #[test]
+    fn test_profile_accounts_of_other_networks_are_kept_apart() {
+        let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let profile = format!(
+            r#"{{"networks": [
+                {{"networkID": 1, "accounts": [{}, {}]}},
+                {{"networkID": 2, "accounts": [{}, {}]}},
+                {{"networkID": 240, "accounts": [{}]}}
+            ]}}"#,
+            account_json(&mnemonic, 0, Network::Mainnet, "curve25519"),
+            account_json(&mnemonic, 1, Network::Mainnet, "secp256k1"),
+            account_json(&mnemonic, 0, Network::Stokenet, "curve25519"),
+            account_json(&mnemonic, 1, Network::Stokenet, "curve25519"),
+            account_json(&mnemonic, 2, Network::Stokenet, "curve25519"),
+        );
+        let profile: Profile = serde_json::from_str(&profile).unwrap();
+
+        let import = accounts_from_profile(profile, &mnemonic, None, Network::Mainnet);
+        assert_eq!(import.accounts.len(), 1);
+        assert_eq!(import.olympia, 1);
+        assert_eq!(import.unmatched, 0);
+        assert_eq!(import.unknown_networks, 1);
+        assert_eq!(import.other_network_accounts.len(), 2);
+        for (id, account) in import.other_network_accounts.iter().enumerate() {
+            assert_eq!(account.network, Network::Stokenet);
+            assert_eq!(account.id, id);
+            assert_eq!(account.derivation_index(), id as u32);
+        }
+    }
+
+    #[test]
+    fn test_read_encrypted_profile() {
+        let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+        let path = std::env::temp_dir().join(format!("radix_profile_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            encrypt_snapshot(&profile_json(&mnemonic, 0), "profile password"),
+        )
+        .unwrap();
+
+        let wrong_password = read_profile(&path, &Password::from("wrong password"));
+        let profile = read_profile(&path, &Password::from("profile password"));
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(
+            wrong_password,
+            Err(ProfileImportError::IncorrectPassword)
+        ));
+        assert_eq!(profile.unwrap().networks[0].accounts.len(), 1);
+    }
+}
@@ -1,11 +1,11 @@
 use deps::{debug_print::debug_println, *};
 use store::{AppDataDb, DataBase, IconsDb};
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::{BTreeSet, HashMap}, sync::Arc};
 
-use types::{crypto::Key, address::ResourceAddress, collections::AccountsUpdate, crypto::{bip39::{Language, Mnemonic, MnemonicType}, EncryptedMnemonic, HashedPassword, KeySaltPair, Password}, Account, AppError, AppPath, Network, UnwrapUnreachable};
+use types::{crypto::Key, address::ResourceAddress, collections::AccountsUpdate, crypto::{bip39::{Language, Mnemonic, MnemonicType}, EncryptedMnemonic, HashedPassword, KeySaltPair, Password}, Account, AppError, AppPath, Network, RequestLimits, UnwrapUnreachable};
 
-use crate::{settings::Settings, wallet::{create_account_from_mnemonic, resource_data::ResourceData, WalletState}, wallet_encryption_keys::WalletEncryptionKeys, Unlocked, Wallet, WalletData};
+use crate::{settings::Settings, wallet::{create_account_from_mnemonic, resource_data::ResourceData, wallet_data::take_transactions, WalletState}, wallet_encryption_keys::WalletEncryptionKeys, Unlocked, Wallet, WalletData};
 
 use super::{setup_error::SetupError, task_manager::TaskManager};
 
@@ -15,6 +15,8 @@ pub struct Setup {
     pub mnemonic_with_password: Option<(Mnemonic, Option<Password>, u16)>,
     pub password: Option<(Password, u16)>,
     pub accounts: Vec<Account>,
+    /// Imported accounts of other networks, they are stored in the database of their network
+    pub other_network_accounts: Vec<Account>,
     pub setup_tasks: Arc<TaskManager>,
 }
 
@@ -25,6 +27,7 @@ impl Setup {
             mnemonic_with_password: None,
             password: None,
             accounts: Vec::new(),
+            other_network_accounts: Vec::new(),
             setup_tasks: Arc::new(TaskManager::new()),
         }
     }
@@ -34,6 +37,7 @@ impl Setup {
         self.mnemonic_with_password = None;
         self.password = None;
         self.accounts.clear();
+        self.other_network_accounts.clear();
         self.setup_tasks = Arc::new(TaskManager::new());
     }
 
@@ -67,7 +71,8 @@ impl Setup {
         });
     }
 
-    pub fn set_mnemonic_and_password(&mut self, new_mnemonic: Mnemonic, new_seed_password: Option<Password>) {
+    /// The accounts are fetched from the gateway with `request_limits`
+    pub fn set_mnemonic_and_password(&mut self, new_mnemonic: Mnemonic, new_seed_password: Option<Password>, request_limits: RequestLimits) {
         match &mut self.mnemonic_with_password {
             Some((mnemonic, seed_password, id)) => {
                 if new_mnemonic.phrase() == mnemonic.phrase() && new_seed_password.as_ref() == seed_password.as_ref() {
@@ -91,7 +96,7 @@ impl Setup {
         let seed_password = seed_password.clone();
         let id = *id;
         tokio::spawn(async move {
-            task_manager.run_task_create_and_update_accounts(id, mnemonic, seed_password, network).await;
+            task_manager.run_task_create_and_update_accounts(id, mnemonic, seed_password, network, request_limits).await;
         });
     }
 
@@ -157,18 +162,25 @@ impl Setup {
             self.get_seed_password(),
             wallet_keys.db_key_salt,
             wallet_keys.mnemonic_key_salt,
-            password_hash,
+            password_hash.clone(),
             &self.accounts,
             settings.network,
         )
         .await
         .map_err(|_| SetupError::Unspecified)?;
+
+        store_accounts_of_other_networks(&self.other_network_accounts, settings.network, &db_key, &password_hash)
+            .await
+            .map_err(|_| SetupError::Unspecified)?;
         
         let mut wallet_data = WalletData::new(settings);
 
+        let mut accounts_update = self.get_updated_accounts().await?;
+        let transactions = take_transactions(&mut accounts_update);
+
         save_updated_accounts_to_resource_data(
             std::mem::take(&mut self.accounts), 
-            self.get_updated_accounts().await?, 
+            accounts_update, 
             Arc::make_mut(&mut wallet_data.resource_data)
         );
 
@@ -179,6 +191,7 @@ impl Setup {
             wallet_data.settings.network
         ).await?;
 
+        wallet_data.save_transactions_to_disk(transactions, db_key.clone()).await?;
         wallet_data.save_resource_data_to_disk(db_key.clone()).await?;
 
         debug_println!("Saved {} icons to disk", wallet_data.resource_data.resource_icons.len());
@@ -224,10 +237,46 @@ pub async fn create_new_wallet_with_accounts(
     Ok(())
 }
 
+/// Creates the database of every network of `accounts` other than `network` and stores the accounts of that network in it
+async fn store_accounts_of_other_networks(
+    accounts: &[Account],
+    network: Network,
+    db_key: &Key<DataBase>,
+    password_hash: &HashedPassword,
+) -> Result<(), AppError> {
+    let networks = accounts.iter()
+        .map(|account| account.network)
+        .filter(|account_network| *account_network != network)
+        .collect::<BTreeSet<_>>();
+
+    for account_network in networks {
+        let db = AppDataDb::load_from_path(&AppPath::get().db_path(account_network), db_key.clone())
+            .await
+            .map_err(|err| AppError::Fatal(err.to_string()))?;
+
+        db.upsert_password_hash(password_hash.clone()).await
+            .map_err(|err| AppError::Fatal(err.to_string()))?;
+        db.upsert_accounts(
+            accounts.iter()
+                .filter(|account| account.network == account_network)
+                .cloned()
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .map_err(|err| AppError::Fatal(err.to_string()))?;
+    }
+
+    Ok(())
+}
+
 fn save_updated_accounts_to_resource_data(accounts: Vec<Account>, mut accounts_update: AccountsUpdate, resource_data: &mut ResourceData) {
     for mut account in accounts {
         let Some(account_update) = accounts_update.account_updates.iter_mut()
-            .find(|account_update|&account_update.account.address == &account.address) else {continue};
+            .find(|account_update|&account_update.account.address == &account.address) else {
+                // Imported accounts can be beyond the scanned indexes, they are synced after login
+                resource_data.accounts.insert(account.address.clone(), account);
+                continue
+            };
 
         let fungibles = std::mem::take(&mut account_update.fungibles);
         let fungibles = fungibles.into_values().collect();
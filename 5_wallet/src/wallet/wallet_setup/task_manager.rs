@@ -3,7 +3,8 @@ use deps::{debug_print::debug_println, *};
 use std::{collections::{BTreeMap, HashMap}, fmt::Debug, sync::Arc};
 
 use debug_print::debug_eprintln;
-use types::{address::ResourceAddress, collections::AccountsUpdate, crypto::{bip39::Mnemonic, Password}, Account, AccountSummary, Network};
+use handles::radix_dlt::gateway_requests::RadixGateway;
+use types::{address::ResourceAddress, collections::AccountsUpdate, crypto::{bip39::Mnemonic, Password}, Account, AccountSummary, Network, RequestLimits};
 
 use crate::{wallet::create_multiple_accounts_from_mnemonic, wallet_encryption_keys::WalletEncryptionKeys, SetupError};
 
@@ -52,10 +53,10 @@ impl TaskManager {
         self.wallet_keys_and_salt.run_task(task_id,  move||Self::create_encryption_keys(password)).await;
     }
 
-    pub async fn run_task_create_and_update_accounts(&self, task_id: u16, mnemonic: Mnemonic, seed_password: Option<Password>, network: Network) {
+    pub async fn run_task_create_and_update_accounts(&self, task_id: u16, mnemonic: Mnemonic, seed_password: Option<Password>, network: Network, request_limits: RequestLimits) {
         let accounts = Self::create_accounts(mnemonic, seed_password, network).await;
 
-        self.accounts_update.run_task(task_id, move || Self::update_accounts(accounts, network)).await;
+        self.accounts_update.run_task(task_id, move || Self::update_accounts(accounts, network, request_limits)).await;
         let accounts_update = self.accounts_update.get_result().await.unwrap_or(AccountsUpdate::new(network));
         let icon_urls = accounts_update.icon_urls.clone();
 
@@ -99,10 +100,12 @@ impl TaskManager {
         )
     }
 
-    async fn update_accounts(accounts: Vec<Account>, network: Network) -> Result<AccountsUpdate, SetupError> {
+    async fn update_accounts(accounts: Vec<Account>, network: Network, request_limits: RequestLimits) -> Result<AccountsUpdate, SetupError> {
         Ok(handles::radix_dlt::updates::update_accounts(
+            RadixGateway::new(network, request_limits),
             network,
             Arc::new(HashMap::new()),
+            Arc::new(HashMap::new()),
             accounts,
         )
         .await)
@@ -0,0 +1,96 @@
+//! Runs in its own test binary, the application directory is read from the environment once
+//! per process and the test points it to a temporary directory before anything reads it
+
+use deps::*;
+
+use handles::credentials::{
+    ConfiguredCredentialStore, get_db_encryption_salt, get_encrypted_mnemonic,
+    memory_store::MemoryStore, set_credential_store, store_db_encryption_salt,
+    store_encrypted_mnemonic,
+};
+use store::{AppDataDb, DataBase, IconsDb};
+use types::{
+    AppPath,
+    crypto::{
+        EncryptedMnemonic, KdfParams, Key, KeySaltPair, KeyType, Password, Salt,
+        bip39::{Language, Mnemonic, MnemonicType},
+    },
+};
+use wallet::{Locked, LoginResponse, Settings, Wallet, WalletData};
+
+/// A wallet created before the key derivation parameters were recorded, the salts use PBKDF2
+async fn create_pbkdf2_wallet(password: &Password, settings: &Settings) -> Key<DataBase> {
+    let mnemonic = Mnemonic::new(MnemonicType::Words24, Language::English);
+    let mnemonic_salt = Salt::new(KdfParams::Pbkdf2).unwrap();
+    let encrypted_mnemonic = EncryptedMnemonic::new_with_key_and_salt(
+        &mnemonic,
+        "",
+        KeySaltPair::from_salt(password.as_str(), mnemonic_salt),
+    )
+    .unwrap();
+    let salt = Salt::new(KdfParams::Pbkdf2).unwrap();
+    let key = Key::<DataBase>::new(password.as_str(), &salt);
+
+    let db = AppDataDb::get_or_init(settings.network, key.clone())
+        .await
+        .unwrap();
+    db.upsert_password_hash(password.derive_db_encryption_key_hash_from_salt(&salt))
+        .await
+        .unwrap();
+    IconsDb::get_or_init(settings.network, key.clone())
+        .await
+        .unwrap();
+    store_encrypted_mnemonic(&encrypted_mnemonic).unwrap();
+    store_db_encryption_salt(salt).unwrap();
+
+    key
+}
+
+#[test]
+fn test_login_upgrades_key_derivation() {
+    let directory = std::env::temp_dir().join(format!("wallet_login_{}", std::process::id()));
+    // Set before the runtime starts, no other thread of this binary reads the environment
+    unsafe {
+        std::env::set_var("XDG_DATA_HOME", &directory);
+        std::env::set_var("LOCALAPPDATA", &directory);
+    }
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(login_upgrades_key_derivation(&directory));
+}
+
+async fn login_upgrades_key_derivation(directory: &std::path::Path) {
+    AppPath::get().create_directories_if_not_exists().unwrap();
+    set_credential_store(ConfiguredCredentialStore::Memory(MemoryStore::new()));
+
+    let password = Password::from("wallet password");
+    let settings = Settings::new();
+    let network = settings.network;
+    let old_key = create_pbkdf2_wallet(&password, &settings).await;
+
+    let wallet = Wallet::new(Locked::new(false), WalletData::new(settings));
+    let response = wallet.login_with_password(password.clone()).await;
+
+    let salt = get_db_encryption_salt().unwrap();
+    let encrypted_mnemonic = get_encrypted_mnemonic().unwrap();
+    assert!(matches!(response, LoginResponse::Success(..)));
+
+    let db_path = AppPath::get().db_path_ref(network).into_owned();
+    let new_key = Key::<DataBase>::new(password.as_str(), &salt);
+    let with_new_key = AppDataDb::load_from_path(&db_path, new_key)
+        .await
+        .unwrap()
+        .get_db_password_hash()
+        .await;
+    let with_old_key = AppDataDb::load_from_path(&db_path, old_key).await;
+    std::fs::remove_dir_all(directory).ok();
+
+    assert_eq!(salt.kdf(), &DataBase::KDF);
+    assert_eq!(encrypted_mnemonic.kdf(), &EncryptedMnemonic::KDF);
+    assert!(encrypted_mnemonic.decrypt_mnemonic(&password).is_ok());
+    assert!(with_new_key.unwrap() == password.derive_db_encryption_key_hash_from_salt(&salt));
+    assert!(with_old_key.is_err());
+}
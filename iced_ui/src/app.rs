@@ -4,6 +4,7 @@ use deps::*;
 use no_mangle_if_debug::no_mangle_if_debug;
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::time::Duration;
 
 use debug_print::debug_println;
 use font_and_icons::BOOTSTRAP_FONT_BYTES;
@@ -11,19 +12,23 @@ use font_and_icons::images::WINDOW_LOGO;
 use iced::time;
 use iced::widget::{container, text};
 use iced::{Length, Settings, Size, application, window};
+use futures::{Stream, StreamExt};
+use handles::radix_dlt::{gateway_requests::RadixGateway, updates};
 use iced::{Subscription, Task};
 use store::AppDataDb;
+use tokio::sync::mpsc::{self, UnboundedSender};
 use types::AppError;
-use types::{Network, Notification, Theme};
+use types::{Network, Notification, RequestLimits, Theme};
 use wallet::wallet::Wallet;
 use wallet::{Locked, Unlocked, WalletData};
 
 use crate::common::Message;
+use crate::initial::restore_from_backup;
 use crate::initial::restore_from_seed;
 use crate::initial::setup::{self, Setup};
 use crate::locked::loginscreen::{self, LoginScreen};
 use crate::unlocked;
-use crate::unlocked::app_view::AppView;
+use crate::unlocked::app_view::{AppView, RefreshInFlight};
 
 //Reexport for hot reloading
 pub use iced::Element;
@@ -80,10 +85,18 @@ impl App {
 
     pub fn inner_new() -> (Self, Task<AppMessage>) {
         let settings = wallet::Settings::load_from_disk_or_default();
+        // Before the password change recovery, which writes to the credential store
+        let credential_backend = settings.use_credential_backend();
 
         let app_state =
-            match handles::statics::initialize_statics::initialize_statics(settings.network) {
-                Err(err) => AppState::Error(err.to_string()),
+            match handles::statics::initialize_statics::initialize_statics()
+                .map_err(|err| err.to_string())
+                .and_then(|_| {
+                    // Puts the old password back if the wallet was closed while changing it
+                    handles::wallet::recover_interrupted_password_change()
+                        .map_err(|err| err.to_string())
+                }) {
+                Err(err) => AppState::Error(err),
                 Ok(_) => {
                     if AppDataDb::exists(settings.network) {
                         AppState::Locked(
@@ -99,13 +112,16 @@ impl App {
                 }
             };
 
-        let app = App {
+        let mut app = App {
             app_state,
             // app_data: AppData::new(settings),
             appview: AppView::new(),
             notification: Notification::None,
             preferences: Preferences::default(),
         };
+        if let Err(err) = credential_backend {
+            app.handle_error(err);
+        }
 
         (app, Task::none())
     }
@@ -129,6 +145,14 @@ impl App {
                 setup::Message::RestoreFromSeedMessage(
                     restore_from_seed::Message::WalletCreated(wallet),
                 ) => self.app_state = AppState::Unlocked(wallet),
+                setup::Message::RestoreFromBackupMessage(
+                    restore_from_backup::Message::WalletCreated(wallet, warning),
+                ) => {
+                    self.app_state = AppState::Unlocked(wallet);
+                    if let Some(err) = warning {
+                        self.handle_error(err);
+                    }
+                }
                 setup::Message::Error(err) => self.handle_error(err),
                 message => {
                     if let AppState::Initial(setup, wallet) = &mut self.app_state {
@@ -195,10 +219,53 @@ impl App {
     }
 
     #[cfg(debug_assertions)]
+    pub fn subscription(&self) -> Subscription<HotMessage> {
+        self.inner_subscription().map(HotMessage::from_message)
+    }
+
+    #[cfg(not(debug_assertions))]
     pub fn subscription(&self) -> Subscription<AppMessage> {
-        Subscription::batch([
-            time::every(time::Duration::from_millis(500)).map(|_| AppMessage::None)
-        ])
+        self.inner_subscription()
+    }
+
+    pub fn inner_subscription(&self) -> Subscription<AppMessage> {
+        let mut subscriptions = Vec::new();
+
+        #[cfg(debug_assertions)]
+        subscriptions.push(time::every(time::Duration::from_millis(500)).map(|_| AppMessage::None));
+
+        if let AppState::Unlocked(wallet) = &self.app_state
+            && !wallet.pending_transactions().is_empty()
+        {
+            subscriptions.push(
+                time::every(AppView::PENDING_TRANSACTIONS_POLL_INTERVAL).map(|_| {
+                    unlocked::app_view::Message::PollPendingTransactions.into()
+                }),
+            );
+        }
+
+        if let AppState::Unlocked(wallet) = &self.app_state
+            && let Some(interval) = wallet.settings().refresh_interval()
+        {
+            subscriptions.push(Subscription::run_with(
+                (
+                    wallet.settings().network,
+                    interval,
+                    wallet.settings().request_limits(),
+                    self.appview.refresh_in_flight(),
+                ),
+                account_refresh,
+            ));
+        }
+
+        if matches!(self.app_state, AppState::Unlocked(_)) && self.appview.is_offline() {
+            subscriptions.push(
+                time::every(AppView::GATEWAY_CHECK_INTERVAL)
+                    .map(|_| unlocked::app_view::Message::CheckGateway.into()),
+            );
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     pub fn theme(&self) -> iced::Theme {
@@ -268,6 +335,97 @@ impl App {
     }
 }
 
+/// Refreshes the accounts right away and then every `interval`. The progress of each refresh
+/// is streamed to the app view
+fn account_refresh(
+    (network, interval, limits, in_flight): &(Network, Duration, RequestLimits, RefreshInFlight),
+) -> impl Stream<Item = AppMessage> {
+    let (network, interval, limits, in_flight) = (*network, *interval, *limits, in_flight.clone());
+    futures::stream::once(async move {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(refresh_accounts(
+            network, interval, limits, in_flight, sender,
+        ));
+        futures::stream::poll_fn(move |cx| receiver.poll_recv(cx))
+    })
+    .flatten()
+}
+
+/// Runs until the subscription is dropped. While no account can be updated, e.g. when offline,
+/// the time between refreshes is doubled up to [`AppView::MAX_REFRESH_INTERVAL`].
+/// The refresh is skipped if the gateway can not be reached,
+/// instead of waiting for every request to fail, or if a refresh started by the app is running
+async fn refresh_accounts(
+    network: Network,
+    interval: Duration,
+    limits: RequestLimits,
+    in_flight: RefreshInFlight,
+    sender: UnboundedSender<AppMessage>,
+) {
+    let gateway = RadixGateway::new(network, limits);
+    let mut next_refresh = interval;
+
+    loop {
+        let Some(refresh_guard) = in_flight.start() else {
+            tokio::time::sleep(next_refresh).await;
+            if sender.is_closed() {
+                return;
+            }
+            continue;
+        };
+
+        if !gateway.is_reachable().await {
+            drop(refresh_guard);
+            next_refresh = (next_refresh * 2).min(AppView::MAX_REFRESH_INTERVAL.max(interval));
+            if sender
+                .send(unlocked::app_view::Message::GatewayChecked(false).into())
+                .is_err()
+            {
+                return;
+            }
+            tokio::time::sleep(next_refresh).await;
+            continue;
+        }
+
+        let (progress_sender, mut progress_receiver) = mpsc::unbounded_channel();
+        let update = tokio::spawn(updates::update_all_accounts(
+            gateway.clone(),
+            network,
+            Some(progress_sender),
+        ));
+
+        while let Some(progress) = progress_receiver.recv().await {
+            sender
+                .send(unlocked::app_view::Message::RefreshProgress(progress).into())
+                .ok();
+        }
+
+        let update = update.await;
+        drop(refresh_guard);
+        let message = match update {
+            Ok(Ok(accounts_update)) => {
+                next_refresh = if accounts_update.every_account_failed() {
+                    (next_refresh * 2).min(AppView::MAX_REFRESH_INTERVAL.max(interval))
+                } else {
+                    interval
+                };
+                unlocked::app_view::Message::AccountsRefreshed(accounts_update).into()
+            }
+            Ok(Err(err)) => AppMessage::Error(err),
+            Err(err) => AppMessage::Error(AppError::NonFatal(Notification::Warn(err.to_string()))),
+        };
+
+        if sender.send(message).is_err() {
+            return;
+        }
+
+        tokio::time::sleep(next_refresh).await;
+        if sender.is_closed() {
+            return;
+        }
+    }
+}
+
 // #[no_mangle_if_debug]
 // pub fn update(state: &mut App, message: AppMessage) -> Task<AppMessage> {
 //     let mut task = Task::none();
@@ -34,6 +34,10 @@ impl TextField {
         }
     }
 
+    pub fn text(&self) -> String {
+        self.content.text()
+    }
+
     pub fn update(&mut self, message: Message) {
         match message {
             Message::Edit(edit) => self.content.perform(Action::Edit(edit)),
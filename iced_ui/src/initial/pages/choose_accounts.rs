@@ -18,6 +18,8 @@ pub enum Message {
     Next,
     Accounts(Vec<(Account, AccountSummary)>),
     ToggleAccountSelection(usize),
+    /// Take the accounts from a Radix Wallet profile instead, handled in parent
+    ImportProfile,
 }
 
 #[derive(Debug)]
@@ -65,7 +67,7 @@ impl<'a> ChooseAccounts {
                     account.1 = !account.1
                 }
             }
-            Message::Back | Message::Next => { /*Handled in parent*/ }
+            Message::Back | Message::Next | Message::ImportProfile => { /*Handled in parent*/ }
         }
         Task::none()
     }
@@ -78,6 +80,7 @@ impl<'a> ChooseAccounts {
             .collect();
 
         wallet.set_accounts(accounts);
+        wallet.set_other_network_accounts(Vec::new());
     }
 }
 
@@ -119,7 +122,12 @@ impl<'a> ChooseAccounts {
             nav_button("Next", Message::Next),
         );
 
-        let content_and_nav = column![content_container, nav];
+        let import_profile = widget::button("Import accounts from the Radix Wallet")
+            .on_press(Message::ImportProfile);
+
+        let content_and_nav = column![content_container, import_profile, nav]
+            .align_x(iced::Alignment::Center)
+            .spacing(10);
 
         widget::container(content_and_nav)
             .center_x(650)
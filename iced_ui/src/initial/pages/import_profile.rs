@@ -0,0 +1,210 @@
+use deps::*;
+
+use std::path::PathBuf;
+
+use iced::{
+    Element, Length, Task,
+    widget::{self, button, column, container, text, text_input},
+};
+use types::{AppError, Notification, crypto::Password};
+use wallet::{ProfileImport, Setup, wallet::Wallet};
+use zeroize::Zeroize;
+
+use crate::{
+    common_elements,
+    components::{self, password_input::password_input},
+    initial::common::{nav_button, nav_row},
+};
+
+#[derive(Clone)]
+pub enum Message {
+    Back,
+    Next,
+    InputPath(String),
+    InputPassword(String),
+    TogglePasswordVisibility,
+    Import,
+    Imported(Result<ProfileImport, AppError>),
+}
+
+/// Reads the accounts from a profile backup of the official Radix wallet
+/// instead of scanning the ledger for them
+#[derive(Debug)]
+pub struct ImportProfile {
+    pub notification: Notification,
+    path: String,
+    /// Only needed if the profile backup is encrypted
+    password: Password,
+    reveal_password: bool,
+    importing: bool,
+    import: Option<ProfileImport>,
+}
+
+impl<'a> ImportProfile {
+    pub fn new() -> Self {
+        Self {
+            notification: Notification::None,
+            path: String::new(),
+            password: Password::new(),
+            reveal_password: false,
+            importing: false,
+            import: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message, wallet: &'a mut Wallet<Setup>) -> Task<Message> {
+        match message {
+            Message::InputPath(path) => {
+                self.path = path;
+                self.import = None;
+            }
+            Message::InputPassword(mut input) => {
+                self.password.replace(&input);
+                input.zeroize();
+            }
+            Message::TogglePasswordVisibility => self.reveal_password = !self.reveal_password,
+            Message::Import => return self.import(wallet),
+            Message::Imported(result) => {
+                self.importing = false;
+                match result {
+                    Ok(import) => {
+                        self.notification = Notification::Success(Self::summary(&import));
+                        self.import = Some(import);
+                    }
+                    Err(AppError::NonFatal(notification)) => self.notification = notification,
+                    Err(err) => self.notification = Notification::Warn(err.to_string()),
+                }
+                return Task::none();
+            }
+            Message::Back | Message::Next => { /*Handled in parent*/ }
+        }
+        if !self.importing {
+            self.notification = Notification::None;
+        }
+        Task::none()
+    }
+
+    fn import(&mut self, wallet: &'a mut Wallet<Setup>) -> Task<Message> {
+        if self.importing || self.path.trim().is_empty() {
+            return Task::none();
+        }
+        self.importing = true;
+        self.import = None;
+        self.notification = Notification::Info("Reading the profile...".to_string());
+
+        let handle = wallet.import_radix_wallet_profile(
+            PathBuf::from(self.path.trim()),
+            std::mem::take(&mut self.password),
+        );
+
+        Task::perform(handle, |result| {
+            let result = result
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+                .and_then(|result| result);
+            Message::Imported(result)
+        })
+    }
+
+    fn summary(import: &ProfileImport) -> String {
+        let mut summary = format!("Found {} account(s)", import.accounts.len());
+        if !import.other_network_accounts.is_empty() {
+            summary.push_str(&format!(
+                ", {} on other networks are restored on their network",
+                import.other_network_accounts.len()
+            ));
+        }
+        if import.unmatched > 0 {
+            summary.push_str(&format!(
+                ", {} belong to another recovery phrase or a Ledger device",
+                import.unmatched
+            ));
+        }
+        if import.olympia > 0 {
+            summary.push_str(&format!(
+                ", {} are Olympia accounts which can not be imported",
+                import.olympia
+            ));
+        }
+        if import.unknown_networks > 0 {
+            summary.push_str(&format!(
+                ", {} are on networks unknown to the wallet",
+                import.unknown_networks
+            ));
+        }
+        summary
+    }
+
+    /// Fails if no profile has been imported yet
+    pub fn save_to_wallet(&mut self, wallet: &'a mut Wallet<Setup>) -> Result<(), ()> {
+        let Some(import) = &self.import else {
+            self.notification = Notification::Info("Import a profile first".to_string());
+            return Err(());
+        };
+        wallet.set_accounts(import.accounts.clone());
+        wallet.set_other_network_accounts(import.other_network_accounts.clone());
+        Ok(())
+    }
+}
+
+impl<'a> ImportProfile {
+    pub fn view(&'a self) -> Element<'a, Message> {
+        let header = common_elements::header_one("Import from Radix Wallet");
+
+        let info = text(
+            "Select a profile backup exported from the Radix Wallet. Only the accounts \
+            of the entered recovery phrase are imported",
+        )
+        .size(12);
+
+        let notification = components::notification::notification(&self.notification);
+
+        let path_input = text_input("Profile backup file", &self.path)
+            .on_input_maybe((!self.importing).then_some(Message::InputPath))
+            .on_submit(Message::Import)
+            .size(16)
+            .padding(10);
+
+        let password_input = password_input(
+            "Backup password, if encrypted",
+            self.password.as_str(),
+            self.reveal_password,
+            Message::TogglePasswordVisibility,
+            Message::InputPassword,
+            Message::Import,
+        );
+
+        let import_button = button("Import").on_press_maybe(
+            (!self.importing && !self.path.trim().is_empty()).then_some(Message::Import),
+        );
+
+        let content = column![
+            header,
+            info,
+            notification,
+            path_input,
+            password_input,
+            import_button
+        ]
+        .align_x(iced::Alignment::Center)
+        .spacing(30);
+
+        let content_container = container(content)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let nav = nav_row(
+            nav_button("Back", Message::Back),
+            nav_button("Next", Message::Next)
+                .on_press_maybe(self.import.is_some().then_some(Message::Next)),
+        );
+
+        let content_and_nav = column![content_container, nav];
+
+        widget::container(content_and_nav)
+            .max_width(350)
+            .max_height(600)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into()
+    }
+}
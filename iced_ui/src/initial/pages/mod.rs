@@ -3,4 +3,5 @@ pub mod view_seed_phrase;
 pub mod verify_seed_phrase;
 pub mod enter_password;
 pub mod choose_accounts;
+pub mod import_profile;
 pub mod name_accounts;
\ No newline at end of file
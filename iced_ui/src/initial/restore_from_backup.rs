@@ -1,15 +1,36 @@
 use deps::*;
 
-use iced::{widget::column, Element};
+use std::path::PathBuf;
 
-use crate::{app::App, app::AppMessage};
+use iced::{
+    Element, Length, Task,
+    widget::{self, checkbox, column, container, text_input},
+};
+use types::{AppError, AppPath, Notification, crypto::Password};
+use wallet::{Setup, Unlocked, wallet::Wallet};
+use zeroize::Zeroize;
+
+use crate::{
+    app::AppMessage,
+    common_elements,
+    components::{self, password_input::password_input},
+    initial::common::{nav_button, nav_row},
+};
 
 use super::setup;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum Message {
-    FromCloud,
-    FromFile,
+    Back,
+    InputPath(String),
+    InputBackupPassword(String),
+    InputPassword(String),
+    ToggleShowPasswords,
+    ToggleResync(bool),
+    Restore,
+    Restored(Result<(Wallet<Unlocked>, Option<AppError>), AppError>),
+    /// Propagated to the app with the error of a failed sync of the restored accounts
+    WalletCreated(Wallet<Unlocked>, Option<AppError>),
 }
 
 impl Into<AppMessage> for Message {
@@ -19,21 +40,163 @@ impl Into<AppMessage> for Message {
 }
 
 #[derive(Debug)]
-pub enum RestoreFromBackup {
-    FromBackup,
-    FromSeed,
+pub struct RestoreFromBackup {
+    notification: Notification,
+    path: String,
+    backup_password: Password,
+    password: Password,
+    show_passwords: bool,
+    /// Also fetch the balances and transactions of the restored accounts
+    resync: bool,
+    restoring: bool,
 }
 
 impl<'a> RestoreFromBackup {
-    pub fn view(&self, _app: &'a App) -> Element<'_, Message> {
-        column!().into()
-        // let from_backup =
-        //     Setup::creation_button("From Cloud").on_press(setup::Message::FromBackup);
+    pub fn new() -> Self {
+        Self {
+            notification: Notification::None,
+            // Backups are exported here, only the file name has to be added
+            path: format!(
+                "{}{}",
+                AppPath::get().exports_directory().display(),
+                std::path::MAIN_SEPARATOR
+            ),
+            backup_password: Password::new(),
+            password: Password::new(),
+            show_passwords: false,
+            resync: true,
+            restoring: false,
+        }
+    }
+
+    pub fn update(&mut self, message: Message, wallet: &'a mut Wallet<Setup>) -> Task<Message> {
+        match message {
+            Message::InputPath(path) => self.path = path,
+            Message::InputBackupPassword(input) => {
+                Self::input_password(&mut self.backup_password, input)
+            }
+            Message::InputPassword(input) => Self::input_password(&mut self.password, input),
+            Message::ToggleShowPasswords => self.show_passwords = !self.show_passwords,
+            Message::ToggleResync(resync) => self.resync = resync,
+            Message::Restore => return self.restore(wallet),
+            Message::Restored(result) => return self.restored(result),
+            Message::Back | Message::WalletCreated(..) => { /*Handle in parent*/ }
+        }
+        if !self.restoring {
+            self.notification = Notification::None;
+        }
+        Task::none()
+    }
+
+    fn input_password(password: &mut Password, mut input: String) {
+        password.replace(&input);
+        input.zeroize();
+    }
 
-        // let from_seed =
-        //     Setup::creation_button("From File").on_press(setup::Message::FromBackup);
+    fn restore(&mut self, wallet: &'a mut Wallet<Setup>) -> Task<Message> {
+        if self.restoring || self.path.is_empty() {
+            return Task::none();
+        }
 
-        // column![from_backup, from_seed].into()
+        self.restoring = true;
+        self.notification = Notification::Info("Restoring the wallet...".to_string());
+
+        let handle = wallet.restore_from_backup(
+            PathBuf::from(self.path.trim()),
+            std::mem::take(&mut self.backup_password),
+            std::mem::take(&mut self.password),
+            self.resync,
+        );
+
+        Task::perform(handle, |result| {
+            let result = result
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+                .and_then(|result| result);
+            Message::Restored(result)
+        })
+    }
+
+    fn restored(
+        &mut self,
+        result: Result<(Wallet<Unlocked>, Option<AppError>), AppError>,
+    ) -> Task<Message> {
+        self.restoring = false;
+        match result {
+            Ok((wallet, warning)) => {
+                return Task::perform(async {}, move |_| Message::WalletCreated(wallet, warning));
+            }
+            Err(AppError::NonFatal(notification)) => self.notification = notification,
+            // Nothing is left behind by a failed restore, so it can be tried again
+            Err(AppError::Fatal(err)) => self.notification = Notification::Warn(err),
+            Err(AppError::Ignore) => self.notification = Notification::None,
+        }
+        Task::none()
     }
 }
 
+impl<'a> RestoreFromBackup {
+    pub fn view(&'a self) -> Element<'a, Message> {
+        let header = common_elements::header_one("Restore from backup");
+
+        let notification = components::notification::notification(&self.notification);
+
+        let path_input = text_input("Backup file", &self.path)
+            .on_input_maybe((!self.restoring).then_some(Message::InputPath))
+            .size(16)
+            .padding(10);
+
+        let backup_password_input = password_input(
+            "Backup password",
+            self.backup_password.as_str(),
+            self.show_passwords,
+            Message::ToggleShowPasswords,
+            Message::InputBackupPassword,
+            Message::Restore,
+        );
+
+        let password_input = password_input(
+            "Wallet password",
+            self.password.as_str(),
+            self.show_passwords,
+            Message::ToggleShowPasswords,
+            Message::InputPassword,
+            Message::Restore,
+        );
+
+        let resync = checkbox("Sync accounts with the ledger", self.resync)
+            .on_toggle_maybe((!self.restoring).then_some(Message::ToggleResync));
+
+        let content = column![
+            header,
+            notification,
+            path_input,
+            backup_password_input,
+            password_input,
+            resync,
+        ]
+        .align_x(iced::Alignment::Center)
+        .spacing(30);
+
+        let content_container = container(content)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+        let can_restore =
+            !self.restoring && !self.backup_password.is_empty() && !self.password.is_empty();
+        let nav = nav_row(
+            nav_button("Back", Message::Back)
+                .on_press_maybe((!self.restoring).then_some(Message::Back)),
+            nav_button("Restore", Message::Restore)
+                .on_press_maybe(can_restore.then_some(Message::Restore)),
+        );
+
+        let content_and_nav = column![content_container, nav];
+
+        widget::container(content_and_nav)
+            .max_width(350)
+            .max_height(600)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into()
+    }
+}
@@ -4,7 +4,7 @@ use iced::{widget::{self, container}, Element, Length, Task};
 use types::{AppError, Notification};
 use wallet::{wallet::Wallet, Setup, Unlocked};
 
-use super::pages::{choose_accounts::{self, ChooseAccounts}, enter_password::{self, EnterPassword}, enter_seed_phrase::{self, EnterSeedPhrase}, name_accounts::{self, NameAccounts}};
+use super::pages::{choose_accounts::{self, ChooseAccounts}, enter_password::{self, EnterPassword}, enter_seed_phrase::{self, EnterSeedPhrase}, import_profile::{self, ImportProfile}, name_accounts::{self, NameAccounts}};
 
 pub enum Action {
     SetupSelection,
@@ -24,6 +24,7 @@ pub enum Message {
     EnterSeedPhraseMessage(enter_seed_phrase::Message),
     EnterPasswordMessage(enter_password::Message),
     ChooseAccountsMessage(choose_accounts::Message),
+    ImportProfileMessage(import_profile::Message),
     NameAccountsMessage(name_accounts::Message),
     WalletCreated(Wallet<Unlocked>),
     Error(AppError)
@@ -34,7 +35,10 @@ pub enum RestoreFromSeed {
     EnterSeedPhrase(EnterSeedPhrase),
     EnterPassword(EnterPassword),
     ChooseAccounts(ChooseAccounts),
-    NameAccounts(NameAccounts),
+    /// Alternative to choosing the accounts, they are read from a Radix Wallet profile
+    ImportProfile(ImportProfile),
+    /// Holds the import page if the accounts were imported from a profile, going back returns to it
+    NameAccounts(NameAccounts, Option<ImportProfile>),
     Finalizing,
 }
 
@@ -48,10 +52,12 @@ impl<'a> RestoreFromSeed {
             Message::EnterSeedPhraseMessage(enter_seed_phrase::Message::Back)
             | Message::EnterPasswordMessage(enter_password::Message::Back)
             | Message::ChooseAccountsMessage(choose_accounts::Message::Back)
+            | Message::ImportProfileMessage(import_profile::Message::Back)
             | Message::NameAccountsMessage(name_accounts::Message::Back) => return self.back(wallet),
             Message::EnterSeedPhraseMessage(enter_seed_phrase::Message::Next)
             | Message::EnterPasswordMessage(enter_password::Message::Next)
             | Message::ChooseAccountsMessage(choose_accounts::Message::Next)
+            | Message::ImportProfileMessage(import_profile::Message::Next)
             | Message::NameAccountsMessage(name_accounts::Message::Next) => return self.next(wallet),
             Message::EnterSeedPhraseMessage(message) => {
                 if let Self::EnterSeedPhrase(page) = self {
@@ -65,14 +71,23 @@ impl<'a> RestoreFromSeed {
                         .map(Message::EnterPasswordMessage))
                 }
             }
+            Message::ChooseAccountsMessage(choose_accounts::Message::ImportProfile) => {
+                *self = Self::ImportProfile(ImportProfile::new())
+            }
             Message::ChooseAccountsMessage(message) => {
                 if let Self::ChooseAccounts(page) = self {
                     return Action::Task(page.update(message, wallet)
                         .map(Message::ChooseAccountsMessage))
                 }
             }
+            Message::ImportProfileMessage(message) => {
+                if let Self::ImportProfile(page) = self {
+                    return Action::Task(page.update(message, wallet)
+                        .map(Message::ImportProfileMessage))
+                }
+            }
             Message::NameAccountsMessage(message) => {
-                if let Self::NameAccounts(page) = self {
+                if let Self::NameAccounts(page, _) = self {
                     return Action::Task(page.update(message)
                         .map(Message::NameAccountsMessage))
                 }
@@ -94,7 +109,7 @@ impl<'a> RestoreFromSeed {
                 return Action::Task(task.map(Message::ChooseAccountsMessage))
             }
             Message::NameAccounts => {
-                *self = Self::NameAccounts(NameAccounts::new(wallet))
+                *self = Self::NameAccounts(NameAccounts::new(wallet), None)
             }
         }
         Action::None
@@ -105,7 +120,16 @@ impl<'a> RestoreFromSeed {
             Self::EnterSeedPhrase(_) => return Action::SetupSelection,
             Self::EnterPassword(_) => *self = Self::EnterSeedPhrase(EnterSeedPhrase::new(wallet, Notification::None)),
             Self::ChooseAccounts(_) => *self = Self::EnterPassword(EnterPassword::new(wallet, Notification::None)),
-            Self::NameAccounts(_) => {
+            Self::ImportProfile(_) => {
+                let (page, task) = ChooseAccounts::new(wallet);
+                *self = Self::ChooseAccounts(page);
+                return Action::Task(task.map(Message::ChooseAccountsMessage))
+            }
+            Self::NameAccounts(_, import_page) => {
+                if let Some(import_page) = import_page.take() {
+                    *self = Self::ImportProfile(import_page);
+                    return Action::None
+                }
                 let (page, task) = ChooseAccounts::new(wallet);
                 *self = Self::ChooseAccounts(page);
                 return Action::Task(task.map(Message::ChooseAccountsMessage))
@@ -134,9 +158,15 @@ impl<'a> RestoreFromSeed {
             },
             Self::ChooseAccounts(page) => {
                 page.save_to_wallet(wallet);
-                *self = Self::NameAccounts(NameAccounts::new(wallet))
+                *self = Self::NameAccounts(NameAccounts::new(wallet), None)
+            },
+            Self::ImportProfile(page) => {
+                if let Ok(()) = page.save_to_wallet(wallet) {
+                    let import_page = std::mem::replace(page, ImportProfile::new());
+                    *self = Self::NameAccounts(NameAccounts::new(wallet), Some(import_page))
+                }
             },
-            Self::NameAccounts(page) => {
+            Self::NameAccounts(page, _) => {
                 page.save_to_wallet(wallet);
                 *self = Self::Finalizing;
                 let setup = wallet.get_setup();
@@ -168,7 +198,9 @@ impl<'a> RestoreFromSeed {
                 .map(Message::EnterPasswordMessage),
             Self::ChooseAccounts(page) => page.view()
                 .map(Message::ChooseAccountsMessage),
-            Self::NameAccounts(page) => page.view()
+            Self::ImportProfile(page) => page.view()
+                .map(Message::ImportProfileMessage),
+            Self::NameAccounts(page, _) => page.view()
                 .map(Message::NameAccountsMessage),
             Self::Finalizing => widget::text("Finalizing setup...").into(),
         };
@@ -62,6 +62,7 @@ impl<'a> Setup {
                 wallet.reset();
             }
             Message::NewWallet => *self = Self::NewWallet(NewWallet::new(wallet)),
+            Message::FromBackup => *self = Self::RestoreFromBackup(RestoreFromBackup::new()),
             Message::FromSeed => {
                 *self = Self::RestoreFromSeed(restore_from_seed::RestoreFromSeed::new(wallet))
             }
@@ -94,18 +95,26 @@ impl<'a> Setup {
                     }
                 }
             }
+            Message::RestoreFromBackupMessage(message) => {
+                if let restore_from_backup::Message::Back = message {
+                    *self = Self::SelectSetup;
+                } else if let Setup::RestoreFromBackup(restore_from_backup) = self {
+                    return Ok(restore_from_backup
+                        .update(message, wallet)
+                        .map(Message::RestoreFromBackupMessage));
+                }
+            }
             Message::Error(_) => { /*Propagate*/ }
             Message::WalletCreated(_) => { /*Propagate*/ }
-            _ => {}
         }
         Ok(Task::none())
     }
 
-    pub fn view(&'a self, app: &'a App, wallet: &Wallet<wallet::Setup>) -> Element<'a, Message> {
+    pub fn view(&'a self, _app: &'a App, wallet: &Wallet<wallet::Setup>) -> Element<'a, Message> {
         match self {
             Setup::SelectSetup => self.select_creation_view(),
             Setup::RestoreFromBackup(restore_from_backup) => restore_from_backup
-                .view(app)
+                .view()
                 .map(Message::RestoreFromBackupMessage),
             Setup::NewWallet(new_wallet) => new_wallet.view(wallet).map(|m| match m {
                 new_wallet::Message::SetupSelection => Message::SelectSetup,
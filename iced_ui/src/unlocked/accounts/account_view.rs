@@ -20,6 +20,8 @@ use types::Account;
 use wallet::Unlocked;
 use wallet::Wallet;
 
+use super::balance_chart::{self, BalanceChart};
+use super::history::{self, History};
 use super::non_fungibles::NonFungibles;
 use super::{accounts_view, non_fungibles};
 use super::{fungibles, fungibles::Fungibles};
@@ -28,6 +30,8 @@ use super::{fungibles, fungibles::Fungibles};
 pub enum Message {
     FungiblesView(AccountAddress),
     NonFungiblesView(AccountAddress),
+    HistoryView(AccountAddress),
+    ChartView(AccountAddress),
     SelectFungible,
     SelectNonFungible {
         account_id: usize,
@@ -39,6 +43,8 @@ pub enum Message {
     },
     FungiblesMessage(fungibles::Message),
     NonFungiblesMessage(non_fungibles::Message),
+    HistoryMessage(history::Message),
+    ChartMessage(balance_chart::Message),
     //Transaction(Account),
 }
 
@@ -54,6 +60,8 @@ impl Into<AppMessage> for Message {
 pub enum AssetView {
     Tokens(Fungibles),
     NonFungibles(NonFungibles),
+    History(History),
+    Chart(BalanceChart),
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +119,16 @@ impl<'a> AccountView {
             Message::NonFungiblesView(account_address) => {
                 self.view = AssetView::NonFungibles(NonFungibles::new(account_address))
             }
+            Message::HistoryView(account_address) => {
+                let mut history = History::new(account_address);
+                command = history.load_page(wallet);
+                self.view = AssetView::History(history)
+            }
+            Message::ChartView(account_address) => {
+                let mut chart = BalanceChart::new(account_address);
+                command = chart.load_snapshots(wallet);
+                self.view = AssetView::Chart(chart)
+            }
             Message::SelectFungible => self.select_fungible(wallet),
             Message::SelectNonFungible {
                 account_id,
@@ -129,6 +147,16 @@ impl<'a> AccountView {
                 if let AssetView::NonFungibles(non_fungibles) = &mut self.view {
                     command = non_fungibles.update(nfts_message, wallet)
                 }
+            }
+            Message::HistoryMessage(history_message) => {
+                if let AssetView::History(history) = &mut self.view {
+                    command = history.update(history_message, wallet)
+                }
+            }
+            Message::ChartMessage(chart_message) => {
+                if let AssetView::Chart(chart) = &mut self.view {
+                    command = chart.update(chart_message)
+                }
             } // Self::Transaction(account) => Self::transaction_from_account(account, app),
         }
         command
@@ -189,7 +217,8 @@ impl<'a> AccountView {
             widget::Space::new(Length::FillPortion(2), 1)
         ];
 
-        let history_button = Self::nav_button("History");
+        let history_button = Self::nav_button("History")
+            .on_press(Message::HistoryView(self.address.clone()).into());
 
         let transfer_button = Self::nav_button("Send")
             .on_press(app_view::Message::NewTransaction(Some(account.clone())).into());
@@ -209,6 +238,12 @@ impl<'a> AccountView {
         let mut nft_button = Self::select_asset_button("NFTs")
             .on_press(Message::NonFungiblesView(self.address.clone()).into());
 
+        let mut history_tab = Self::select_asset_button("History")
+            .on_press(Message::HistoryView(self.address.clone()).into());
+
+        let mut chart_tab = Self::select_asset_button("Chart")
+            .on_press(Message::ChartView(self.address.clone()).into());
+
         let assets = match &self.view {
             AssetView::Tokens(fungibles_view) => {
                 fung_button = fung_button.style(styles::button::base_layer_2_selected);
@@ -220,9 +255,19 @@ impl<'a> AccountView {
 
                 non_fungibles.view(wallet)
             }
+            AssetView::History(history) => {
+                history_tab = history_tab.style(styles::button::base_layer_2_selected);
+
+                history.view(wallet)
+            }
+            AssetView::Chart(chart) => {
+                chart_tab = chart_tab.style(styles::button::base_layer_2_selected);
+
+                chart.view(wallet)
+            }
         };
 
-        let asset_button_row = row![fung_button, nft_button].spacing(100);
+        let asset_button_row = row![fung_button, nft_button, history_tab, chart_tab].spacing(100);
         let asset_button_cont = container(asset_button_row).center_x(Length::Fill);
 
         let col = column![top_row, nav_button_cont, asset_button_cont, assets]
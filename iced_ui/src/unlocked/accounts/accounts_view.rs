@@ -109,9 +109,20 @@ impl<'a> AccountsView {
         .style(styles::button::base_layer_2_rounded_with_shadow)
         .on_press(app_view::Message::SpawnOverlay(SpawnOverlay::AddAccount).into());
 
-        let header = row![title, widget::Space::new(Length::Fill, 1), new_account]
-            .align_y(iced::Alignment::End)
-            .padding(20);
+        let prices = wallet.prices();
+        let total_value = prices
+            .total_value(wallet.fungibles().values().flatten())
+            .map(|value| text(format!("Total {}", prices.format(value))).size(18));
+
+        let header = row![
+            title,
+            widget::Space::new(Length::Fill, 1),
+            total_value,
+            new_account
+        ]
+        .spacing(20)
+        .align_y(iced::Alignment::End)
+        .padding(20);
 
         let accounts = wallet
             .accounts()
@@ -178,6 +189,13 @@ impl<'a> AccountsView {
 
         let name_address_row = row![account_name_widget, space, account_address_widget];
 
+        let prices = wallet.prices();
+        let account_value = wallet
+            .fungibles()
+            .get(&account.address)
+            .and_then(|fungibles| prices.total_value(fungibles))
+            .map(|value| text(prices.format(value)).size(16));
+
         let mut icons: Vec<Element<AppMessage>> = Vec::new();
 
         let fungibles = wallet
@@ -235,7 +253,10 @@ impl<'a> AccountsView {
 
         let space = iced::widget::Space::new(Length::Fill, Length::Fill);
 
-        let columns = column![name_address_row, icons].spacing(20);
+        let icons_and_value = row![icons, widget::Space::new(Length::Fill, 1), account_value]
+            .align_y(iced::Alignment::Center);
+
+        let columns = column![name_address_row, icons_and_value].spacing(20);
 
         let button = widget::button(columns)
             .height(100)
@@ -0,0 +1,209 @@
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use deps::*;
+
+use iced::{
+    Element, Length, Task,
+    widget::{self, column, container, row, text},
+};
+use types::{
+    BalanceSnapshot, HistoryPoint, TimeStamp,
+    address::{AccountAddress, ResourceAddress},
+    balance_history, value_history,
+};
+use wallet::{Unlocked, Wallet};
+use widgets::LineChart;
+
+use crate::{app::AppMessage, styles, unlocked::app_view};
+
+use super::{account_view, accounts_view, history::History};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SnapshotsLoaded(Result<Vec<BalanceSnapshot>, String>),
+    SelectSeries(Series),
+}
+
+impl Into<AppMessage> for Message {
+    fn into(self) -> AppMessage {
+        AppMessage::AppView(app_view::Message::AccountsViewMessage(
+            accounts_view::Message::AccountViewMessage(account_view::Message::ChartMessage(self)),
+        ))
+    }
+}
+
+/// What the chart shows, `None` is the value of the account
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub resource: Option<ResourceAddress>,
+    pub label: String,
+}
+
+impl Display for Series {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// The value or the balance of one resource of the account over time,
+/// recorded by the snapshots taken on every sync
+#[derive(Debug, Clone)]
+pub struct BalanceChart {
+    pub account_addr: AccountAddress,
+    pub resource: Option<ResourceAddress>,
+    /// `None` while the snapshots are loading
+    snapshots: Option<Result<Vec<BalanceSnapshot>, String>>,
+}
+
+impl<'a> BalanceChart {
+    pub fn new(account_addr: AccountAddress) -> Self {
+        Self {
+            account_addr,
+            resource: None,
+            snapshots: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<AppMessage> {
+        match message {
+            Message::SnapshotsLoaded(snapshots) => self.snapshots = Some(snapshots),
+            Message::SelectSeries(series) => self.resource = series.resource,
+        }
+        Task::none()
+    }
+
+    pub fn load_snapshots(&mut self, wallet: &Wallet<Unlocked>) -> Task<AppMessage> {
+        self.snapshots = None;
+
+        Task::perform(
+            wallet.balance_snapshots(self.account_addr.clone()),
+            |result| {
+                let snapshots = result
+                    .map_err(|err| err.to_string())
+                    .and_then(|snapshots| snapshots.map_err(|err| err.to_string()));
+                Message::SnapshotsLoaded(snapshots).into()
+            },
+        )
+    }
+
+    pub fn view(&'a self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let snapshots = match &self.snapshots {
+            None => return Self::centered_text("Loading..."),
+            Some(Err(error)) => return Self::centered_text(error),
+            Some(Ok(snapshots)) => snapshots,
+        };
+
+        let prices = wallet.prices();
+        let history = match &self.resource {
+            Some(resource_address) => balance_history(snapshots, resource_address),
+            None => value_history(snapshots, prices),
+        };
+
+        let chart: Element<'a, AppMessage> = if history.is_empty() {
+            let empty = match &self.resource {
+                None if prices.prices.is_empty() => {
+                    "No prices, set a price source to see the value of the account"
+                }
+                _ => "No balance history yet, it is recorded every time the account is synced",
+            };
+            Self::centered_text(empty)
+        } else {
+            let currency = prices.currency.clone();
+            let value_is_currency = self.resource.is_none();
+            LineChart::new(Self::held_until_now(history))
+                .format_x(|timestamp| {
+                    let date = TimeStamp::from_unix_seconds(timestamp as u64);
+                    format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day())
+                })
+                .format_y(move |value| {
+                    if value_is_currency {
+                        format!("{value:.2} {currency}")
+                    } else {
+                        format!("{value}")
+                    }
+                })
+                .height(Length::Fill)
+                .into()
+        };
+
+        let series_row = row![
+            text("Show").size(12),
+            self.series_picker(wallet),
+            widget::Space::new(Length::Fill, 1),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        column![series_row, container(chart).padding(10)]
+            .spacing(10)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// The value of the last point is held until now
+    fn held_until_now(history: Vec<HistoryPoint>) -> Vec<(f64, f64)> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+
+        let last = history.last().copied();
+        history
+            .into_iter()
+            .chain(
+                last.filter(|last| last.timestamp < now)
+                    .map(|last| HistoryPoint {
+                        timestamp: now,
+                        ..last
+                    }),
+            )
+            .map(|point| (point.timestamp as f64, point.value))
+            .collect()
+    }
+
+    /// The value of the account and every fungible it holds
+    fn series_picker(&'a self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let value = Series {
+            resource: None,
+            label: format!("Value ({})", wallet.prices().currency),
+        };
+
+        let options = std::iter::once(value.clone())
+            .chain(
+                wallet
+                    .fungibles()
+                    .get(&self.account_addr)
+                    .into_iter()
+                    .flatten()
+                    .map(|fungible| Series {
+                        resource: Some(fungible.resource_address.clone()),
+                        label: History::resource_label(&fungible.resource_address, wallet),
+                    }),
+            )
+            .collect::<Vec<_>>();
+
+        let selected = options
+            .iter()
+            .find(|option| option.resource == self.resource)
+            .cloned()
+            .unwrap_or(value);
+
+        widget::pick_list(options, Some(selected), |series| {
+            Message::SelectSeries(series).into()
+        })
+        .text_size(12)
+        .menu_style(styles::menu::primary)
+        .style(styles::pick_list::from_account)
+        .into()
+    }
+
+    fn centered_text(content: &'a str) -> Element<'a, AppMessage> {
+        container(text(content))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into()
+    }
+}
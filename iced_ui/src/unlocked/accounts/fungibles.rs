@@ -180,11 +180,23 @@ impl<'a> Fungibles {
             .spacing(3)
             .align_x(iced::Alignment::Start);
 
+        let prices = wallet.prices();
+        let value_widget = prices
+            .value(&fungible.resource_address, &fungible.amount)
+            .map(|value| text(prices.format(value)).size(14));
+
+        let amount_and_value = column![
+            text(format!("{} {}", &fungible.amount, symbol.unwrap_or(""))).size(18),
+            value_widget
+        ]
+        .spacing(3)
+        .align_x(iced::Alignment::End);
+
         let list_button_content = row![
             icon,
             name_and_symbol,
             widget::Space::new(Length::Fill, 1),
-            text(format!("{} {}", &fungible.amount, symbol.unwrap_or(""))).size(18)
+            amount_and_value
         ]
         .padding(Padding {
             left: 10.,
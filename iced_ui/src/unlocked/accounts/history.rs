@@ -0,0 +1,493 @@
+use std::{fmt::Display, path::PathBuf};
+
+use deps::*;
+
+use font_and_icons::{BOOTSTRAP_FONT, Bootstrap};
+use handles::transaction_export::{self, ExportFormat};
+use iced::{
+    Element, Length, Padding, Task,
+    widget::{self, Button, button, column, container, image::Handle, row, text},
+};
+use store::{AppDataDb, DbError};
+use types::{
+    BalanceChange, TimeStamp, Transaction, TransactionStatus,
+    address::{AccountAddress, Address, ResourceAddress},
+};
+use wallet::{Unlocked, Wallet};
+
+use crate::{app::AppMessage, common, styles, unlocked::app_view};
+
+use super::{account_view, accounts_view};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TransactionsLoaded(Vec<Transaction>),
+    LoadFailed(String),
+    NextPage,
+    PreviousPage,
+    FilterResource(ResourceFilter),
+    SelectExportRange(ExportRange),
+    ToggleExportAllAccounts(bool),
+    Export(ExportFormat),
+    Exported(Result<PathBuf, String>),
+}
+
+impl Into<AppMessage> for Message {
+    fn into(self) -> AppMessage {
+        AppMessage::AppView(app_view::Message::AccountsViewMessage(
+            accounts_view::Message::AccountViewMessage(account_view::Message::HistoryMessage(self)),
+        ))
+    }
+}
+
+/// An entry in the resource filter, `None` shows transactions for all resources
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceFilter {
+    pub resource: Option<ResourceAddress>,
+    pub label: String,
+}
+
+impl Display for ResourceFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRange {
+    ThisYear,
+    LastYear,
+    AllTime,
+}
+
+impl ExportRange {
+    const ALL: [ExportRange; 3] = [Self::ThisYear, Self::LastYear, Self::AllTime];
+
+    /// The first and last second of the range in UTC
+    fn bounds(&self) -> (TimeStamp, TimeStamp) {
+        let whole_year = |year: u16| {
+            (
+                TimeStamp::new(year, 1, 1, 0, 0, 0),
+                TimeStamp::new(year, 12, 31, 23, 59, 59),
+            )
+        };
+        let current_year = TimeStamp::now().year();
+
+        match self {
+            Self::ThisYear => whole_year(current_year),
+            Self::LastYear => whole_year(current_year - 1),
+            Self::AllTime => (
+                TimeStamp::new(0, 1, 1, 0, 0, 0),
+                TimeStamp::new(9999, 12, 31, 23, 59, 59),
+            ),
+        }
+    }
+}
+
+impl Display for ExportRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ThisYear => write!(f, "This year"),
+            Self::LastYear => write!(f, "Last year"),
+            Self::AllTime => write!(f, "All time"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    pub account_addr: AccountAddress,
+    pub page: usize,
+    pub filter: Option<ResourceAddress>,
+    pub export_range: ExportRange,
+    /// Export the transactions of every account in the wallet instead of only this account
+    pub export_all_accounts: bool,
+    exporting: bool,
+    /// `None` while the page is loading
+    transactions: Option<Result<Vec<Transaction>, String>>,
+}
+
+impl<'a> History {
+    pub const PAGE_SIZE: usize = 20;
+
+    pub fn new(account_addr: AccountAddress) -> Self {
+        Self {
+            account_addr,
+            page: 0,
+            filter: None,
+            export_range: ExportRange::ThisYear,
+            export_all_accounts: false,
+            exporting: false,
+            transactions: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message, wallet: &Wallet<Unlocked>) -> Task<AppMessage> {
+        match message {
+            Message::TransactionsLoaded(transactions) => self.transactions = Some(Ok(transactions)),
+            Message::LoadFailed(error) => self.transactions = Some(Err(error)),
+            Message::NextPage => {
+                if self.has_next_page() {
+                    self.page += 1;
+                    return self.load_page(wallet);
+                }
+            }
+            Message::PreviousPage => {
+                if self.page > 0 {
+                    self.page -= 1;
+                    return self.load_page(wallet);
+                }
+            }
+            Message::FilterResource(filter) => {
+                self.filter = filter.resource;
+                self.page = 0;
+                return self.load_page(wallet);
+            }
+            Message::SelectExportRange(export_range) => self.export_range = export_range,
+            Message::ToggleExportAllAccounts(all_accounts) => {
+                self.export_all_accounts = all_accounts
+            }
+            Message::Export(format) => return self.export(format, wallet),
+            Message::Exported(result) => {
+                self.exporting = false;
+                let notification = match result {
+                    Ok(path) => format!("Transactions exported to {}", path.display()),
+                    Err(err) => format!("Export failed: {err}"),
+                };
+                return Task::perform(async {}, move |_| {
+                    common::Message::Notify(notification).into()
+                });
+            }
+        }
+        Task::none()
+    }
+
+    /// Exports every transaction of the account, or of all accounts, in the selected range,
+    /// not only the filtered resource
+    fn export(&mut self, format: ExportFormat, wallet: &Wallet<Unlocked>) -> Task<AppMessage> {
+        if self.exporting {
+            return Task::none();
+        }
+        self.exporting = true;
+
+        let network = wallet.settings().network;
+        let account_address = (!self.export_all_accounts).then(|| self.account_addr.clone());
+        let (from, to) = self.export_range.bounds();
+
+        Task::perform(
+            transaction_export::export_transactions(network, account_address, from, to, format),
+            |result| Message::Exported(result.map_err(|err| err.to_string())).into(),
+        )
+    }
+
+    /// Loads one transaction more than the page size to know if there is a next page
+    pub fn load_page(&mut self, wallet: &Wallet<Unlocked>) -> Task<AppMessage> {
+        self.transactions = None;
+
+        let network = wallet.settings().network;
+        let account_address = self.account_addr.clone();
+        let resource = self.filter.clone();
+        let offset = self.page * Self::PAGE_SIZE;
+
+        Task::perform(
+            async move {
+                let db = AppDataDb::get(network).ok_or(DbError::DatabaseNotLoaded)?;
+                db.get_transactions_page_for_account(
+                    account_address,
+                    resource,
+                    Self::PAGE_SIZE + 1,
+                    offset,
+                )
+                .await
+            },
+            |result| match result {
+                Ok(transactions) => Message::TransactionsLoaded(transactions).into(),
+                Err(err) => Message::LoadFailed(err.to_string()).into(),
+            },
+        )
+    }
+
+    fn has_next_page(&self) -> bool {
+        matches!(&self.transactions, Some(Ok(transactions)) if transactions.len() > Self::PAGE_SIZE)
+    }
+
+    pub fn view(&'a self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let transactions: Element<'a, AppMessage> = match &self.transactions {
+            None => Self::centered_text("Loading..."),
+            Some(Err(error)) => Self::centered_text(error),
+            Some(Ok(transactions)) if transactions.is_empty() => {
+                Self::centered_text("No transactions")
+            }
+            Some(Ok(transactions)) => {
+                let elements = transactions.iter().take(Self::PAGE_SIZE).enumerate().map(
+                    |(i, transaction)| {
+                        let item = container(self.transaction_item(transaction, wallet))
+                            .style(styles::container::base_layer_1);
+
+                        let mut column = column![];
+                        if i != 0 {
+                            column = column.push(widget::Rule::horizontal(2));
+                        }
+                        column.push(item).into()
+                    },
+                );
+
+                let column = column(elements)
+                    .padding(Padding {
+                        right: 15.,
+                        ..Padding::ZERO
+                    })
+                    .width(Length::Fill);
+
+                widget::scrollable(column)
+                    .height(Length::Fill)
+                    .width(Length::Fill)
+                    .style(styles::scrollable::vertical_scrollable_secondary)
+                    .into()
+            }
+        };
+
+        let mut previous = Self::page_button(Bootstrap::ChevronLeft);
+        if self.page > 0 {
+            previous = previous.on_press(Message::PreviousPage.into());
+        }
+
+        let mut next = Self::page_button(Bootstrap::ChevronRight);
+        if self.has_next_page() {
+            next = next.on_press(Message::NextPage.into());
+        }
+
+        let paging = row![
+            previous,
+            text(format!("Page {}", self.page + 1)).size(12),
+            next
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let filter_row = row![
+            self.resource_filter(wallet),
+            widget::Space::new(Length::Fill, 1),
+            self.export_controls()
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        column![
+            filter_row,
+            transactions,
+            container(paging).center_x(Length::Fill)
+        ]
+        .spacing(10)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Lists every resource the account currently holds
+    fn resource_filter(&'a self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let all = ResourceFilter {
+            resource: None,
+            label: "All resources".to_string(),
+        };
+
+        let fungibles = wallet
+            .fungibles()
+            .get(&self.account_addr)
+            .into_iter()
+            .flatten()
+            .map(|fungible| &fungible.resource_address);
+        let non_fungibles = wallet
+            .non_fungibles()
+            .get(&self.account_addr)
+            .into_iter()
+            .flatten()
+            .map(|non_fungible| &non_fungible.resource_address);
+
+        let options = std::iter::once(all.clone())
+            .chain(
+                fungibles
+                    .chain(non_fungibles)
+                    .map(|resource_address| ResourceFilter {
+                        resource: Some(resource_address.clone()),
+                        label: Self::resource_label(resource_address, wallet),
+                    }),
+            )
+            .collect::<Vec<_>>();
+
+        let selected = options
+            .iter()
+            .find(|option| option.resource == self.filter)
+            .cloned()
+            .unwrap_or(all);
+
+        widget::pick_list(options, Some(selected), |filter| {
+            Message::FilterResource(filter).into()
+        })
+        .text_size(12)
+        .menu_style(styles::menu::primary)
+        .style(styles::pick_list::from_account)
+        .into()
+    }
+
+    fn export_controls(&'a self) -> Element<'a, AppMessage> {
+        let range = widget::pick_list(ExportRange::ALL, Some(self.export_range), |range| {
+            Message::SelectExportRange(range).into()
+        })
+        .text_size(12)
+        .menu_style(styles::menu::primary)
+        .style(styles::pick_list::from_account);
+
+        let export_button = |format: ExportFormat| {
+            let mut button = button(text(format!("Export {format}")).size(12))
+                .style(styles::button::base_layer_2);
+            if !self.exporting {
+                button = button.on_press(Message::Export(format).into());
+            }
+            button
+        };
+
+        let all_accounts =
+            widget::checkbox("All accounts", self.export_all_accounts)
+                .text_size(12)
+                .on_toggle_maybe((!self.exporting).then_some(|all_accounts| {
+                    Message::ToggleExportAllAccounts(all_accounts).into()
+                }));
+
+        row![
+            range,
+            all_accounts,
+            export_button(ExportFormat::Csv),
+            export_button(ExportFormat::JsonLines)
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn transaction_item(
+        &'a self,
+        transaction: &'a Transaction,
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Element<'a, AppMessage> {
+        let timestamp = &transaction.timestamp;
+        let timestamp = text(format!(
+            "{}-{:02}-{:02} {:02}:{:02}",
+            timestamp.year(),
+            timestamp.month(),
+            timestamp.day(),
+            timestamp.hour(),
+            timestamp.minute()
+        ))
+        .size(12)
+        .style(styles::text::muted);
+
+        let status = match transaction.status {
+            TransactionStatus::Success => text(""),
+            TransactionStatus::Failed => text("Failed").size(12).style(styles::text::error),
+            TransactionStatus::Pending => text("Pending").size(12).style(styles::text::warning),
+        };
+
+        let header = row![timestamp, widget::Space::new(Length::Fill, 1), status]
+            .align_y(iced::Alignment::Center);
+
+        let balance_changes = column(
+            transaction
+                .balance_changes
+                .iter()
+                .map(|balance_change| Self::balance_change(balance_change, wallet)),
+        )
+        .spacing(5);
+
+        let mut column = column![header, balance_changes].spacing(5);
+        if let Some(message) = &transaction.message {
+            column = column.push(text(message).size(12));
+        }
+
+        // Custom networks without a dashboard have nowhere to link to
+        if let Some(dashboard_url) = wallet.settings().network.dashboard_url() {
+            let url = format!(
+                "{}/transaction/{}",
+                dashboard_url,
+                transaction.transaction_address.as_str()
+            );
+            let link = row![
+                text(url.clone()).size(11).style(styles::text::muted),
+                text(Bootstrap::Copy).font(BOOTSTRAP_FONT).size(11)
+            ]
+            .spacing(5);
+            let link = button(link)
+                .padding(0)
+                .style(button::text)
+                .on_press(common::Message::CopyToClipBoard(url).into());
+            column = column.push(link);
+        }
+
+        column
+            .padding(Padding {
+                left: 10.,
+                right: 10.,
+                bottom: 5.,
+                top: 5.,
+            })
+            .into()
+    }
+
+    fn balance_change(
+        balance_change: &'a BalanceChange,
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Element<'a, AppMessage> {
+        let icon: Element<'a, AppMessage> =
+            match wallet.resource_icons().get(&balance_change.resource) {
+                Some(bytes) => widget::image(Handle::from_bytes(bytes.clone()))
+                    .width(20)
+                    .height(20)
+                    .into(),
+                None => container(text(Bootstrap::Image).font(BOOTSTRAP_FONT).size(15))
+                    .center_x(20)
+                    .center_y(20)
+                    .into(),
+            };
+
+        let amount = balance_change.amount.as_deref().unwrap_or("0");
+        let amount = if amount.starts_with('-') {
+            amount.to_string()
+        } else {
+            format!("+{amount}")
+        };
+        let amount = match balance_change.nfts {
+            Some(_) => format!("{amount} NFT(s)"),
+            None => amount,
+        };
+
+        row![
+            icon,
+            text(Self::resource_label(&balance_change.resource, wallet)).size(14),
+            widget::Space::new(Length::Fill, 1),
+            text(amount).size(14)
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// The symbol of the resource, or the name if it has no symbol
+    pub(crate) fn resource_label(resource_address: &ResourceAddress, wallet: &'a Wallet<Unlocked>) -> String {
+        match wallet.resources().get(resource_address) {
+            Some(resource) if !resource.symbol.is_empty() => resource.symbol.clone(),
+            Some(resource) if !resource.name.is_empty() => resource.name.clone(),
+            _ => resource_address.truncate(),
+        }
+    }
+
+    fn page_button(icon: Bootstrap) -> Button<'a, AppMessage> {
+        button(text(icon).font(BOOTSTRAP_FONT).size(12)).style(styles::button::base_layer_2)
+    }
+
+    fn centered_text(content: &'a str) -> Element<'a, AppMessage> {
+        container(text(content))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into()
+    }
+}
@@ -1,7 +1,9 @@
 pub mod account_view;
 pub mod accounts_view;
+pub mod balance_chart;
 pub mod fungible;
 pub mod fungibles;
+pub mod history;
 pub mod non_fungible;
 pub mod non_fungibles;
 
@@ -1,12 +1,29 @@
 use deps::*;
 
 use font_and_icons::{images::MENU_LOGO, Bootstrap, BOOTSTRAP_FONT};
+use handles::radix_dlt::gateway_requests::RadixGateway;
 use iced::{
     widget::{self, button, image::Handle, row, text, Row, Text},
     Element, Length, Task,
 };
-use std::{collections::HashMap, str::FromStr};
-use types::{address::ResourceAddress, Account, Decimal, RadixDecimal};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use store::DataBase;
+use types::{
+    address::{AccountAddress, Address, ResourceAddress, TransactionAddress},
+    collections::{AccountsUpdate, UpdateProgress},
+    crypto::Key,
+    Account, AppError, Decimal, Notification, PriceTable, RadixDecimal, TransactionStatus,
+};
 use wallet::{Unlocked, Wallet};
 
 use crate::{app::AppMessage, styles, App};
@@ -15,6 +32,8 @@ use super::{
     accounts::{self, accounts_view::AccountsView},
     overlays::{
         add_account::AddAccount,
+        change_password::ChangePassword,
+        export_backup::ExportBackup,
         overlay::{self, Overlay, SpawnOverlay},
         receive::Receive,
     },
@@ -30,6 +49,21 @@ pub enum Message {
     SpawnOverlay(SpawnOverlay),
     CloseOverlay,
     OverlayMessage(overlay::Message),
+    PollPendingTransactions,
+    PendingTransactionsPolled(Result<Vec<(TransactionAddress, TransactionStatus)>, AppError>),
+    AccountsUpdated(AccountsUpdate),
+    /// An account has been updated by the background refresh
+    RefreshProgress(UpdateProgress),
+    /// The background refresh has finished, failures are shown in the sync status
+    AccountsRefreshed(AccountsUpdate),
+    /// Checks if the gateway can be reached again, sent periodically while offline
+    CheckGateway,
+    /// `true` if the gateway could be reached
+    GatewayChecked(bool),
+    PricesUpdated(Result<PriceTable, AppError>),
+    /// The new database key, handled here as the overlay can be closed while the password changes
+    PasswordChanged(Result<Key<DataBase>, AppError>),
+    SetRefreshInterval(RefreshInterval),
 }
 
 impl Into<AppMessage> for Message {
@@ -55,17 +89,107 @@ pub struct AppView {
     pub notification: Option<String>,
     pub active_tab: ActiveTab,
     pub overlay: Option<Overlay>,
+    polling_transactions: bool,
+    sync_status: SyncStatus,
+    refresh_in_flight: RefreshInFlight,
+}
+
+/// The state of the background refresh of the accounts
+#[derive(Debug, Default)]
+struct SyncStatus {
+    /// Accounts updated and the total number of accounts, while a refresh is running
+    progress: Option<(usize, usize)>,
+    /// Accounts that could not be updated by the last refresh
+    failed_accounts: HashSet<AccountAddress>,
+    /// The gateway could not be reached, the data shown is the data saved on disk
+    /// and might be out of date
+    offline: bool,
+    /// A refresh was requested while offline or while another refresh was running,
+    /// it runs once the gateway can be reached again or the running refresh has finished
+    refresh_queued: bool,
+}
+
+/// Set while the accounts are fetched. Shared by the background refresh and the refreshes
+/// started by the app view, so only one of them runs at a time
+#[derive(Debug, Clone, Default)]
+pub struct RefreshInFlight(Arc<AtomicBool>);
+
+impl RefreshInFlight {
+    /// `None` if a refresh is already running, otherwise the refresh counts as running
+    /// until the returned guard is dropped
+    pub fn start(&self) -> Option<RefreshGuard> {
+        (!self.0.swap(true, Ordering::AcqRel)).then(|| RefreshGuard(self.0.clone()))
+    }
+}
+
+/// The flag itself is hashed, so the background refresh keeps running while the flag is the same
+impl Hash for RefreshInFlight {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+pub struct RefreshGuard(Arc<AtomicBool>);
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// An interval of the background refresh, `None` turns the refresh off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshInterval(Option<Duration>);
+
+impl RefreshInterval {
+    const ALL: [RefreshInterval; 5] = [
+        Self(None),
+        Self(Some(Duration::from_secs(60))),
+        Self(Some(Duration::from_secs(5 * 60))),
+        Self(Some(Duration::from_secs(15 * 60))),
+        Self(Some(Duration::from_secs(60 * 60))),
+    ];
+}
+
+impl Display for RefreshInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.map(|interval| interval.as_secs()) {
+            None => write!(f, "Refresh off"),
+            Some(secs) if secs % 3600 == 0 => write!(f, "Refresh every {} h", secs / 3600),
+            Some(secs) if secs % 60 == 0 => write!(f, "Refresh every {} min", secs / 60),
+            Some(secs) => write!(f, "Refresh every {secs} s"),
+        }
+    }
 }
 
 impl<'a> AppView {
+    /// How often the gateway is asked for the status of submitted transactions
+    pub const PENDING_TRANSACTIONS_POLL_INTERVAL: Duration = Duration::from_secs(3);
+    /// The longest time between background refreshes while the gateway can not be reached
+    pub const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+    /// How often the gateway is checked while offline
+    pub const GATEWAY_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
     pub fn new() -> Self {
         Self {
             notification: None,
             active_tab: ActiveTab::Accounts(AccountsView::new()),
             overlay: None,
+            polling_transactions: false,
+            sync_status: SyncStatus::default(),
+            refresh_in_flight: RefreshInFlight::default(),
         }
     }
 
+    pub fn refresh_in_flight(&self) -> RefreshInFlight {
+        self.refresh_in_flight.clone()
+    }
+
+    /// Network only actions, like sending, are disabled while offline
+    pub fn is_offline(&self) -> bool {
+        self.sync_status.offline
+    }
+
     pub fn update(&mut self, message: Message, wallet: &mut Wallet<Unlocked>) -> Task<AppMessage> {
         match message {
             Message::SelectTab(tab_id) => self.select_tab(tab_id),
@@ -86,24 +210,268 @@ impl<'a> AppView {
                     self.overlay = Some(Overlay::AddAccount(add_account_view));
                     return task;
                 }
+                SpawnOverlay::ChangePassword => {
+                    self.overlay = Some(Overlay::ChangePassword(ChangePassword::new()))
+                }
+                SpawnOverlay::ExportBackup => {
+                    self.overlay = Some(Overlay::ExportBackup(ExportBackup::new()))
+                }
                 SpawnOverlay::Receive(account_address) => {
                     self.overlay = Some(Overlay::Receive(Receive::new(account_address)))
                 }
             },
             Message::CloseOverlay => self.overlay = None,
+            Message::PasswordChanged(result) => return self.password_changed(result, wallet),
             Message::OverlayMessage(overlay_message) => {
                 if let Some(overlay) = &mut self.overlay {
                     return overlay.update(overlay_message, wallet);
                 }
             }
+            Message::PollPendingTransactions => return self.poll_pending_transactions(wallet),
+            Message::PendingTransactionsPolled(result) => {
+                return self.pending_transactions_polled(result, wallet);
+            }
+            Message::AccountsUpdated(accounts_update) => {
+                let offline = accounts_update.every_account_failed();
+                let update_incomplete = !accounts_update.failures.is_empty();
+                let handle = wallet.apply_accounts_update(accounts_update);
+                let task = Task::perform(handle, |result| match result {
+                    Ok(Err(err)) => AppMessage::Error(err),
+                    _ => AppMessage::None,
+                });
+                let task = Task::batch([
+                    task,
+                    self.refresh_finished(offline, wallet),
+                    self.update_prices(wallet),
+                ]);
+                if !update_incomplete || offline {
+                    return task;
+                }
+
+                let err = AppError::NonFatal(Notification::Warn(
+                    "Some account data could not be updated, it will be retried".to_string(),
+                ));
+                return Task::batch([
+                    task,
+                    Task::perform(async {}, move |_| AppMessage::Error(err)),
+                ]);
+            }
+            Message::RefreshProgress(progress) => self.refresh_progress(progress),
+            Message::AccountsRefreshed(accounts_update) => {
+                self.sync_status.progress = None;
+                let offline = accounts_update.every_account_failed();
+
+                let handle = wallet.apply_accounts_update(accounts_update);
+                return Task::batch([
+                    Task::perform(handle, |result| match result {
+                        Ok(Err(err)) => AppMessage::Error(err),
+                        _ => AppMessage::None,
+                    }),
+                    self.refresh_finished(offline, wallet),
+                    self.update_prices(wallet),
+                ]);
+            }
+            Message::CheckGateway => return self.check_gateway(wallet),
+            Message::GatewayChecked(reachable) => return self.set_offline(!reachable, wallet),
+            Message::PricesUpdated(result) => match result {
+                Ok(price_table) => wallet.apply_price_table(price_table),
+                Err(err) => return Task::perform(async {}, move |_| AppMessage::Error(err)),
+            },
+            Message::SetRefreshInterval(interval) => {
+                return Self::set_refresh_interval(interval, wallet);
+            }
+        }
+
+        Task::none()
+    }
+
+    fn refresh_progress(&mut self, progress: UpdateProgress) {
+        self.sync_status.progress = Some((progress.completed, progress.total));
+        if progress.failures.is_empty() {
+            self.sync_status.failed_accounts.remove(&progress.account);
+        } else {
+            self.sync_status.failed_accounts.insert(progress.account);
+        }
+    }
+
+    /// A refresh queued while this one was running runs now
+    fn refresh_finished(
+        &mut self,
+        offline: bool,
+        wallet: &'a mut Wallet<Unlocked>,
+    ) -> Task<AppMessage> {
+        let task = self.set_offline(offline, wallet);
+        if !offline && std::mem::take(&mut self.sync_status.refresh_queued) {
+            return Task::batch([task, self.refresh_accounts(wallet)]);
+        }
+        task
+    }
+
+    /// The background refresh picks up the new interval right away
+    fn set_refresh_interval(
+        interval: RefreshInterval,
+        wallet: &'a mut Wallet<Unlocked>,
+    ) -> Task<AppMessage> {
+        wallet.settings_mut().set_refresh_interval(interval.0);
+        match wallet.settings().save_to_disk() {
+            Ok(()) => Task::none(),
+            Err(err) => {
+                let err = AppError::NonFatal(Notification::Warn(format!(
+                    "Unable to save settings: {err}"
+                )));
+                Task::perform(async {}, move |_| AppMessage::Error(err))
+            }
+        }
+    }
+
+    /// Going offline queues a refresh, which runs when the gateway can be reached again
+    fn set_offline(&mut self, offline: bool, wallet: &'a mut Wallet<Unlocked>) -> Task<AppMessage> {
+        let reconnected = self.sync_status.offline && !offline;
+        self.sync_status.offline = offline;
+        if offline {
+            self.sync_status.refresh_queued = true;
+        }
+        if let ActiveTab::Transfer(view) = &mut self.active_tab {
+            view.offline = offline;
         }
 
+        if reconnected && std::mem::take(&mut self.sync_status.refresh_queued) {
+            return self.refresh_accounts(wallet);
+        }
         Task::none()
     }
 
+    fn check_gateway(&self, wallet: &'a Wallet<Unlocked>) -> Task<AppMessage> {
+        if !self.sync_status.offline {
+            return Task::none();
+        }
+
+        let gateway = RadixGateway::new(
+            wallet.settings().network,
+            wallet.settings().request_limits(),
+        );
+        Task::perform(async move { gateway.is_reachable().await }, |reachable| {
+            Message::GatewayChecked(reachable).into()
+        })
+    }
+
+    /// Queued while offline or while another refresh is running
+    fn refresh_accounts(&mut self, wallet: &'a mut Wallet<Unlocked>) -> Task<AppMessage> {
+        let in_flight = match self.refresh_in_flight.start() {
+            Some(in_flight) if !self.sync_status.offline => in_flight,
+            _ => {
+                self.sync_status.refresh_queued = true;
+                return Task::none();
+            }
+        };
+
+        let handle = wallet.update_accounts();
+        Task::perform(
+            async move {
+                let result = handle.await;
+                drop(in_flight);
+                result
+            },
+            |result| match result {
+                Ok(accounts_update) => Message::AccountsUpdated(accounts_update).into(),
+                Err(_) => AppMessage::None,
+            },
+        )
+    }
+
+    /// The prices are only updated while online and if a price source is set
+    fn update_prices(&self, wallet: &Wallet<Unlocked>) -> Task<AppMessage> {
+        if self.sync_status.offline {
+            return Task::none();
+        }
+
+        match wallet.update_prices() {
+            Some(handle) => Task::perform(handle, |result| {
+                let result = result.unwrap_or_else(|err| {
+                    Err(AppError::NonFatal(Notification::Warn(err.to_string())))
+                });
+                Message::PricesUpdated(result).into()
+            }),
+            None => Task::none(),
+        }
+    }
+
+    fn poll_pending_transactions(&mut self, wallet: &'a mut Wallet<Unlocked>) -> Task<AppMessage> {
+        if self.polling_transactions
+            || self.sync_status.offline
+            || wallet.pending_transactions().is_empty()
+        {
+            return Task::none();
+        }
+        self.polling_transactions = true;
+
+        Task::perform(wallet.poll_pending_transactions(), |result| {
+            let result = result.unwrap_or_else(|err| {
+                Err(AppError::NonFatal(Notification::Warn(err.to_string())))
+            });
+            Message::PendingTransactionsPolled(result).into()
+        })
+    }
+
+    /// Removes the transactions that are no longer pending and refreshes the accounts
+    /// so the balances reflect the committed transactions
+    fn pending_transactions_polled(
+        &mut self,
+        result: Result<Vec<(TransactionAddress, TransactionStatus)>, AppError>,
+        wallet: &'a mut Wallet<Unlocked>,
+    ) -> Task<AppMessage> {
+        self.polling_transactions = false;
+
+        let resolved = match result {
+            Ok(resolved) if resolved.is_empty() => return Task::none(),
+            Ok(resolved) => resolved,
+            Err(err) => return Task::perform(async {}, move |_| AppMessage::Error(err)),
+        };
+
+        wallet.remove_pending_transactions(&resolved);
+
+        let notification = resolved
+            .iter()
+            .map(|(transaction_address, status)| match status {
+                TransactionStatus::Success => {
+                    format!("Transaction {} succeeded", transaction_address.truncate())
+                }
+                _ => format!("Transaction {} failed", transaction_address.truncate()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.notification = Some(notification);
+
+        self.refresh_accounts(wallet)
+    }
+
+    /// Switches the wallet to the new key, also if the overlay was closed in the meantime
+    fn password_changed(
+        &mut self,
+        result: Result<Key<DataBase>, AppError>,
+        wallet: &'a mut Wallet<Unlocked>,
+    ) -> Task<AppMessage> {
+        let overlay_result = match &mut self.overlay {
+            Some(Overlay::ChangePassword(change_password)) => {
+                change_password.password_changed(&result)
+            }
+            _ => result.as_ref().map(|_| ()).map_err(Clone::clone),
+        };
+
+        if let Ok(key) = result {
+            wallet.apply_password_change(key);
+        }
+
+        match overlay_result {
+            Ok(()) => Task::none(),
+            Err(err) => Task::perform(async {}, move |_| AppMessage::Error(err)),
+        }
+    }
+
     fn select_tab(&mut self, tab_id: TabId) {
         match tab_id {
             TabId::Accounts => self.active_tab = ActiveTab::Accounts(accounts::AccountsView::new()),
+            TabId::Transfer if self.sync_status.offline => {}
             TabId::Transfer => {
                 self.active_tab = ActiveTab::Transfer(CreateTransaction::new(None, None))
             }
@@ -111,6 +479,10 @@ impl<'a> AppView {
     }
 
     fn new_transaction(&mut self, from_account: Option<Account>, wallet: &'a mut Wallet<Unlocked>) {
+        if self.sync_status.offline {
+            return;
+        }
+
         match from_account {
             Some(ref account) => {
                 let asset_amounts =
@@ -158,16 +530,18 @@ impl<'a> AppView {
             .width(Length::Fill)
             .height(Length::Fill);
 
-        let panels: Element<'a, AppMessage>;
+        let mut panels = widget::Column::new();
 
-        if let Some(notification) = &self.notification {
-            let notification_widget = Self::notification_widget(notification);
+        if self.sync_status.offline {
+            panels = panels.push(Self::offline_banner());
+        }
 
-            panels = widget::column![notification_widget, menu_center_row].into()
-        } else {
-            panels = menu_center_row.into()
+        if let Some(notification) = &self.notification {
+            panels = panels.push(Self::notification_widget(notification));
         }
 
+        let panels = panels.push(menu_center_row);
+
         let appview = widget::container(panels).style(styles::container::main_window);
 
         let overlay = self
@@ -212,6 +586,9 @@ impl<'a> AppView {
             _ => Message::SelectTab(TabId::Transfer).into(),
         };
         let mut transaction_button = Self::menu_button(transaction_icon, "Send", message);
+        if self.sync_status.offline {
+            transaction_button = transaction_button.on_press_maybe(None);
+        }
 
         match self.active_tab {
             ActiveTab::Accounts(_) => {
@@ -222,16 +599,49 @@ impl<'a> AppView {
             }
         }
 
-        let buttons = widget::column![
+        let password_icon = text(Bootstrap::Key).font(BOOTSTRAP_FONT);
+        let password_button = Self::menu_button(
+            password_icon,
+            "Password",
+            Message::SpawnOverlay(SpawnOverlay::ChangePassword).into(),
+        );
+
+        let backup_icon = text(Bootstrap::Safe).font(BOOTSTRAP_FONT);
+        let backup_button = Self::menu_button(
+            backup_icon,
+            "Backup",
+            Message::SpawnOverlay(SpawnOverlay::ExportBackup).into(),
+        );
+
+        let mut buttons = widget::column![
             logo_container,
             toggle_theme_button,
             accounts_button,
-            transaction_button
-        ]
-        .width(Length::Fill)
-        .height(Length::Shrink)
-        .spacing(5)
-        .padding(15);
+            transaction_button,
+            password_button,
+            backup_button
+        ];
+
+        let pending = wallet.pending_transactions().len();
+        if pending > 0 {
+            let hourglass = text(Bootstrap::Hourglass).font(BOOTSTRAP_FONT).size(12);
+            let pending_text = text(format!("{pending} pending transaction(s)")).size(12);
+            buttons = buttons.push(
+                row![hourglass, pending_text]
+                    .spacing(10)
+                    .padding([0, 10])
+                    .align_y(iced::Alignment::Center),
+            );
+        }
+
+        buttons = buttons.push(self.sync_status(wallet));
+        buttons = buttons.push(Self::refresh_interval(wallet));
+
+        let buttons = buttons
+            .width(Length::Fill)
+            .height(Length::Shrink)
+            .spacing(5)
+            .padding(15);
 
         let scrollable = widget::scrollable(buttons).height(Length::Shrink);
 
@@ -242,6 +652,53 @@ impl<'a> AppView {
             .into()
     }
 
+    /// Shows the progress of a running refresh, otherwise the ledger state
+    /// every account is synced up to
+    fn sync_status(&self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let status = match self.sync_status.progress {
+            Some((completed, total)) => format!("Syncing accounts {completed}/{total}"),
+            None if self.sync_status.offline => "Offline, showing saved data".to_string(),
+            None => match wallet
+                .accounts()
+                .values()
+                .filter_map(|account| account.balances_last_updated)
+                .min()
+            {
+                Some(state_version) => format!("Last synced at ledger state {state_version}"),
+                None => "Not synced yet".to_string(),
+            },
+        };
+
+        let icon = text(Bootstrap::ArrowRepeat).font(BOOTSTRAP_FONT).size(12);
+        let mut status = widget::column![
+            row![icon, text(status).size(12)]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+        ];
+
+        let failed = self.sync_status.failed_accounts.len();
+        if failed > 0 && !self.sync_status.offline {
+            status = status.push(text(format!("{failed} account(s) could not be synced")).size(12));
+        }
+
+        status.spacing(2).padding([0, 10]).into()
+    }
+
+    fn refresh_interval(wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let selected = RefreshInterval(wallet.settings().refresh_interval());
+
+        widget::container(
+            widget::pick_list(RefreshInterval::ALL, Some(selected), |interval| {
+                Message::SetRefreshInterval(interval).into()
+            })
+            .text_size(12)
+            .menu_style(styles::menu::primary)
+            .style(styles::pick_list::from_account),
+        )
+        .padding([0, 10])
+        .into()
+    }
+
     fn menu_button(
         icon: Text<'a>,
         name: &'a str,
@@ -264,6 +721,24 @@ impl<'a> AppView {
             .on_press(message)
     }
 
+    fn offline_banner() -> widget::Container<'a, AppMessage> {
+        let icon = text(Bootstrap::WifiOff).font(BOOTSTRAP_FONT);
+        let text = text(
+            "The gateway can not be reached. Balances and transactions might be out of date \
+            and sending is disabled until the connection is back",
+        )
+        .size(12);
+
+        widget::container(
+            row![icon, text]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(5)
+        .style(styles::container::notification_error)
+    }
+
     fn notification_widget(content: &'a str) -> Row<'a, AppMessage> {
         let text = text(content).size(12).line_height(2.);
 
@@ -0,0 +1,224 @@
+use deps::*;
+
+use font_and_icons::{BOOTSTRAP_FONT, Bootstrap};
+use iced::{
+    Element, Length, Task,
+    widget::{self, button, column, container, text},
+};
+use store::DataBase;
+use types::{
+    AppError, Notification,
+    crypto::{Key, Password},
+};
+use wallet::{Unlocked, Wallet};
+use zeroize::Zeroize;
+
+use crate::{
+    app::AppMessage,
+    components::{self, password_input::password_input},
+    styles,
+    unlocked::app_view,
+};
+
+use super::overlay;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputCurrentPassword(String),
+    InputNewPassword(String),
+    InputConfirmPassword(String),
+    ToggleShowPasswords,
+    Submit,
+}
+
+impl Into<AppMessage> for Message {
+    fn into(self) -> AppMessage {
+        AppMessage::AppView(app_view::Message::OverlayMessage(
+            overlay::Message::ChangePasswordMessage(self),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Input,
+    /// The databases are being re-encrypted
+    Changing,
+    Changed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangePassword {
+    notification: Notification,
+    current_password: Password,
+    new_password: Password,
+    confirm_password: Password,
+    show_passwords: bool,
+    status: Status,
+}
+
+impl<'a> ChangePassword {
+    pub fn new() -> Self {
+        Self {
+            notification: Notification::None,
+            current_password: Password::new(),
+            new_password: Password::new(),
+            confirm_password: Password::new(),
+            show_passwords: false,
+            status: Status::Input,
+        }
+    }
+
+    pub fn update(&mut self, message: Message, wallet: &mut Wallet<Unlocked>) -> Task<AppMessage> {
+        match message {
+            Message::InputCurrentPassword(input) => {
+                Self::input_password(&mut self.current_password, input)
+            }
+            Message::InputNewPassword(input) => Self::input_password(&mut self.new_password, input),
+            Message::InputConfirmPassword(input) => {
+                Self::input_password(&mut self.confirm_password, input)
+            }
+            Message::ToggleShowPasswords => self.show_passwords = !self.show_passwords,
+            Message::Submit => return self.submit(wallet),
+        }
+        if self.status == Status::Input {
+            self.notification = Notification::None;
+        }
+        Task::none()
+    }
+
+    fn input_password(password: &mut Password, mut input: String) {
+        password.replace(&input);
+        input.zeroize();
+    }
+
+    fn submit(&mut self, wallet: &mut Wallet<Unlocked>) -> Task<AppMessage> {
+        if self.status != Status::Input {
+            return Task::none();
+        }
+        if self.new_password.len() < Password::MIN_LEN {
+            self.notification = Notification::Info(format!(
+                "The new password needs to be at least {} characters long",
+                Password::MIN_LEN
+            ));
+            return Task::none();
+        }
+        if self.new_password.as_str() != self.confirm_password.as_str() {
+            self.notification = Notification::Info("Passwords do not match".to_string());
+            return Task::none();
+        }
+
+        self.status = Status::Changing;
+        self.notification = Notification::Info("Re-encrypting the wallet...".to_string());
+
+        let handle = wallet.change_password(
+            std::mem::take(&mut self.current_password),
+            self.new_password.clone(),
+        );
+        self.new_password.clear();
+        self.confirm_password.clear();
+
+        Task::perform(handle, |result| {
+            let result = result
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+                .and_then(|result| result);
+            app_view::Message::PasswordChanged(result).into()
+        })
+    }
+
+    /// Fatal errors are returned to be handled by the app
+    pub fn password_changed(
+        &mut self,
+        result: &Result<Key<DataBase>, AppError>,
+    ) -> Result<(), AppError> {
+        match result {
+            Ok(_) => {
+                self.status = Status::Changed;
+                self.notification = Notification::Success("Password changed".to_string());
+            }
+            Err(AppError::NonFatal(notification)) => {
+                self.status = Status::Input;
+                self.notification = notification.clone();
+            }
+            Err(err) => return Err(err.clone()),
+        }
+        Ok(())
+    }
+
+    pub fn view(&'a self) -> Element<'a, AppMessage> {
+        let close = button(text(Bootstrap::XLg).font(BOOTSTRAP_FONT).size(18))
+            .on_press_maybe(
+                (self.status != Status::Changing).then(|| app_view::Message::CloseOverlay.into()),
+            )
+            .style(button::text);
+        let close = container(close)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right);
+
+        let header = text("Change password")
+            .size(16)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let info = text(
+            "The recovery phrase and the data of every network are encrypted again \
+            with the new password",
+        )
+        .size(12);
+
+        let notification = components::notification::notification(&self.notification);
+
+        let inputs = column![
+            password_input::<AppMessage>(
+                "Current password",
+                self.current_password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputCurrentPassword(input).into(),
+                Message::Submit.into(),
+            ),
+            password_input::<AppMessage>(
+                "New password",
+                self.new_password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputNewPassword(input).into(),
+                Message::Submit.into(),
+            ),
+            password_input::<AppMessage>(
+                "Confirm new password",
+                self.confirm_password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputConfirmPassword(input).into(),
+                Message::Submit.into(),
+            ),
+        ]
+        .spacing(15);
+
+        let can_submit = self.status == Status::Input
+            && !self.current_password.is_empty()
+            && !self.new_password.is_empty();
+        let submit_button =
+            button("Change password").on_press_maybe(can_submit.then(|| Message::Submit.into()));
+
+        let content = column![
+            close,
+            header,
+            info,
+            notification,
+            inputs,
+            widget::Space::with_height(Length::Fill),
+            submit_button
+        ]
+        .align_x(iced::Alignment::Center)
+        .spacing(15);
+
+        container(content)
+            .width(400)
+            .height(500)
+            .padding(10)
+            .style(styles::container::overlay_inner)
+            .into()
+    }
+}
@@ -0,0 +1,220 @@
+use deps::*;
+
+use std::path::PathBuf;
+
+use font_and_icons::{BOOTSTRAP_FONT, Bootstrap};
+use iced::{
+    Element, Length, Task,
+    widget::{self, button, column, container, text},
+};
+use types::{AppError, Notification, crypto::Password};
+use wallet::{Unlocked, Wallet};
+use zeroize::Zeroize;
+
+use crate::{
+    app::AppMessage,
+    components::{self, password_input::password_input},
+    styles,
+    unlocked::app_view,
+};
+
+use super::overlay;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputPassword(String),
+    InputBackupPassword(String),
+    InputConfirmPassword(String),
+    ToggleShowPasswords,
+    Submit,
+    Exported(Result<PathBuf, AppError>),
+}
+
+impl Into<AppMessage> for Message {
+    fn into(self) -> AppMessage {
+        AppMessage::AppView(app_view::Message::OverlayMessage(
+            overlay::Message::ExportBackupMessage(self),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    Input,
+    Exporting,
+    Exported,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportBackup {
+    notification: Notification,
+    password: Password,
+    backup_password: Password,
+    confirm_password: Password,
+    show_passwords: bool,
+    status: Status,
+}
+
+impl<'a> ExportBackup {
+    pub fn new() -> Self {
+        Self {
+            notification: Notification::None,
+            password: Password::new(),
+            backup_password: Password::new(),
+            confirm_password: Password::new(),
+            show_passwords: false,
+            status: Status::Input,
+        }
+    }
+
+    pub fn update(&mut self, message: Message, wallet: &mut Wallet<Unlocked>) -> Task<AppMessage> {
+        match message {
+            Message::InputPassword(input) => Self::input_password(&mut self.password, input),
+            Message::InputBackupPassword(input) => {
+                Self::input_password(&mut self.backup_password, input)
+            }
+            Message::InputConfirmPassword(input) => {
+                Self::input_password(&mut self.confirm_password, input)
+            }
+            Message::ToggleShowPasswords => self.show_passwords = !self.show_passwords,
+            Message::Submit => return self.submit(wallet),
+            Message::Exported(result) => return self.exported(result),
+        }
+        if self.status == Status::Input {
+            self.notification = Notification::None;
+        }
+        Task::none()
+    }
+
+    fn input_password(password: &mut Password, mut input: String) {
+        password.replace(&input);
+        input.zeroize();
+    }
+
+    fn submit(&mut self, wallet: &mut Wallet<Unlocked>) -> Task<AppMessage> {
+        if self.status == Status::Exporting {
+            return Task::none();
+        }
+        if self.backup_password.len() < Password::MIN_LEN {
+            self.notification = Notification::Info(format!(
+                "The backup password needs to be at least {} characters long",
+                Password::MIN_LEN
+            ));
+            return Task::none();
+        }
+        if self.backup_password.as_str() != self.confirm_password.as_str() {
+            self.notification = Notification::Info("Passwords do not match".to_string());
+            return Task::none();
+        }
+
+        self.status = Status::Exporting;
+        self.notification = Notification::Info("Encrypting the backup...".to_string());
+
+        let handle = wallet.export_backup(
+            std::mem::take(&mut self.password),
+            self.backup_password.clone(),
+        );
+        self.backup_password.clear();
+        self.confirm_password.clear();
+
+        Task::perform(handle, |result| {
+            let result = result
+                .map_err(|err| AppError::NonFatal(Notification::Warn(err.to_string())))
+                .and_then(|result| result);
+            Message::Exported(result).into()
+        })
+    }
+
+    fn exported(&mut self, result: Result<PathBuf, AppError>) -> Task<AppMessage> {
+        match result {
+            Ok(path) => {
+                self.status = Status::Exported;
+                self.notification =
+                    Notification::Success(format!("Backup saved to {}", path.display()));
+            }
+            Err(AppError::NonFatal(notification)) => {
+                self.status = Status::Input;
+                self.notification = notification;
+            }
+            Err(err) => return Task::perform(async {}, move |_| AppMessage::Error(err)),
+        }
+        Task::none()
+    }
+
+    pub fn view(&'a self) -> Element<'a, AppMessage> {
+        let close = button(text(Bootstrap::XLg).font(BOOTSTRAP_FONT).size(18))
+            .on_press_maybe(
+                (self.status != Status::Exporting).then(|| app_view::Message::CloseOverlay.into()),
+            )
+            .style(button::text);
+        let close = container(close)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Right);
+
+        let header = text("Export backup")
+            .size(16)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let info = text(
+            "The backup holds the recovery phrase, the accounts and the settings. \
+            Restoring it needs both the backup password and the wallet password",
+        )
+        .size(12);
+
+        let notification = components::notification::notification(&self.notification);
+
+        let inputs = column![
+            password_input::<AppMessage>(
+                "Wallet password",
+                self.password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputPassword(input).into(),
+                Message::Submit.into(),
+            ),
+            password_input::<AppMessage>(
+                "Backup password",
+                self.backup_password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputBackupPassword(input).into(),
+                Message::Submit.into(),
+            ),
+            password_input::<AppMessage>(
+                "Confirm backup password",
+                self.confirm_password.as_str(),
+                self.show_passwords,
+                Message::ToggleShowPasswords.into(),
+                |input| Message::InputConfirmPassword(input).into(),
+                Message::Submit.into(),
+            ),
+        ]
+        .spacing(15);
+
+        let can_submit = self.status != Status::Exporting
+            && !self.password.is_empty()
+            && !self.backup_password.is_empty();
+        let submit_button =
+            button("Export").on_press_maybe(can_submit.then(|| Message::Submit.into()));
+
+        let content = column![
+            close,
+            header,
+            info,
+            notification,
+            inputs,
+            widget::Space::with_height(Length::Fill),
+            submit_button
+        ]
+        .align_x(iced::Alignment::Center)
+        .spacing(15);
+
+        container(content)
+            .width(400)
+            .height(500)
+            .padding(10)
+            .style(styles::container::overlay_inner)
+            .into()
+    }
+}
@@ -1,3 +1,5 @@
 pub mod add_account;
+pub mod change_password;
+pub mod export_backup;
 pub mod overlay;
 pub mod receive;
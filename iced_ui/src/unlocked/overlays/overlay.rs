@@ -6,11 +6,16 @@ use wallet::{Unlocked, Wallet};
 
 use crate::{app::AppMessage, unlocked::app_view};
 
-use super::{add_account::AddAccount, receive::Receive};
+use super::{
+    add_account::AddAccount, change_password::ChangePassword, export_backup::ExportBackup,
+    receive::Receive,
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     AddAccountMessage(super::add_account::Message),
+    ChangePasswordMessage(super::change_password::Message),
+    ExportBackupMessage(super::export_backup::Message),
     ReceiveMessage(super::receive::Message),
 }
 
@@ -23,12 +28,16 @@ impl Into<AppMessage> for Message {
 #[derive(Debug, Clone)]
 pub enum SpawnOverlay {
     AddAccount,
+    ChangePassword,
+    ExportBackup,
     Receive(AccountAddress),
 }
 
 #[derive(Debug, Clone)]
 pub enum Overlay {
     AddAccount(AddAccount),
+    ChangePassword(ChangePassword),
+    ExportBackup(ExportBackup),
     Receive(Receive),
 }
 
@@ -41,6 +50,16 @@ impl<'a> Overlay {
                     task = add_account.update(message, wallet)
                 }
             }
+            Message::ChangePasswordMessage(message) => {
+                if let Self::ChangePassword(change_password) = self {
+                    task = change_password.update(message, wallet)
+                }
+            }
+            Message::ExportBackupMessage(message) => {
+                if let Self::ExportBackup(export_backup) = self {
+                    task = export_backup.update(message, wallet)
+                }
+            }
             Message::ReceiveMessage(message) => {
                 if let Self::Receive(receive) = self {
                     task = receive.update(message)
@@ -53,6 +72,8 @@ impl<'a> Overlay {
     pub fn view(&'a self, _wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
         match self {
             Self::AddAccount(add_account_view) => add_account_view.view(),
+            Self::ChangePassword(change_password) => change_password.view(),
+            Self::ExportBackup(export_backup) => export_backup.view(),
             Self::Receive(receive) => receive.view(),
         }
     }
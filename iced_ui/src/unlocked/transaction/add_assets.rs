@@ -1,6 +1,10 @@
 use deps::*;
 
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem,
+    str::FromStr,
+};
 
 use iced::{
     widget::{
@@ -13,6 +17,7 @@ use font_and_icons::{Bootstrap, BOOTSTRAP_FONT};
 use types::{
     address::{AccountAddress, Address, ResourceAddress},
     assets::FungibleAsset,
+    RadixDecimal,
 };
 use wallet::{Unlocked, Wallet};
 
@@ -30,6 +35,9 @@ pub enum Message {
     SelectAsset(ResourceAddress, String),
     UnselectAllTokens,
     UnselectAsset(ResourceAddress),
+    /// Select a single NFT by resource address, collection name and NFT id
+    SelectNFT(ResourceAddress, String, String),
+    UnselectNFT(ResourceAddress, String),
     SubmitAssets,
 }
 
@@ -54,7 +62,11 @@ pub struct AddAssets {
     pub recipient_index: usize,
     pub filter: String,
     pub selected: HashMap<ResourceAddress, (String, String)>,
+    /// The selected NFT ids and the collection name per resource
+    pub selected_nfts: HashMap<ResourceAddress, (String, BTreeSet<String>)>,
     pub select_all: bool,
+    /// XRD kept back by the max amount when the sending account also pays the fee
+    pub fee_reserve: Option<RadixDecimal>,
 }
 
 impl<'a> AddAssets {
@@ -62,6 +74,8 @@ impl<'a> AddAssets {
         from_account: AccountAddress,
         recipient_index: usize,
         selected: HashMap<ResourceAddress, (String, String)>,
+        selected_nfts: HashMap<ResourceAddress, (String, BTreeSet<String>)>,
+        fee_reserve: Option<RadixDecimal>,
     ) -> Self {
         Self {
             tab: AssetTab::Tokens,
@@ -69,7 +83,9 @@ impl<'a> AddAssets {
             recipient_index,
             filter: String::new(),
             selected,
+            selected_nfts,
             select_all: false,
+            fee_reserve,
         }
     }
 
@@ -97,6 +113,16 @@ impl<'a> AddAssets {
                 self.select_all = false
             }
             Message::UnselectAsset(asset_address) => self.remove_selected_asset(asset_address),
+            Message::SelectNFT(resource_address, name, nfid) => {
+                self.selected_nfts
+                    .entry(resource_address)
+                    .or_insert((name, BTreeSet::new()))
+                    .1
+                    .insert(nfid);
+            }
+            Message::UnselectNFT(resource_address, nfid) => {
+                self.remove_selected_nft(resource_address, nfid)
+            }
             Message::SubmitAssets => {
                 command = self.submit_selected_assets(recipients);
             }
@@ -130,7 +156,23 @@ impl<'a> AddAssets {
                 .and_then(|fungible| Some(fungible.amount.clone()))
                 .unwrap_or("0".to_string());
 
-            *amount = fungible_amount;
+            *amount = match self.fee_reserve {
+                Some(fee_reserve) if resource_address.is_xrd() => {
+                    Self::subtract_fee_reserve(&fungible_amount, fee_reserve)
+                }
+                _ => fungible_amount,
+            };
+        }
+    }
+
+    /// Keeps the fee reserve back from the balance, returns zero if the balance doesn't cover it
+    fn subtract_fee_reserve(balance: &str, fee_reserve: RadixDecimal) -> String {
+        let balance = RadixDecimal::from_str(balance).unwrap_or(RadixDecimal::ZERO);
+
+        if balance > fee_reserve {
+            (balance - fee_reserve).to_string()
+        } else {
+            "0".to_string()
         }
     }
 
@@ -175,6 +217,15 @@ impl<'a> AddAssets {
         self.selected.remove_entry(&asset_address);
     }
 
+    fn remove_selected_nft(&mut self, resource_address: ResourceAddress, nfid: String) {
+        if let Some((_, nfids)) = self.selected_nfts.get_mut(&resource_address) {
+            nfids.remove(&nfid);
+            if nfids.is_empty() {
+                self.selected_nfts.remove(&resource_address);
+            }
+        }
+    }
+
     fn submit_selected_assets(&mut self, recipients: &mut Vec<Recipient>) -> Task<AppMessage> {
         for (resource_address, (symbol, amount)) in self.selected.drain() {
             if let Some((_, old_amount)) = recipients[self.recipient_index]
@@ -188,6 +239,7 @@ impl<'a> AddAssets {
                     .insert(resource_address, (symbol, amount));
             }
         }
+        recipients[self.recipient_index].nfts = mem::take(&mut self.selected_nfts);
 
         Task::perform(async {}, |_| create_transaction::Message::OverView.into())
     }
@@ -242,9 +294,11 @@ impl<'a> AddAssets {
 
         let asset_tab = match self.tab {
             AssetTab::Tokens => self.tokens_tab(wallet, &mut amounts_within_limits),
-            AssetTab::NFTs => self.nfts_tab(wallet, &mut amounts_within_limits),
+            AssetTab::NFTs => self.nfts_tab(wallet),
         };
 
+        let nothing_selected = self.selected.is_empty() && self.selected_nfts.is_empty();
+
         let submit_button = button(
             text("Submit")
                 .size(16)
@@ -255,7 +309,7 @@ impl<'a> AddAssets {
         )
         .width(150)
         .height(40)
-        .on_press_maybe(if self.selected.is_empty() | !amounts_within_limits {
+        .on_press_maybe(if nothing_selected | !amounts_within_limits {
             None
         } else {
             Some(Message::SubmitAssets.into())
@@ -469,36 +523,15 @@ impl<'a> AddAssets {
             .height(Length::Fill)
     }
 
-    fn nfts_tab(
-        &self,
-        wallet: &'a Wallet<Unlocked>,
-        within_limits: &mut bool,
-    ) -> Container<'a, AppMessage> {
+    fn nfts_tab(&'a self, wallet: &'a Wallet<Unlocked>) -> Container<'a, AppMessage> {
         let headers: Element<'a, AppMessage> = {
-            let token_name = text("Token").size(12);
+            let collection_name = text("Collection").size(12);
 
             let space = widget::Space::new(Length::Fill, 1);
 
-            let balance = text("Available balance").size(12);
-
-            let amount = container(
-                button(text("Set max").size(12))
-                    .padding(0)
-                    .style(button::text)
-                    .on_press(Message::InputMaxSelected.into()),
-            )
-            .center_x(85)
-            .center_y(Length::Fill);
-
-            let selected = checkbox("", self.select_all).size(12).on_toggle(|select| {
-                if select {
-                    Message::SelectAllTokens.into()
-                } else {
-                    Message::UnselectAllTokens.into()
-                }
-            });
+            let selected = text("Selected").size(12);
 
-            let header_row = row![token_name, space, balance, amount, selected]
+            let header_row = row![collection_name, space, selected]
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .spacing(10)
@@ -515,7 +548,104 @@ impl<'a> AddAssets {
                 .into()
         };
 
-        let scrollable = widget::scrollable(column!().padding(Padding {
+        let non_fungibles = wallet.non_fungibles().get(&self.from_account);
+
+        let elements: Vec<Element<'a, AppMessage>> = match non_fungibles {
+            Some(non_fungibles) => non_fungibles
+                .iter()
+                .filter_map(|non_fungible| {
+                    let resource = wallet.resources().get(&non_fungible.resource_address)?;
+                    (resource.name.to_ascii_lowercase().contains(&self.filter)
+                        || resource.symbol.to_ascii_lowercase().contains(&self.filter)
+                        || resource.address.as_str().contains(&self.filter))
+                    .then_some((non_fungible, resource))
+                })
+                .map(|(non_fungible, resource)| {
+                    let selected_nfids = self
+                        .selected_nfts
+                        .get(&non_fungible.resource_address)
+                        .and_then(|(_, nfids)| Some(nfids));
+
+                    let icon: Element<'a, AppMessage> = wallet
+                        .resource_icons()
+                        .get(&non_fungible.resource_address)
+                        .and_then(|bytes| {
+                            Some(
+                                widget::image(Handle::from_bytes(bytes.clone()))
+                                    .width(40)
+                                    .height(40)
+                                    .into(),
+                            )
+                        })
+                        .unwrap_or(
+                            container(text(Bootstrap::Image).font(BOOTSTRAP_FONT).size(30))
+                                .center_x(40)
+                                .center_y(40)
+                                .into(),
+                        );
+
+                    let name = text(&resource.name).size(12);
+                    let space = widget::Space::new(Length::Fill, 1);
+                    let count = text(format!(
+                        "{}/{}",
+                        selected_nfids.map(|nfids| nfids.len()).unwrap_or(0),
+                        non_fungible.nfids.nr_of_nfts()
+                    ))
+                    .size(12);
+
+                    let collection = row![icon, name, space, count]
+                        .spacing(10)
+                        .align_y(iced::Alignment::Center)
+                        .width(Length::Fill)
+                        .padding(5);
+
+                    let nfts = non_fungible.nfids.iter().map(|nft| {
+                        let is_selected =
+                            selected_nfids.is_some_and(|nfids| nfids.contains(&nft.id));
+
+                        let checkbox =
+                            checkbox("", is_selected).size(12).on_toggle(move |select| {
+                                if select {
+                                    Message::SelectNFT(
+                                        resource.address.clone(),
+                                        resource.name.clone(),
+                                        nft.id.clone(),
+                                    )
+                                    .into()
+                                } else {
+                                    Message::UnselectNFT(resource.address.clone(), nft.id.clone())
+                                        .into()
+                                }
+                            });
+
+                        row![
+                            text(&nft.id).size(12),
+                            widget::Space::new(Length::Fill, 1),
+                            checkbox
+                        ]
+                        .spacing(10)
+                        .align_y(iced::Alignment::Center)
+                        .padding(Padding {
+                            left: 55.,
+                            right: 5.,
+                            top: 2.,
+                            bottom: 2.,
+                        })
+                        .into()
+                    });
+
+                    let column = column![collection, column(nfts), widget::Rule::horizontal(1)]
+                        .width(Length::Fill);
+
+                    container(column)
+                        .style(styles::container::base_layer_1)
+                        .into()
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let scrollable = widget::scrollable(column(elements).padding(Padding {
             right: 15.,
             ..Padding::ZERO
         }))
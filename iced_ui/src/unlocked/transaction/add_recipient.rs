@@ -1,7 +1,5 @@
 use deps::{iced::alignment::Horizontal, *};
 
-use std::str::FromStr;
-
 use iced::{
     Element, Length, Padding, Task,
     widget::{self, button, column, container, row, text},
@@ -66,7 +64,9 @@ impl<'a> AddRecipient {
     }
 
     fn recipient_input(&mut self, input: String, wallet: &'a mut Wallet<Unlocked>) {
-        if let Ok(account_address) = AccountAddress::from_str(input.as_str()) {
+        if let Ok(account_address) =
+            AccountAddress::from_str_on(wallet.settings().network, input.as_str())
+        {
             if let Some(address) = &self.from_account {
                 if &account_address != address {
                     self.chosen_account = Some(account_address)
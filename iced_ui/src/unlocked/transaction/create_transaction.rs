@@ -6,24 +6,33 @@ use deps::{
     *,
 };
 
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    mem,
+    str::FromStr,
+};
 
 use crate::{
     app::AppMessage,
-    components::{self, text_field::TextField},
+    common,
+    components::{self, password_input::password_input, text_field::TextField},
     styles,
     unlocked::app_view,
 };
 use font_and_icons::{BOOTSTRAP_FONT, Bootstrap};
+use handles::radix_dlt::gateway_requests::RadixGateway;
 use iced::{
     Alignment, Element, Length, Padding, Task,
     widget::{self, Container, button, container, image::Handle, row, text},
 };
 use types::{
-    Account, Decimal,
+    Account, AppError, Decimal, Fee, RadixDecimal, ResourceChange, Transaction, TransactionPreview,
+    Transfer, TransferNonFungibles, TransferRecipient, TransferResource,
     address::{AccountAddress, Address, ResourceAddress},
+    crypto::Password,
 };
 use wallet::{Unlocked, Wallet};
+use zeroize::Zeroize;
 
 use super::{
     add_assets::{self, AddAssets},
@@ -34,18 +43,33 @@ use super::{
 pub enum Message {
     OverView,
     SelectAccount(Account),
+    SelectFeePayer(Account),
     // UpdateTextMessage(Edit),
     RemoveRecipient(usize),
     UpdateResourceAmount(usize, ResourceAddress, String),
     SelectRecipient(usize),
-    AddRecipient,
+    /// Add an account to withdraw from besides the from account, chosen once for the transaction
+    AddSource(Account),
+    /// Remove a source account together with the recipients of its assets
+    RemoveSource(AccountAddress),
+    /// Add a recipient of assets from the source account, `None` for the from account
+    AddRecipient(Option<Account>),
     ChooseRecipientMessage(add_recipient::Message),
     ///Pass the index of the account to add assets for
     AddAssets(usize),
     AddAssetsMessage(add_assets::Message),
     TextFieldMessage(components::text_field::Message),
     RemoveAsset(usize, ResourceAddress),
+    RemoveNFT(usize, ResourceAddress, String),
     ToggleTextField,
+    ReviewTransaction,
+    PreviewReceived(Result<TransactionPreview, String>),
+    SignTransaction,
+    PasswordInput(String),
+    ToggleShowPassword,
+    SignAndSubmit,
+    TransactionSubmitted(Transaction),
+    SubmitFailed(String),
 }
 
 impl Into<AppMessage> for Message {
@@ -57,14 +81,27 @@ impl Into<AppMessage> for Message {
 #[derive(Debug, Clone)]
 pub struct Recipient {
     pub(crate) address: Option<AccountAddress>,
+    /// Withdraws from this source account of the transaction instead of the from account when set
+    pub(crate) source: Option<Account>,
     pub(crate) resources: HashMap<ResourceAddress, (String, String)>,
+    /// The collection name and the selected NFT ids per resource
+    pub(crate) nfts: HashMap<ResourceAddress, (String, BTreeSet<String>)>,
 }
 
 impl Recipient {
     pub fn new(address: Option<AccountAddress>) -> Self {
         Self {
             address,
+            source: None,
             resources: HashMap::new(),
+            nfts: HashMap::new(),
+        }
+    }
+
+    pub fn with_source(source: Option<Account>) -> Self {
+        Self {
+            source,
+            ..Self::new(None)
         }
     }
 }
@@ -74,15 +111,30 @@ pub enum View {
     Transaction,
     AddAssets(AddAssets),
     ChooseRecipient(AddRecipient),
+    /// Holds the preview once the gateway has responded
+    Review(Option<Result<TransactionPreview, String>>),
+    Sign,
 }
 
 #[derive(Debug)]
 pub struct CreateTransaction {
     pub(crate) from_account: Option<Account>,
+    /// Other accounts assets are withdrawn from, each recipient receives from one of these or the from account
+    pub(crate) sources: Vec<Account>,
+    /// Pays the fee instead of the sending account when set
+    pub(crate) fee_payer: Option<Account>,
+    pub(crate) fee_estimate: Option<RadixDecimal>,
+    pub(crate) fee_margin_percent: u8,
     pub(crate) resource_amounts: HashMap<ResourceAddress, Decimal>,
     pub(crate) recipients: Vec<Recipient>,
     pub(crate) text_field: Option<components::text_field::TextField>,
     pub(crate) view: View,
+    pub(crate) password: Password,
+    pub(crate) show_password: bool,
+    pub(crate) submitting: bool,
+    /// Set while the gateway can not be reached, transactions can not be previewed or submitted
+    pub(crate) offline: bool,
+    pub(crate) notification: String,
 }
 
 impl CreateTransaction {
@@ -92,21 +144,128 @@ impl CreateTransaction {
     ) -> Self {
         Self {
             from_account,
+            sources: Vec::new(),
+            fee_payer: None,
+            fee_estimate: None,
+            fee_margin_percent: Fee::DEFAULT_MARGIN_PERCENT,
             resource_amounts: account_resources.unwrap_or(HashMap::new()),
             recipients: vec![Recipient::new(None)],
             text_field: None,
             view: View::Transaction,
+            password: Password::new(),
+            show_password: false,
+            submitting: false,
+            offline: false,
+            notification: String::new(),
         }
     }
 
     pub fn from_recipient(address: AccountAddress) -> Self {
         Self {
             from_account: None,
+            sources: Vec::new(),
+            fee_payer: None,
+            fee_estimate: None,
+            fee_margin_percent: Fee::DEFAULT_MARGIN_PERCENT,
             resource_amounts: HashMap::new(),
             recipients: vec![Recipient::new(Some(address))],
             text_field: None,
             view: View::Transaction,
+            password: Password::new(),
+            show_password: false,
+            submitting: false,
+            offline: false,
+            notification: String::new(),
+        }
+    }
+
+    /// Creates the transfer from the selected accounts and recipients, returns `None` if
+    /// no account is selected, a recipient is missing an address, an amount is invalid or
+    /// an NFT is sent to more than one recipient
+    pub fn transfer(&self) -> Option<Transfer> {
+        let from_account = self.from_account.as_ref()?;
+        if self.duplicate_nft().is_some() {
+            return None;
+        }
+        let message = self
+            .text_field
+            .as_ref()
+            .map(|text_field| text_field.text().trim().to_string())
+            .filter(|message| !message.is_empty());
+
+        let mut transfer = Transfer::new(from_account.address.clone(), message);
+        transfer.fee = self.fee()?;
+
+        for recipient in &self.recipients {
+            let mut transfer_recipient = TransferRecipient::new(recipient.address.clone()?);
+            let source = recipient.source.as_ref().unwrap_or(from_account);
+
+            for (resource_address, (_, amount)) in &recipient.resources {
+                let amount = RadixDecimal::from_str(amount).ok()?;
+                if amount <= RadixDecimal::ZERO {
+                    return None;
+                }
+                transfer_recipient.resources.push(TransferResource::new(
+                    source.address.clone(),
+                    resource_address.clone(),
+                    amount,
+                ));
+            }
+
+            for (resource_address, (_, nfids)) in &recipient.nfts {
+                transfer_recipient
+                    .non_fungibles
+                    .push(TransferNonFungibles::new(
+                        source.address.clone(),
+                        resource_address.clone(),
+                        nfids.iter().cloned().collect(),
+                    ));
+            }
+
+            transfer.recipients.push(transfer_recipient);
+        }
+
+        if transfer.is_empty() {
+            return None;
         }
+
+        Some(transfer)
+    }
+
+    /// The collection name and id of an NFT selected for more than one recipient
+    fn duplicate_nft(&self) -> Option<(&String, &String)> {
+        let mut nfids = BTreeSet::new();
+
+        self.recipients
+            .iter()
+            .flat_map(|recipient| recipient.nfts.iter())
+            .flat_map(|(resource_address, (name, selected))| {
+                selected
+                    .iter()
+                    .map(move |nfid| (resource_address, name, nfid))
+            })
+            .find(|(resource_address, _, nfid)| !nfids.insert((*resource_address, *nfid)))
+            .map(|(_, name, nfid)| (name, nfid))
+    }
+
+    /// The account the assets of the recipient are withdrawn from
+    fn source(&self, recipient_index: usize) -> Option<&Account> {
+        self.recipients
+            .get(recipient_index)?
+            .source
+            .as_ref()
+            .or(self.from_account.as_ref())
+    }
+
+    /// The fee of the transfer, paid by the selected fee payer or else by the sending account
+    pub fn fee(&self) -> Option<Fee> {
+        let payer = self.fee_payer.as_ref().or(self.from_account.as_ref())?;
+
+        let mut fee = Fee::new(payer.address.clone());
+        fee.estimate = self.fee_estimate;
+        fee.margin_percent = self.fee_margin_percent;
+
+        Some(fee)
     }
 }
 
@@ -118,7 +277,8 @@ impl<'a> CreateTransaction {
     ) -> Task<AppMessage> {
         match message {
             Message::OverView => self.view = View::Transaction,
-            Message::SelectAccount(account) => self.from_account = Some(account),
+            Message::SelectAccount(account) => self.select_from_account(account),
+            Message::SelectFeePayer(account) => self.fee_payer = Some(account),
             Message::RemoveRecipient(recipient_index) => self.remove_recipient(recipient_index),
             Message::UpdateResourceAmount(account_index, resource, amount) => {
                 self.update_resource_amount(account_index, resource, amount)
@@ -130,9 +290,11 @@ impl<'a> CreateTransaction {
                     .and_then(|account| Some(account.address.clone()));
                 self.view = View::ChooseRecipient(AddRecipient::new(recipient_index, from_address))
             }
-            Message::AddRecipient => self.recipients.push(Recipient::new(None)),
+            Message::AddSource(account) => self.add_source(account),
+            Message::RemoveSource(address) => self.remove_source(&address),
+            Message::AddRecipient(source) => self.recipients.push(Recipient::with_source(source)),
             Message::AddAssets(recipient_index) => {
-                if let Some(account) = &self.from_account {
+                if let Some(account) = self.source(recipient_index) {
                     self.create_new_add_assets_view(recipient_index, account.address.clone())
                 }
             }
@@ -155,6 +317,15 @@ impl<'a> CreateTransaction {
                     .resources
                     .remove(&resource_address);
             }
+            Message::RemoveNFT(recipient_index, resource_address, nfid) => {
+                let nfts = &mut self.recipients[recipient_index].nfts;
+                if let Some((_, nfids)) = nfts.get_mut(&resource_address) {
+                    nfids.remove(&nfid);
+                    if nfids.is_empty() {
+                        nfts.remove(&resource_address);
+                    }
+                }
+            }
             Message::TextFieldMessage(message) => {
                 self.text_field
                     .as_mut()
@@ -166,14 +337,169 @@ impl<'a> CreateTransaction {
                     None => Some(TextField::new()),
                 }
             }
+            Message::ReviewTransaction => return self.review_transaction(wallet),
+            Message::PreviewReceived(preview) => {
+                if let View::Review(pending) = &mut self.view {
+                    if let Ok(preview) = &preview {
+                        self.fee_estimate = Some(preview.fee.total());
+                    }
+                    *pending = Some(preview)
+                }
+            }
+            Message::SignTransaction => {
+                if let View::Review(Some(Ok(preview))) = &self.view
+                    && preview.succeeded
+                {
+                    self.notification.clear();
+                    self.view = View::Sign
+                }
+            }
+            Message::PasswordInput(mut input) => {
+                self.password.replace(&input);
+                input.zeroize();
+            }
+            Message::ToggleShowPassword => self.show_password = !self.show_password,
+            Message::SignAndSubmit => return self.sign_and_submit(wallet),
+            Message::TransactionSubmitted(transaction) => {
+                *self = Self::new(None, None);
+                let intent_hash = transaction.transaction_address.clone();
+                wallet.add_pending_transaction(transaction);
+
+                return Task::perform(async {}, move |_| {
+                    common::Message::Notify(format!(
+                        "Transaction submitted: {}",
+                        intent_hash.truncate_long()
+                    ))
+                    .into()
+                });
+            }
+            Message::SubmitFailed(error) => {
+                self.submitting = false;
+                self.notification = error;
+            }
         }
 
         Task::none()
     }
 
+    fn review_transaction(&mut self, wallet: &'a mut Wallet<Unlocked>) -> Task<AppMessage> {
+        // The preview locks the default amount, the estimate is replaced by the preview result
+        self.fee_estimate = None;
+        self.fee_margin_percent = wallet.settings().fee_margin_percent();
+
+        let Some(transfer) = self.transfer() else {
+            return Task::none();
+        };
+        let network = wallet.settings().network;
+        let limits = wallet.settings().request_limits();
+        let signer_public_keys = wallet.signer_public_keys(&transfer);
+        self.view = View::Review(None);
+
+        Task::perform(
+            async move {
+                handles::radix_dlt::transfer::preview_transfer(
+                    RadixGateway::new(network, limits),
+                    network,
+                    &transfer,
+                    &signer_public_keys,
+                )
+                .await
+                .map_err(|err| err.to_string())
+            },
+            |preview| Message::PreviewReceived(preview).into(),
+        )
+    }
+
+    fn sign_and_submit(&mut self, wallet: &'a mut Wallet<Unlocked>) -> Task<AppMessage> {
+        // Submitting from the password field bypasses the disabled button
+        if self.submitting || self.offline {
+            return Task::none();
+        }
+        let Some(transfer) = self.transfer() else {
+            self.view = View::Transaction;
+            return Task::none();
+        };
+        let password = mem::take(&mut self.password);
+
+        match wallet.submit_transfer(transfer, password) {
+            Ok(handle) => {
+                self.submitting = true;
+                self.notification.clear();
+                Task::perform(handle, |result| match result {
+                    Ok(Ok(transaction)) => Message::TransactionSubmitted(transaction).into(),
+                    Ok(Err(err)) => Message::SubmitFailed(error_message(err)).into(),
+                    Err(join_error) => Message::SubmitFailed(join_error.to_string()).into(),
+                })
+            }
+            Err(err) => {
+                self.notification = error_message(err);
+                Task::none()
+            }
+        }
+    }
+
     fn create_new_add_assets_view(&mut self, recipient_index: usize, from_account: AccountAddress) {
         let selected = self.recipients[recipient_index].resources.clone();
-        self.view = View::AddAssets(AddAssets::new(from_account, recipient_index, selected))
+        let selected_nfts = self.recipients[recipient_index].nfts.clone();
+        let fee_reserve = self
+            .fee()
+            .filter(|fee| fee.payer == from_account)
+            .map(|fee| fee.lock_amount());
+
+        self.view = View::AddAssets(AddAssets::new(
+            from_account,
+            recipient_index,
+            selected,
+            selected_nfts,
+            fee_reserve,
+        ))
+    }
+
+    /// Recipients of a source that becomes the from account now receive from the from account
+    fn select_from_account(&mut self, account: Account) {
+        self.sources
+            .retain(|source| source.address != account.address);
+        for recipient in &mut self.recipients {
+            if recipient
+                .source
+                .as_ref()
+                .is_some_and(|source| source.address == account.address)
+            {
+                recipient.source = None;
+            }
+        }
+        self.from_account = Some(account);
+    }
+
+    /// Adds the source with an empty recipient, an account is only added once
+    fn add_source(&mut self, account: Account) {
+        let is_from_account = self
+            .from_account
+            .as_ref()
+            .is_some_and(|from_account| from_account.address == account.address);
+        let is_source = self
+            .sources
+            .iter()
+            .any(|source| source.address == account.address);
+
+        if !is_from_account && !is_source {
+            self.recipients
+                .push(Recipient::with_source(Some(account.clone())));
+            self.sources.push(account);
+        }
+    }
+
+    fn remove_source(&mut self, address: &AccountAddress) {
+        self.sources.retain(|source| &source.address != address);
+        self.recipients.retain(|recipient| {
+            recipient
+                .source
+                .as_ref()
+                .is_none_or(|source| &source.address != address)
+        });
+        if self.recipients.is_empty() {
+            self.recipients.push(Recipient::new(None));
+        }
     }
 
     fn remove_recipient(&mut self, index: usize) {
@@ -205,6 +531,8 @@ impl<'a> CreateTransaction {
             View::Transaction => self.create_transaction(wallet),
             View::ChooseRecipient(choose_recipient) => choose_recipient.view(wallet),
             View::AddAssets(choose_assets) => choose_assets.view(wallet),
+            View::Review(preview) => self.review(preview.as_ref(), wallet),
+            View::Sign => self.sign_transaction(wallet),
         }
     }
 
@@ -219,20 +547,36 @@ impl<'a> CreateTransaction {
 
         let header = Self::header("Transaction");
 
-        let from_account_field = self.from_account_field(accounts);
+        let fee_payer_field = self.fee_payer_field(accounts.clone());
+
+        let recipient_field = self.recipients(accounts.clone(), wallet);
 
-        let recipient_field = self.recipients(wallet);
+        let from_account_field = self.from_account_field(accounts);
 
         let message_field = self.message();
 
-        let fields = widget::column![header, from_account_field, recipient_field, message_field]
-            .spacing(30)
-            .height(Length::Shrink)
-            .padding(Padding {
-                left: 10.,
-                right: 15.,
-                ..Padding::ZERO
-            });
+        let mut fields = widget::column![
+            header,
+            from_account_field,
+            recipient_field,
+            message_field,
+            fee_payer_field
+        ]
+        .spacing(30)
+        .height(Length::Shrink)
+        .padding(Padding {
+            left: 10.,
+            right: 15.,
+            ..Padding::ZERO
+        });
+
+        if let Some((name, nfid)) = self.duplicate_nft() {
+            fields = fields.push(
+                text(format!("{name} {nfid} is sent to more than one recipient"))
+                    .size(12)
+                    .style(styles::text::warning),
+            );
+        }
 
         let scrollable =
             widget::scrollable(fields).style(styles::scrollable::vertical_scrollable_secondary);
@@ -251,7 +595,12 @@ impl<'a> CreateTransaction {
             button(text("Create transaction").width(Length::Fill).center())
                 .width(Length::Fill)
                 .style(styles::button::primary)
-                .height(50),
+                .height(50)
+                .on_press_maybe(
+                    self.transfer()
+                        .filter(|_| !self.offline)
+                        .and_then(|_| Some(Message::ReviewTransaction.into())),
+                ),
         )
         .padding(Padding {
             left: 15.,
@@ -262,6 +611,307 @@ impl<'a> CreateTransaction {
         column![page_top, create_transaction].into()
     }
 
+    fn review(
+        &'a self,
+        preview: Option<&'a Result<TransactionPreview, String>>,
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Element<'a, AppMessage> {
+        let header = Self::header("Review transaction");
+
+        let content: Element<'a, AppMessage> = match preview {
+            None => container(text("Previewing transaction...").style(styles::text::muted))
+                .center_x(Length::Fill)
+                .padding(20)
+                .into(),
+            Some(Err(error)) => container(
+                text(format!("Unable to preview transaction: {error}"))
+                    .size(12)
+                    .style(styles::text::error),
+            )
+            .padding(20)
+            .into(),
+            Some(Ok(preview)) => {
+                let status: Element<'a, AppMessage> = if preview.succeeded {
+                    text("The transaction is expected to succeed")
+                        .size(12)
+                        .style(styles::text::muted)
+                        .into()
+                } else {
+                    text(format!(
+                        "The transaction is expected to fail: {}",
+                        preview.error_message.as_deref().unwrap_or("unknown reason")
+                    ))
+                    .size(12)
+                    .style(styles::text::error)
+                    .into()
+                };
+
+                let guaranteed = Self::balance_changes(
+                    "Guaranteed balance changes",
+                    &preview.guaranteed_changes,
+                    wallet,
+                );
+                let estimated = Self::balance_changes(
+                    "Estimated balance changes",
+                    &preview.estimated_changes,
+                    wallet,
+                );
+
+                let fee = &preview.fee;
+                let fee_rows = [
+                    ("Execution", fee.execution),
+                    ("Finalization", fee.finalization),
+                    ("Storage", fee.storage),
+                    ("Royalties", fee.royalty),
+                    ("Tip", fee.tipping),
+                    ("Total", fee.total()),
+                ]
+                .into_iter()
+                .map(|(label, amount)| {
+                    row![
+                        text(label).size(12),
+                        Space::new(Length::Fill, 1),
+                        text(format!("{amount} XRD")).size(12)
+                    ]
+                    .into()
+                });
+                let fee_payer = self
+                    .fee()
+                    .map(|fee| {
+                        let payer = wallet
+                            .accounts()
+                            .get(&fee.payer)
+                            .and_then(|account| Some(account.name.clone()))
+                            .unwrap_or(fee.payer.truncate_long());
+
+                        format!(
+                            "Paid by {payer}, {} XRD is locked including a {}% margin",
+                            fee.lock_amount(),
+                            fee.margin_percent
+                        )
+                    })
+                    .unwrap_or_default();
+                let fee_payer = text(fee_payer).size(12).style(styles::text::muted);
+
+                let fees = container(
+                    column![
+                        Self::field_label("Network fee"),
+                        column(fee_rows).spacing(5),
+                        fee_payer
+                    ]
+                    .spacing(5),
+                )
+                .padding(10)
+                .width(Length::Fill)
+                .style(styles::container::weak_layer_2_rounded_with_shadow);
+
+                column![status, guaranteed, estimated, fees]
+                    .spacing(20)
+                    .into()
+            }
+        };
+
+        let fields = column![header, content].spacing(20).padding(Padding {
+            left: 10.,
+            right: 15.,
+            ..Padding::ZERO
+        });
+
+        let scrollable = widget::scrollable(fields)
+            .height(Length::Fill)
+            .style(styles::scrollable::vertical_scrollable_secondary);
+
+        let back = button(text("Back").center())
+            .width(100)
+            .style(styles::button::base_layer_2_rounded_with_shadow)
+            .on_press(Message::OverView.into());
+
+        let can_continue = matches!(preview, Some(Ok(preview)) if preview.succeeded);
+        let continue_button = button(text("Continue").width(Length::Fill).center())
+            .width(Length::Fill)
+            .height(50)
+            .style(styles::button::primary)
+            .on_press_maybe(can_continue.then_some(Message::SignTransaction.into()));
+
+        let buttons = row![back, continue_button]
+            .spacing(20)
+            .align_y(Alignment::Center)
+            .padding(Padding {
+                left: 15.,
+                right: 20.,
+                ..Padding::ZERO
+            });
+
+        column![scrollable, buttons].spacing(10).into()
+    }
+
+    fn balance_changes(
+        label: &'a str,
+        changes: &[ResourceChange],
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Container<'a, AppMessage> {
+        let accounts = ResourceChange::per_account(changes)
+            .into_iter()
+            .map(|(account_address, resources)| {
+                let account_name = wallet
+                    .accounts()
+                    .get(account_address)
+                    .and_then(|account| Some(account.name.clone()))
+                    .unwrap_or(account_address.truncate_long());
+
+                let resources = resources.into_iter().map(|(resource_address, amount)| {
+                    let symbol = wallet
+                        .resources()
+                        .get(resource_address)
+                        .and_then(|resource| Some(resource.symbol.clone()))
+                        .unwrap_or(resource_address.truncate());
+
+                    row![
+                        text(symbol).size(12),
+                        Space::new(Length::Fill, 1),
+                        text(amount.to_string()).size(12)
+                    ]
+                    .into()
+                });
+
+                column![text(account_name).size(14), column(resources).spacing(5)]
+                    .spacing(5)
+                    .into()
+            });
+
+        container(column![Self::field_label(label), column(accounts).spacing(10)].spacing(5))
+            .padding(10)
+            .width(Length::Fill)
+            .style(styles::container::weak_layer_2_rounded_with_shadow)
+    }
+
+    fn sign_transaction(&'a self, wallet: &'a Wallet<Unlocked>) -> Element<'a, AppMessage> {
+        let header = Self::header("Sign transaction");
+
+        let from = self
+            .from_account
+            .as_ref()
+            .and_then(|account| Some(format!("From {}", account.name)))
+            .unwrap_or_default();
+        let from = text(from).size(14);
+
+        let summary = self.recipients.iter().filter_map(|recipient| {
+            let address = recipient.address.as_ref()?;
+            let mut resources = column![text(address.truncate_long()).size(14)].spacing(5);
+
+            if let Some(source) = &recipient.source {
+                resources = resources.push(
+                    text(format!("From {}", source.name))
+                        .size(12)
+                        .style(styles::text::muted),
+                );
+            }
+
+            for (resource_address, (symbol, amount)) in recipient.resources.iter() {
+                let symbol = wallet
+                    .resources()
+                    .get(resource_address)
+                    .and_then(|resource| Some(resource.symbol.as_str()))
+                    .unwrap_or(symbol.as_str());
+
+                resources = resources.push(
+                    row![
+                        Self::resource_text_field(symbol),
+                        Space::new(Length::Fill, 1),
+                        Self::resource_text_field(amount)
+                    ]
+                    .padding(Padding {
+                        left: 10.,
+                        ..Padding::ZERO
+                    }),
+                );
+            }
+
+            for (name, nfids) in recipient.nfts.values() {
+                for nfid in nfids {
+                    resources = resources.push(
+                        row![
+                            Self::resource_text_field(name),
+                            Space::new(Length::Fill, 1),
+                            Self::resource_text_field(nfid)
+                        ]
+                        .padding(Padding {
+                            left: 10.,
+                            ..Padding::ZERO
+                        }),
+                    );
+                }
+            }
+
+            Some(
+                container(resources)
+                    .padding(10)
+                    .width(Length::Fill)
+                    .style(styles::container::weak_layer_2_rounded_with_shadow)
+                    .into(),
+            )
+        });
+        let summary = column(summary).spacing(10);
+
+        let password = password_input(
+            "Enter password",
+            self.password.as_str(),
+            self.show_password,
+            Message::ToggleShowPassword.into(),
+            |input| Message::PasswordInput(input).into(),
+            Message::SignAndSubmit.into(),
+        );
+
+        let notification = text(&self.notification)
+            .size(12)
+            .style(styles::text::warning);
+
+        let fields = widget::column![header, from, summary, password, notification]
+            .spacing(20)
+            .padding(Padding {
+                left: 10.,
+                right: 15.,
+                ..Padding::ZERO
+            });
+
+        let scrollable = widget::scrollable(fields)
+            .height(Length::Fill)
+            .style(styles::scrollable::vertical_scrollable_secondary);
+
+        let back = button(text("Back").center())
+            .width(100)
+            .style(styles::button::base_layer_2_rounded_with_shadow)
+            .on_press_maybe((!self.submitting).then_some(Message::OverView.into()));
+
+        let submit = button(
+            text(if self.submitting {
+                "Submitting..."
+            } else {
+                "Sign and submit"
+            })
+            .width(Length::Fill)
+            .center(),
+        )
+        .width(Length::Fill)
+        .height(50)
+        .style(styles::button::primary)
+        .on_press_maybe(
+            (!self.submitting && !self.offline && !self.password.is_empty())
+                .then_some(Message::SignAndSubmit.into()),
+        );
+
+        let buttons = row![back, submit]
+            .spacing(20)
+            .align_y(Alignment::Center)
+            .padding(Padding {
+                left: 15.,
+                right: 20.,
+                ..Padding::ZERO
+            });
+
+        column![scrollable, buttons].spacing(10).into()
+    }
+
     fn message(&'a self) -> Container<'a, AppMessage> {
         let label = Self::field_label("Message");
 
@@ -324,13 +974,111 @@ impl<'a> CreateTransaction {
             .height(Length::Shrink)
     }
 
-    fn recipients(&'a self, wallet: &'a Wallet<Unlocked>) -> Container<'a, AppMessage> {
-        let label = Self::field_label("To");
+    fn fee_payer_field(&'a self, accounts: Vec<&'a Account>) -> Container<'a, AppMessage> {
+        let label = Self::field_label("Fee payer");
+
+        let selected = self.fee_payer.as_ref().or(self.from_account.as_ref());
 
+        let picklist = widget::pick_list(accounts, selected, |account| {
+            Message::SelectFeePayer(account.clone()).into()
+        })
+        .placeholder("Select account")
+        .text_line_height(2.)
+        .text_size(14)
+        .width(Length::Fill)
+        .menu_style(styles::menu::primary)
+        .style(styles::pick_list::from_account)
+        .padding(10);
+
+        let lock_amount = self
+            .fee()
+            .map(|fee| {
+                format!(
+                    "Up to {} XRD is locked to pay the network fee",
+                    fee.lock_amount()
+                )
+            })
+            .unwrap_or_default();
+        let lock_amount = text(lock_amount).size(12).style(styles::text::muted);
+
+        let col = widget::column![label, picklist, lock_amount]
+            .width(Length::Fill)
+            .height(Length::Shrink)
+            .align_x(Alignment::Start)
+            .spacing(5);
+
+        widget::container(col)
+            .width(Length::Fill)
+            .height(Length::Shrink)
+    }
+
+    /// The recipients are grouped by the account they receive from, the from account first
+    /// and then each added source account
+    fn recipients(
+        &'a self,
+        accounts: Vec<&'a Account>,
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Container<'a, AppMessage> {
+        let from_account = self.source_recipients(None, wallet);
+
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| self.source_recipients(Some(source), wallet));
+
+        let available = accounts
+            .into_iter()
+            .filter(|account| {
+                self.from_account
+                    .as_ref()
+                    .is_none_or(|from_account| from_account.address != account.address)
+                    && !self
+                        .sources
+                        .iter()
+                        .any(|source| source.address == account.address)
+            })
+            .collect::<Vec<&Account>>();
+
+        let add_source = widget::pick_list(available, None::<&Account>, |account| {
+            Message::AddSource(account.clone()).into()
+        })
+        .placeholder("Also send from another account")
+        .text_size(12)
+        .width(Length::Fill)
+        .menu_style(styles::menu::primary)
+        .style(styles::pick_list::from_account)
+        .padding(5);
+
+        container(column![from_account, column(sources).spacing(30), add_source].spacing(30))
+    }
+
+    /// The recipients of one source account, `None` for the from account
+    fn source_recipients(
+        &'a self,
+        source: Option<&'a Account>,
+        wallet: &'a Wallet<Unlocked>,
+    ) -> Element<'a, AppMessage> {
+        let label: Element<'a, AppMessage> = match source {
+            None => Self::field_label("To").into(),
+            Some(source) => row![
+                Self::field_label_owned(format!("From {} to", source.name)),
+                button(text(Bootstrap::XLg).font(BOOTSTRAP_FONT).line_height(1.))
+                    .padding(0)
+                    .style(styles::button::base_layer_2_rounded_with_shadow)
+                    .on_press(Message::RemoveSource(source.address.clone()).into())
+            ]
+            .align_y(Alignment::Center)
+            .into(),
+        };
+
+        let source_address = source.map(|source| &source.address);
         let recipients = self
             .recipients
             .iter()
             .enumerate()
+            .filter(|(_, recipient)| {
+                recipient.source.as_ref().map(|source| &source.address) == source_address
+            })
             .map(|(recipient_index, recipient)| self.recipient(recipient_index, recipient, wallet));
 
         let recipients = column(recipients).spacing(20);
@@ -342,11 +1090,13 @@ impl<'a> CreateTransaction {
                 .width(Length::FillPortion(6))
                 .height(Length::Shrink)
                 .style(styles::button::base_layer_2_rounded_with_shadow)
-                .on_press(Message::AddRecipient.into()),
+                .on_press(Message::AddRecipient(source.cloned()).into()),
             Space::new(Length::FillPortion(2), 1)
         ];
 
-        container(column![label, recipients, Space::new(1, 30), add_recipient].spacing(5))
+        column![label, recipients, Space::new(1, 30), add_recipient]
+            .spacing(5)
+            .into()
     }
 
     fn resource_text_field(str: &'a str) -> widget::Text<'a> {
@@ -368,8 +1118,16 @@ impl<'a> CreateTransaction {
             .style(styles::text::muted)
     }
 
+    fn field_label_owned(input: String) -> text::Text<'a> {
+        text(input)
+            .align_x(iced::alignment::Horizontal::Left)
+            .align_y(iced::alignment::Vertical::Center)
+            .width(Length::Fill)
+            .style(styles::text::muted)
+    }
+
     fn recipient(
-        &self,
+        &'a self,
         recipient_index: usize,
         recipient: &'a Recipient,
         wallet: &'a Wallet<Unlocked>,
@@ -392,8 +1150,7 @@ impl<'a> CreateTransaction {
         })
         .style(styles::button::base_layer_2_rounded_with_shadow)
         .on_press_maybe(
-            self.from_account
-                .as_ref()
+            self.source(recipient_index)
                 .and_then(|_| Some(Message::AddAssets(recipient_index).into())),
         );
 
@@ -505,6 +1262,37 @@ impl<'a> CreateTransaction {
             assets.push(resource_row.into());
         }
 
+        for (resource_address, (name, nfids)) in recipient.nfts.iter() {
+            for nfid in nfids {
+                let remove_nft = widget::button(
+                    text(Bootstrap::XLg)
+                        .font(BOOTSTRAP_FONT)
+                        .size(15)
+                        .line_height(1.),
+                )
+                .padding(0)
+                .style(button::text)
+                .on_press(
+                    Message::RemoveNFT(recipient_index, resource_address.clone(), nfid.clone())
+                        .into(),
+                );
+
+                let nft_row = row![
+                    Self::resource_text_field(name),
+                    widget::Space::new(Length::Fill, 1),
+                    Self::resource_text_field(nfid),
+                    remove_nft
+                ]
+                .spacing(10)
+                .padding(5)
+                .align_y(Alignment::Center)
+                .width(Length::Fill);
+
+                assets.push(Rule::horizontal(1).into());
+                assets.push(nft_row.into());
+            }
+        }
+
         if assets.len() > 0 {
             assets.push(widget::Rule::horizontal(1).into());
         }
@@ -514,3 +1302,10 @@ impl<'a> CreateTransaction {
             .width(Length::Fill)
     }
 }
+
+fn error_message(err: AppError) -> String {
+    match err {
+        AppError::NonFatal(notification) => notification.message().to_string(),
+        err => err.to_string(),
+    }
+}
@@ -0,0 +1,200 @@
+//! A line chart of a value over time, drawn on a canvas.
+use deps::*;
+
+use iced::{
+    Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector, mouse,
+    widget::canvas::{self, Frame, Geometry, Path, Program, Stroke, Text},
+};
+
+/// Space left of the plot for the value labels
+const LABEL_WIDTH: f32 = 80.;
+/// Space below the plot for the time labels
+const LABEL_HEIGHT: f32 = 20.;
+const PADDING: f32 = 10.;
+const TEXT_SIZE: f32 = 12.;
+
+/// A line chart of `(x, y)` points, where `x` is usually a unix timestamp.
+///
+/// The value of a point is held until the next point, so the line is drawn as steps.
+///
+/// # Example
+/// ```ignore
+/// let chart = LineChart::new(vec![(0., 1.), (60., 2.5)])
+///     .format_x(|x| format!("{x}s"))
+///     .format_y(|y| format!("{y:.2}"))
+///     .height(200);
+/// ```
+pub struct LineChart<'a> {
+    points: Vec<(f64, f64)>,
+    format_x: Box<dyn Fn(f64) -> String + 'a>,
+    format_y: Box<dyn Fn(f64) -> String + 'a>,
+    width: Length,
+    height: Length,
+}
+
+impl<'a> LineChart<'a> {
+    /// The points are sorted by `x`
+    pub fn new(mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self {
+            points,
+            format_x: Box::new(|x| x.to_string()),
+            format_y: Box::new(|y| y.to_string()),
+            width: Length::Fill,
+            height: Length::Fixed(200.),
+        }
+    }
+
+    /// Formats the labels of the first and last `x`
+    pub fn format_x(mut self, format: impl Fn(f64) -> String + 'a) -> Self {
+        self.format_x = Box::new(format);
+        self
+    }
+
+    /// Formats the labels of the lowest and highest `y`
+    pub fn format_y(mut self, format: impl Fn(f64) -> String + 'a) -> Self {
+        self.format_y = Box::new(format);
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.height = height.into();
+        self
+    }
+
+    /// The lowest and highest value of the points, widened if they are the same
+    fn range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+        let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        if min < max {
+            (min, max)
+        } else {
+            (min - 1., max + 1.)
+        }
+    }
+
+    fn label(&self, content: String, position: Point, color: Color) -> Text {
+        Text {
+            content,
+            position,
+            color,
+            size: TEXT_SIZE.into(),
+            ..Text::default()
+        }
+    }
+}
+
+impl<'a, Message> Program<Message> for LineChart<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+        let text_color = palette.background.base.text;
+
+        if self.points.is_empty() {
+            frame.fill_text(self.label(
+                "No history yet".to_string(),
+                Point::new(PADDING, PADDING),
+                text_color,
+            ));
+            return vec![frame.into_geometry()];
+        }
+
+        let plot = Rectangle::new(
+            Point::new(LABEL_WIDTH, PADDING),
+            Size::new(
+                (bounds.width - LABEL_WIDTH - PADDING).max(1.),
+                (bounds.height - LABEL_HEIGHT - PADDING).max(1.),
+            ),
+        );
+        let (min_x, max_x) = Self::range(self.points.iter().map(|(x, _)| *x));
+        let (min_y, max_y) = Self::range(self.points.iter().map(|(_, y)| *y));
+
+        let to_point = |x: f64, y: f64| {
+            Point::new(
+                plot.x + ((x - min_x) / (max_x - min_x)) as f32 * plot.width,
+                plot.y + plot.height - ((y - min_y) / (max_y - min_y)) as f32 * plot.height,
+            )
+        };
+
+        let axes = Path::new(|builder| {
+            builder.move_to(Point::new(plot.x, plot.y));
+            builder.line_to(Point::new(plot.x, plot.y + plot.height));
+            builder.line_to(Point::new(plot.x + plot.width, plot.y + plot.height));
+        });
+        frame.stroke(
+            &axes,
+            Stroke::default()
+                .with_color(palette.background.strong.color)
+                .with_width(1.),
+        );
+
+        let line = Path::new(|builder| {
+            let mut points = self.points.iter();
+            let Some((x, y)) = points.next() else {
+                return;
+            };
+            let mut previous_y = *y;
+            builder.move_to(to_point(*x, *y));
+            for (x, y) in points {
+                builder.line_to(to_point(*x, previous_y));
+                builder.line_to(to_point(*x, *y));
+                previous_y = *y;
+            }
+        });
+        frame.stroke(
+            &line,
+            Stroke::default()
+                .with_color(palette.primary.base.color)
+                .with_width(2.),
+        );
+
+        let y_labels = [(max_y, plot.y), (min_y, plot.y + plot.height - TEXT_SIZE)];
+        for (value, y) in y_labels {
+            frame.fill_text(self.label((self.format_y)(value), Point::new(0., y), text_color));
+        }
+
+        frame.fill_text(self.label(
+            (self.format_x)(self.points[0].0),
+            to_point(self.points[0].0, min_y) + Vector::new(0., 4.),
+            text_color,
+        ));
+        if self.points.len() > 1 {
+            let last = (self.format_x)(max_x);
+            let label_y = plot.y + plot.height + 4.;
+            // Roughly the width of the label, so it ends at the right edge of the plot
+            let width = last.chars().count() as f32 * TEXT_SIZE * 0.6;
+            frame.fill_text(self.label(
+                last,
+                Point::new(plot.x + plot.width - width, label_y),
+                text_color,
+            ));
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl<'a, Message: 'a> From<LineChart<'a>> for Element<'a, Message> {
+    fn from(chart: LineChart<'a>) -> Self {
+        let (width, height) = (chart.width, chart.height);
+        canvas::Canvas::new(chart)
+            .width(width)
+            .height(height)
+            .into()
+    }
+}
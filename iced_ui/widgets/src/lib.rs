@@ -1,3 +1,5 @@
+pub mod chart;
 pub mod modal;
 
+pub use chart::LineChart;
 pub use modal::Modal;
@@ -26,6 +26,7 @@ pub fn run() -> Result<(), deps::iced::Error> {
     #[cfg(not(feature = "reload"))]
     application(App::new, App::update, App::view)
         .title(types::consts::APPLICATION_NAME)
+        .subscription(App::subscription)
         .settings(settings)
         .theme(App::theme)
         .style(App::style)
@@ -40,6 +41,7 @@ pub fn run() -> Result<(), deps::iced::Error> {
         App::inner_view,
     )
     .title(types::consts::APPLICATION_NAME)
+    .subscription(App::inner_subscription)
     .settings(settings)
     .window(window_settings)
     .theme(App::theme)